@@ -11,9 +11,10 @@ use core::fmt;
 
 use clap::{Parser, ValueEnum};
 use gpt_common::{
-    and_reduce_gpt_input,
+    and_reduce_gpt_file, check_gpt_file,
+    diagnostic::Diagnostic,
     dto::NTupleSingleInterval,
-    generate_tests_for_gpt_input,
+    formatter::format_source,
     graph_reduction::{
         create_graph,
         least_losing_components::run_least_losing_components,
@@ -21,6 +22,9 @@ use gpt_common::{
         least_losing_nodes_reachable::run_least_losing_nodes_reachable,
         monke::run_monke,
     },
+    loader::FsFileProvider,
+    number::Num,
+    prelude::GPTError,
 };
 use itertools::Itertools;
 
@@ -36,6 +40,7 @@ struct Cli {
 enum Command {
     Run(Run),
     AndReduce(AndReduce),
+    Fmt(Fmt),
 }
 
 /// Read the input GPT file and generate test cases
@@ -53,6 +58,10 @@ struct Run {
     #[arg(short, long, value_enum, default_value_t = Algo::Monke)]
     algo: Algo,
 
+    /// Also generate test cases with values outside of the declared domains of the variables
+    #[arg(long)]
+    robustness: bool,
+
     /// Input GPT file path
     file_path: String,
 }
@@ -87,6 +96,18 @@ struct AndReduce {
     file_path: String,
 }
 
+/// Format GPT files in the canonical style
+#[derive(Parser, Debug)]
+struct Fmt {
+    /// Don't write the files, only fail if any of them isn't formatted
+    #[arg(long)]
+    check: bool,
+
+    /// Input GPT file paths
+    #[arg(required = true)]
+    file_paths: Vec<String>,
+}
+
 impl fmt::Display for Algo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -104,7 +125,11 @@ impl fmt::Display for Algo {
     }
 }
 
-fn print_typst_format(test_cases: &[NTupleSingleInterval]) {
+/// The numbers are shown with the decimals of the precision of their variable,
+/// and the ones at a named constant of a condition with the name of the constant, like `"LIMIT" = 200 "(ON)"`.
+///
+/// The columns are the values, the expected outcomes, the requirements, the template instances and the bindings.
+fn print_typst_format(test_cases: &[NTupleSingleInterval], precisions: &[(String, Num)]) {
     for test_case in test_cases.iter() {
         let middle = test_case
             .iter()
@@ -114,47 +139,115 @@ fn print_typst_format(test_cases: &[NTupleSingleInterval]) {
                     "{k}: ${}$",
                     match v {
                         gpt_common::dto::Output::MissingVariable => "*".to_owned(),
+                        // `null` can't be an enum member, so it isn't mistaken for one
+                        gpt_common::dto::Output::Null => "\"null\"".to_owned(),
                         gpt_common::dto::Output::Bool(x) => format!("{x}"),
-                        gpt_common::dto::Output::Interval(x) => format!("{x}"),
+                        gpt_common::dto::Output::Interval(x) => {
+                            let precision = precisions
+                                .iter()
+                                .find(|(name, _)| name == k)
+                                .map(|(_, precision)| *precision);
+                            // A test case merged from several branches can be the ON point of one and the OFF point of another
+                            let constants = test_case
+                                .constants
+                                .iter()
+                                .filter(|constant| constant.var_name == *k)
+                                .collect::<Vec<_>>();
+
+                            match (constants.is_empty(), precision) {
+                                (true, Some(precision)) => {
+                                    x.display_with_precision(precision).to_string()
+                                }
+                                (true, None) => format!("{x}"),
+                                // The values at constants are single points
+                                (false, _) => format!(
+                                    "{} = {} \"({})\"",
+                                    constants
+                                        .iter()
+                                        .map(|constant| format!("\"{}\"", constant.name))
+                                        .unique()
+                                        .join(" = "),
+                                    precision.map_or_else(
+                                        || x.lo.to_string(),
+                                        |precision| x.lo.format_with_precision(precision)
+                                    ),
+                                    constants
+                                        .iter()
+                                        .map(|constant| constant.point())
+                                        .unique()
+                                        .join(", ")
+                                ),
+                            }
+                        }
+                        gpt_common::dto::Output::Enum(x) =>
+                            x.iter().map(|member| format!("\"{member}\"")).join(" | "),
+                        gpt_common::dto::Output::Text { .. } =>
+                            gpt_common::test_value_generator::generate_test_value(v, false),
                     }
                 )
             })
             .join(", ")
             .replace("Inf", "infinity");
 
-        println!("[M], [{middle}], [],")
+        println!(
+            "[M], [{middle}], [{}], [{}], [{}], [{}],",
+            test_case.expected.join(", "),
+            test_case.requirements.join(", "),
+            test_case.instances.join(", "),
+            test_case.bindings.join(", ")
+        )
     }
 }
 
 fn show(
     test_cases: &[NTupleSingleInterval],
     show_format: ShowFormat,
+    precisions: &[(String, Num)],
 ) -> Result<(), Box<dyn std::error::Error>> {
     match show_format {
         ShowFormat::Json => {
+            let test_cases = test_cases
+                .iter()
+                .map(|test_case| test_case.with_precisions(precisions))
+                .collect::<Vec<_>>();
             let test_cases_json = serde_json::to_string(&test_cases)?;
             println!("{}", test_cases_json);
 
             Ok(())
         }
         ShowFormat::Typst => {
-            print_typst_format(test_cases);
+            print_typst_format(test_cases, precisions);
             Ok(())
         }
     }
 }
 
 fn run(_cli: &Cli, cmd: &Run) -> Result<(), Box<dyn std::error::Error>> {
-    let input = std::fs::read_to_string(&cmd.file_path)
-        .map_err(|e| format!("Error while reading file {}: {}", &cmd.file_path, e))?;
+    let spec = check_gpt_file(&FsFileProvider, &cmd.file_path)?;
+    for warning in spec.warnings() {
+        eprintln!("{}\n", render_diagnostic(warning));
+    }
 
-    let test_cases = generate_tests_for_gpt_input(&input)?;
+    let (test_cases, infeasible) = spec.generate_tests_and_count_infeasible()?;
+    let precisions = spec.precisions();
 
     println!("Test cases:");
     if !cmd.no_show {
-        show(&test_cases, cmd.show_format)?;
+        show(&test_cases, cmd.show_format, &precisions)?;
     }
     println!("Number of test cases: {}", test_cases.len());
+    if infeasible.branches > 0 {
+        println!(
+            "Number of branches which can't be taken under the assumptions: {}",
+            infeasible.branches
+        );
+    }
+    if infeasible.test_cases > 0 {
+        println!(
+            "Number of test cases removed by the assumptions: {}",
+            infeasible.test_cases
+        );
+    }
 
     let ntuple_graph = create_graph(&test_cases);
 
@@ -180,18 +273,28 @@ fn run(_cli: &Cli, cmd: &Run) -> Result<(), Box<dyn std::error::Error>> {
 
     println!("\nAfter running {}:", cmd.algo);
     if !cmd.no_show {
-        show(&reduced_test_cases, cmd.show_format)?;
+        show(&reduced_test_cases, cmd.show_format, &precisions)?;
     }
     println!("Number of test cases: {}", reduced_test_cases.len());
 
+    if cmd.robustness {
+        let robustness_test_cases = spec.generate_robustness_tests();
+
+        println!("\nRobustness test cases:");
+        if !cmd.no_show {
+            show(&robustness_test_cases, cmd.show_format, &precisions)?;
+        }
+        println!(
+            "Number of robustness test cases: {}",
+            robustness_test_cases.len()
+        );
+    }
+
     Ok(())
 }
 
 fn and_reduce(_cli: &Cli, cmd: &AndReduce) -> Result<(), Box<dyn std::error::Error>> {
-    let input = std::fs::read_to_string(&cmd.file_path)
-        .map_err(|e| format!("Error while reading file {}: {}", &cmd.file_path, e))?;
-
-    let ir = and_reduce_gpt_input(&input)?;
+    let ir = and_reduce_gpt_file(&FsFileProvider, &cmd.file_path)?;
 
     for predicate in ir.into_iter().flat_map(|feature| feature.predicates) {
         println!("Predicate: {predicate}");
@@ -205,17 +308,98 @@ fn and_reduce(_cli: &Cli, cmd: &AndReduce) -> Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
+fn format_files(_cli: &Cli, cmd: &Fmt) -> Result<(), Box<dyn std::error::Error>> {
+    let mut unformatted_count = 0;
+
+    for file_path in &cmd.file_paths {
+        let source = std::fs::read_to_string(file_path)?;
+        let formatted = format_source(file_path, &source)?;
+
+        if formatted == source {
+            continue;
+        }
+
+        if cmd.check {
+            let line = source
+                .lines()
+                .zip(formatted.lines())
+                .position(|(original, formatted)| original != formatted)
+                .unwrap_or_else(|| source.lines().count().min(formatted.lines().count()))
+                + 1;
+            println!("{file_path}:{line}: not formatted");
+            unformatted_count += 1;
+        } else {
+            std::fs::write(file_path, formatted)?;
+        }
+    }
+
+    if unformatted_count > 0 {
+        return Err(format!("{unformatted_count} file(s) would be reformatted").into());
+    }
+
+    Ok(())
+}
+
+/// Renders the diagnostic like rustc, showing the line of the source with the span underlined
+fn render_diagnostic(diagnostic: &Diagnostic) -> String {
+    let header = format!(
+        "{}[{}]: {}\n --> {}:{}:{}",
+        diagnostic.severity,
+        diagnostic.code,
+        diagnostic.message,
+        diagnostic.file,
+        diagnostic.line,
+        diagnostic.column
+    );
+
+    let Some(source_line) = std::fs::read_to_string(&diagnostic.file)
+        .ok()
+        .and_then(|source| source.lines().nth(diagnostic.line - 1).map(str::to_owned))
+    else {
+        return header;
+    };
+
+    let line_number = diagnostic.line.to_string();
+    let padding = " ".repeat(line_number.len());
+    let underline_start = diagnostic.column - 1;
+    let underline_length = source_line
+        .chars()
+        .skip(underline_start)
+        .scan(0, |bytes, c| {
+            *bytes += c.len_utf8();
+            Some(*bytes)
+        })
+        .take_while(|bytes| *bytes <= diagnostic.span.len())
+        .count()
+        .max(1);
+
+    format!(
+        "{header}\n{padding} |\n{line_number} | {source_line}\n{padding} | {}{}",
+        " ".repeat(underline_start),
+        "^".repeat(underline_length)
+    )
+}
+
 pub fn main() {
     let args = Cli::parse();
 
     let result = match &args.command {
         Command::Run(cmd) => run(&args, cmd),
         Command::AndReduce(cmd) => and_reduce(&args, cmd),
+        Command::Fmt(cmd) => format_files(&args, cmd),
     };
 
-    match result {
-        Ok(_) => (),
-        Err(e) => println!("{}", e),
+    if let Err(e) = result {
+        match e.downcast_ref::<GPTError>() {
+            Some(error) if !error.diagnostics().is_empty() => {
+                for diagnostic in error.diagnostics() {
+                    println!("{}\n", render_diagnostic(diagnostic));
+                }
+            }
+            _ => println!("{}", e),
+        }
+
+        std::process::exit(1);
     }
 
     // let test_cases = match generate_tests_for_gpt_input(input3) {
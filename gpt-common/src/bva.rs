@@ -1,4 +1,7 @@
-use crate::interval::{Boundary, Interval, IntervalError, MultiInterval};
+use crate::{
+    interval::{Boundary, Interval, IntervalError, MultiInterval},
+    number::Num,
+};
 
 pub trait Bva
 where
@@ -7,54 +10,58 @@ where
     /// Possible acceptable values except the first from the edges.
     ///
     /// Example: `[1,10)` with the precision of `0.01` will have the in of `[1.0,9.99]`
-    fn calc_in(&self, precision: f32) -> MultiInterval;
+    fn calc_in(&self, precision: Num) -> MultiInterval;
 
     /// Possible not acceptable values except the first from the edges.
     ///
     /// Example: `[1,10)` with a precision of `0.01` will thave he out intervals of `(-Inf,0.98] [10.01,Inf)`
-    fn out(&self, precision: f32) -> MultiInterval;
+    fn out(&self, precision: Num) -> MultiInterval;
 
     /// Possible not acceptable values. This is Off+Out.
     ///
     /// Example: `[1,10)` will have the `off_out` intervals of `(-Inf,0.99] [10,Inf)`
-    fn off_out(&self, precision: f32) -> MultiInterval;
+    fn off_out(&self, precision: Num) -> MultiInterval;
 
     /// First acceptable values from the edges. There can be 0, 1, or 2 such points.
     ///
     /// Example: `[1,10)` with a precision of `0.01` will have the On points of `[1.0, 1.0] [9.99, 9.99]`
-    fn on(&self, precision: f32) -> MultiInterval;
+    fn on(&self, precision: Num) -> MultiInterval;
 
     /// Second acceptable values from the edges.There can be 0, 1, or 2 such points.
     ///
     /// Example: `[1,10)` with the precision of `0.01` will have the inin of `[1.01, 1.01] [9.98,9.98]`  
-    fn inin(&self, precision: f32) -> MultiInterval;
+    fn inin(&self, precision: Num) -> MultiInterval;
 
     /// First not acceptable values from the edges. There can be 0, 1, or 2 such points.
     ///
     /// Example: `[1.10)` with a precision of `0.01` will have the off points of `[0.99,0.99]` and `[10.0, 10.0]`.
-    fn off(&self, precision: f32) -> MultiInterval;
+    fn off(&self, precision: Num) -> MultiInterval;
 }
 
 impl Bva for Interval {
-    fn calc_in(&self, precision: f32) -> MultiInterval {
-        // If interval.lo is f32::NEG_INFINITY this will be f32::NEG_INFINITY
-        let lo = self.lo
-            + if self.lo_boundary == Boundary::Open {
-                1.0
+    fn calc_in(&self, precision: Num) -> MultiInterval {
+        // If interval.lo is Num::NEG_INFINITY this will be Num::NEG_INFINITY
+        let lo = self.lo.add_steps(
+            if self.lo_boundary == Boundary::Open {
+                1
             } else {
-                0.0
-            } * precision;
+                0
+            },
+            precision,
+        );
 
-        // If interval.lo is f32::INFINITY this will be f32::INFINITY
-        let hi = self.hi
-            - if self.hi_boundary == Boundary::Open {
-                1.0
+        // If interval.lo is Num::INFINITY this will be Num::INFINITY
+        let hi = self.hi.add_steps(
+            if self.hi_boundary == Boundary::Open {
+                -1
             } else {
-                0.0
-            } * precision;
+                0
+            },
+            precision,
+        );
 
-        if !((self.contains_point(lo) || self.lo == f32::NEG_INFINITY)
-            && (self.contains_point(hi) || self.hi == f32::INFINITY))
+        if !((self.contains_point(lo) || self.lo == Num::NEG_INFINITY)
+            && (self.contains_point(hi) || self.hi == Num::INFINITY))
         {
             return MultiInterval {
                 intervals: Vec::new(),
@@ -69,33 +76,37 @@ impl Bva for Interval {
         }
     }
 
-    fn out(&self, precision: f32) -> MultiInterval {
+    fn out(&self, precision: Num) -> MultiInterval {
         let mut outs = Vec::new();
 
-        if self.lo != f32::NEG_INFINITY {
+        if self.lo != Num::NEG_INFINITY {
             let out_lo = Self::new_closed(
-                f32::NEG_INFINITY,
-                self.lo
-                    - if self.lo_boundary == Boundary::Open {
-                        1.0
+                Num::NEG_INFINITY,
+                self.lo.add_steps(
+                    if self.lo_boundary == Boundary::Open {
+                        -1
                     } else {
-                        2.0
-                    } * precision,
+                        -2
+                    },
+                    precision,
+                ),
             )
             .expect("Should be a valid interval");
 
             outs.push(out_lo);
         }
 
-        if self.hi != f32::INFINITY {
+        if self.hi != Num::INFINITY {
             let out_hi = Self::new_closed(
-                self.hi
-                    + if self.hi_boundary == Boundary::Open {
-                        1.0
+                self.hi.add_steps(
+                    if self.hi_boundary == Boundary::Open {
+                        1
                     } else {
-                        2.0
-                    } * precision,
-                f32::INFINITY,
+                        2
+                    },
+                    precision,
+                ),
+                Num::INFINITY,
             )
             .expect("Should be a valid interval");
 
@@ -105,33 +116,37 @@ impl Bva for Interval {
         MultiInterval::from_intervals(outs)
     }
 
-    fn off_out(&self, precision: f32) -> MultiInterval {
+    fn off_out(&self, precision: Num) -> MultiInterval {
         let mut off_outs = Vec::new();
 
-        if self.lo != f32::NEG_INFINITY {
+        if self.lo != Num::NEG_INFINITY {
             let off_out_lo = Self::new_closed(
-                f32::NEG_INFINITY,
-                self.lo
-                    - if self.lo_boundary == Boundary::Open {
-                        0.0
+                Num::NEG_INFINITY,
+                self.lo.add_steps(
+                    if self.lo_boundary == Boundary::Open {
+                        0
                     } else {
-                        1.0
-                    } * precision,
+                        -1
+                    },
+                    precision,
+                ),
             )
             .expect("Should be a valid interval");
 
             off_outs.push(off_out_lo);
         }
 
-        if self.hi != f32::INFINITY {
+        if self.hi != Num::INFINITY {
             let off_out_hi = Self::new_closed(
-                self.hi
-                    + if self.hi_boundary == Boundary::Open {
-                        0.0
+                self.hi.add_steps(
+                    if self.hi_boundary == Boundary::Open {
+                        0
                     } else {
-                        1.0
-                    } * precision,
-                f32::INFINITY,
+                        1
+                    },
+                    precision,
+                ),
+                Num::INFINITY,
             )
             .expect("Should be a valid interval");
 
@@ -141,25 +156,29 @@ impl Bva for Interval {
         MultiInterval::from_intervals(off_outs)
     }
 
-    fn on(&self, precision: f32) -> MultiInterval {
+    fn on(&self, precision: Num) -> MultiInterval {
         let mut ons = Vec::new();
 
-        let on_lo = self.lo
-            + if self.lo_boundary == Boundary::Open {
-                1.0
+        let on_lo = self.lo.add_steps(
+            if self.lo_boundary == Boundary::Open {
+                1
             } else {
-                0.0
-            } * precision;
+                0
+            },
+            precision,
+        );
         if self.contains_point(on_lo) {
             ons.push(Self::new_closed_point(on_lo));
         }
 
-        let on_hi = self.hi
-            - if self.hi_boundary == Boundary::Open {
-                1.0
+        let on_hi = self.hi.add_steps(
+            if self.hi_boundary == Boundary::Open {
+                -1
             } else {
-                0.0
-            } * precision;
+                0
+            },
+            precision,
+        );
 
         if self.contains_point(on_hi) {
             ons.push(Self::new_closed_point(on_hi));
@@ -168,22 +187,26 @@ impl Bva for Interval {
         MultiInterval::from_intervals(ons)
     }
 
-    fn inin(&self, precision: f32) -> MultiInterval {
-        // If interval.lo is f32::NEG_INFINITY this will be f32::NEG_INFINITY
-        let lo = self.lo
-            + if self.lo_boundary == Boundary::Open {
-                2.0
+    fn inin(&self, precision: Num) -> MultiInterval {
+        // If interval.lo is Num::NEG_INFINITY this will be Num::NEG_INFINITY
+        let lo = self.lo.add_steps(
+            if self.lo_boundary == Boundary::Open {
+                2
             } else {
-                1.0
-            } * precision;
+                1
+            },
+            precision,
+        );
 
-        // If interval.lo is f32::INFINITY this will be f32::INFINITY
-        let hi = self.hi
-            - if self.hi_boundary == Boundary::Open {
-                2.0
+        // If interval.lo is Num::INFINITY this will be Num::INFINITY
+        let hi = self.hi.add_steps(
+            if self.hi_boundary == Boundary::Open {
+                -2
             } else {
-                1.0
-            } * precision;
+                -1
+            },
+            precision,
+        );
 
         match Self::new_closed(lo, hi) {
             Ok(inin) => MultiInterval::from_interval(inin),
@@ -193,31 +216,31 @@ impl Bva for Interval {
         }
     }
 
-    fn off(&self, precision: f32) -> MultiInterval {
+    fn off(&self, precision: Num) -> MultiInterval {
         let mut offs = Vec::new();
 
-        if self.lo != f32::NEG_INFINITY {
-            let off_lo = Self::new_closed_point(
-                self.lo
-                    - if self.lo_boundary == Boundary::Open {
-                        0.0
-                    } else {
-                        1.0
-                    } * precision,
-            );
+        if self.lo != Num::NEG_INFINITY {
+            let off_lo = Self::new_closed_point(self.lo.add_steps(
+                if self.lo_boundary == Boundary::Open {
+                    0
+                } else {
+                    -1
+                },
+                precision,
+            ));
 
             offs.push(off_lo);
         }
 
-        if self.hi != f32::INFINITY {
-            let off_hi = Self::new_closed_point(
-                self.hi
-                    + if self.hi_boundary == Boundary::Open {
-                        0.0
-                    } else {
-                        1.0
-                    } * precision,
-            );
+        if self.hi != Num::INFINITY {
+            let off_hi = Self::new_closed_point(self.hi.add_steps(
+                if self.hi_boundary == Boundary::Open {
+                    0
+                } else {
+                    1
+                },
+                precision,
+            ));
 
             offs.push(off_hi);
         }
@@ -229,8 +252,8 @@ impl Bva for Interval {
 impl MultiInterval {
     fn bva_all_intervals(
         &self,
-        precision: f32,
-        bva_function: impl Fn(&Interval, f32) -> Self,
+        precision: Num,
+        bva_function: impl Fn(&Interval, Num) -> Self,
     ) -> Self {
         let bar = self
             .intervals
@@ -243,27 +266,27 @@ impl MultiInterval {
 }
 
 impl Bva for MultiInterval {
-    fn calc_in(&self, precision: f32) -> MultiInterval {
+    fn calc_in(&self, precision: Num) -> MultiInterval {
         self.bva_all_intervals(precision, Interval::calc_in)
     }
 
-    fn out(&self, precision: f32) -> MultiInterval {
+    fn out(&self, precision: Num) -> MultiInterval {
         self.bva_all_intervals(precision, Interval::out)
     }
 
-    fn off_out(&self, precision: f32) -> MultiInterval {
+    fn off_out(&self, precision: Num) -> MultiInterval {
         self.bva_all_intervals(precision, Interval::off_out)
     }
 
-    fn on(&self, precision: f32) -> MultiInterval {
+    fn on(&self, precision: Num) -> MultiInterval {
         self.bva_all_intervals(precision, Interval::on)
     }
 
-    fn inin(&self, precision: f32) -> MultiInterval {
+    fn inin(&self, precision: Num) -> MultiInterval {
         self.bva_all_intervals(precision, Interval::inin)
     }
 
-    fn off(&self, precision: f32) -> MultiInterval {
+    fn off(&self, precision: Num) -> MultiInterval {
         self.bva_all_intervals(precision, Interval::off)
     }
 }
@@ -274,33 +297,42 @@ mod test {
     use rstest::rstest;
 
     use super::Bva;
-    use crate::interval::{Interval, MultiInterval};
+    use crate::{
+        interval::{Interval, MultiInterval},
+        number::{num, Num},
+    };
 
     #[rstest]
     // Correct In calculation for the boundaries
-    #[case("[1, 10]", 0.01, "[1, 10]")]
-    #[case("[1, 10)", 0.01, "[1, 9.99]")]
-    #[case("(1, 10)", 0.01, "[1.01, 9.99]")]
-    #[case("(1, 10]", 0.01, "[1.01, 10]")]
+    #[case("[1, 10]", num!(0.01), "[1, 10]")]
+    #[case("[1, 10)", num!(0.01), "[1, 9.99]")]
+    #[case("(1, 10)", num!(0.01), "[1.01, 9.99]")]
+    #[case("(1, 10]", num!(0.01), "[1.01, 10]")]
     // Inf boundaries
-    #[case::inf_left("(-Inf, 10]", 0.01, "(-Inf, 10]")]
-    #[case::inf_left("(-Inf, 10)", 0.01, "(-Inf, 9.99]")]
-    #[case::inf_right("[10, Inf)", 0.01, "[10, Inf)")]
-    #[case::inf_right("(10, Inf)", 0.01, "[10.01, Inf)")]
-    #[case::inf("(-Inf, Inf)", 0.01, "(-Inf, Inf)")]
+    #[case::inf_left("(-Inf, 10]", num!(0.01), "(-Inf, 10]")]
+    #[case::inf_left("(-Inf, 10)", num!(0.01), "(-Inf, 9.99]")]
+    #[case::inf_right("[10, Inf)", num!(0.01), "[10, Inf)")]
+    #[case::inf_right("(10, Inf)", num!(0.01), "[10.01, Inf)")]
+    #[case::inf("(-Inf, Inf)", num!(0.01), "(-Inf, Inf)")]
     // Testing if in would be empty
-    #[case("[1, 1]", 0.01, "[1,1]")]
-    #[case("[1, 1)", 0.01, "")]
-    #[case("(1, 1]", 0.01, "")]
-    #[case("(1, 1)", 0.01, "")]
-    #[case("[1, 1.42)", 0.42, "[1, 1]")]
-    #[case("(0.5, 1]", 0.5, "[1, 1]")]
-    #[case("(1, 10)", 100.0, "")]
-    #[case("(1, 10]", 100.0, "")]
-    #[case("[1, 10)", 100.0, "")]
+    #[case("[1, 1]", num!(0.01), "[1,1]")]
+    #[case("[1, 1)", num!(0.01), "")]
+    #[case("(1, 1]", num!(0.01), "")]
+    #[case("(1, 1)", num!(0.01), "")]
+    #[case("[1, 1.42)", num!(0.42), "[1, 1]")]
+    #[case("(0.5, 1]", num!(0.5), "[1, 1]")]
+    #[case("(1, 10)", num!(100.0), "")]
+    #[case("(1, 10]", num!(100.0), "")]
+    #[case("[1, 10)", num!(100.0), "")]
+    // Stepping by the precision is exact
+    #[case::decimal("[1000.3, 2345.7)", num!(0.1), "[1000.3, 2345.6]")]
+    #[case::decimal("(19.99, 20.1)", num!(0.01), "[20, 20.09]")]
+    // 64-bit integers don't lose their last digits
+    #[case::large("(9007199254740992, 9223372036854775807)", num!(1), "[9007199254740993, 9223372036854775806]")]
+    #[case::large("[0, 18446744073709551615)", num!(1), "[0, 18446744073709551614]")]
     fn test_interval_in(
         #[case] input: Interval,
-        #[case] precision: f32,
+        #[case] precision: Num,
         #[case] expected: MultiInterval,
     ) {
         assert_eq!(input.calc_in(precision), expected);
@@ -308,37 +340,53 @@ mod test {
 
     #[rstest]
     // Correct On calculation for the boundaries
-    #[case("[1, 10]", 0.01, "[1,1] [10,10]")]
-    #[case("[1, 10)", 0.01, "[1,1] [9.99,9.99]")]
-    #[case("(1, 10)", 0.01, "[1.01,1.01] [9.99,9.99]")]
-    #[case("(1, 10]", 0.01, "[1.01,1.01] [10,10]")]
+    #[case("[1, 10]", num!(0.01), "[1,1] [10,10]")]
+    #[case("[1, 10)", num!(0.01), "[1,1] [9.99,9.99]")]
+    #[case("(1, 10)", num!(0.01), "[1.01,1.01] [9.99,9.99]")]
+    #[case("(1, 10]", num!(0.01), "[1.01,1.01] [10,10]")]
     // Inf boundaries
-    #[case::inf_left("(-Inf, 10]", 0.01, "[10,10]")]
-    #[case::inf_left("(-Inf, 10)", 0.01, "[9.99,9.99]")]
-    #[case::inf_right("[10, Inf)", 0.01, "[10,10]")]
-    #[case::inf_right("(10, Inf)", 0.01, "[10.01,10.01]")]
-    #[case::inf("(-Inf, Inf)", 0.01, "")]
+    #[case::inf_left("(-Inf, 10]", num!(0.01), "[10,10]")]
+    #[case::inf_left("(-Inf, 10)", num!(0.01), "[9.99,9.99]")]
+    #[case::inf_right("[10, Inf)", num!(0.01), "[10,10]")]
+    #[case::inf_right("(10, Inf)", num!(0.01), "[10.01,10.01]")]
+    #[case::inf("(-Inf, Inf)", num!(0.01), "")]
     // Testing if on would be empty
-    #[case::single_point("[1, 1]", 0.01, "[1,1]")]
-    #[case::half_open_right_empty("[1, 1)", 0.01, "")]
-    #[case::half_open_left_empty("(1, 1]", 0.01, "")]
-    #[case::empty("(1, 1)", 0.01, "")]
-    #[case("[1, 1.42)", 0.42, "[1,1]")]
-    #[case("(0.5, 1]", 0.5, "[1,1]")]
-    #[case("[1, 10]", 100.0, "[1,1] [10,10]")]
-    #[case("[1, 10)", 100.0, "[1,1]")]
-    #[case("(1, 10]", 100.0, "[10,10]")]
-    #[case("(1, 10)", 100.0, "")]
+    #[case::single_point("[1, 1]", num!(0.01), "[1,1]")]
+    #[case::half_open_right_empty("[1, 1)", num!(0.01), "")]
+    #[case::half_open_left_empty("(1, 1]", num!(0.01), "")]
+    #[case::empty("(1, 1)", num!(0.01), "")]
+    #[case("[1, 1.42)", num!(0.42), "[1,1]")]
+    #[case("(0.5, 1]", num!(0.5), "[1,1]")]
+    #[case("[1, 10]", num!(100.0), "[1,1] [10,10]")]
+    #[case("[1, 10)", num!(100.0), "[1,1]")]
+    #[case("(1, 10]", num!(100.0), "[10,10]")]
+    #[case("(1, 10)", num!(100.0), "")]
+    #[case::decimal("(1000.3, 2345.7)", num!(0.1), "[1000.4,1000.4] [2345.6,2345.6]")]
     fn test_interval_on(
         #[case] input: Interval,
-        #[case] precision: f32,
+        #[case] precision: Num,
         #[case] expected: MultiInterval,
     ) {
         assert_eq!(input.on(precision), expected);
     }
 
     // TODO: Test for interval inin
-    // TODO: Test for interval off
+
+    #[rstest]
+    #[case("[1, 10]", num!(0.01), "[0.99,0.99] [10.01,10.01]")]
+    #[case("(1, 10)", num!(0.01), "[1,1] [10,10]")]
+    #[case::inf_left("(-Inf, 10]", num!(0.01), "[10.01,10.01]")]
+    #[case::inf_right("[10, Inf)", num!(0.01), "[9.99,9.99]")]
+    #[case::decimal("[1000.3, 2345.7]", num!(0.1), "[1000.2,1000.2] [2345.8,2345.8]")]
+    #[case::large("[-9223372036854775808, 9223372036854775807]", num!(1), "[-9223372036854775809,-9223372036854775809] [9223372036854775808,9223372036854775808]")]
+    fn test_interval_off(
+        #[case] input: Interval,
+        #[case] precision: Num,
+        #[case] expected: MultiInterval,
+    ) {
+        assert_eq!(input.off(precision), expected);
+    }
+
     // TODO: Test for interval out
 
     // TODO: Test for multiinterval in
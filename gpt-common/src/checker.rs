@@ -0,0 +1,1558 @@
+use itertools::Itertools;
+
+use crate::{
+    diagnostic::code,
+    interval::{MultiInterval, Unionable},
+    number::Num,
+    parser::ast::{
+        self, BinaryOp, BoolOp, CaseNode, CaseValue, CharClass, ConditionsNode, ConstNode,
+        FeatureNode, IfNode, IntervalNode, LetNode, RootNode, SwitchNode, TemplateNode, Type,
+        UseNode, VarNode,
+    },
+};
+
+/// The longest string the test cases can have, longer ones would take too much memory to make
+const MAX_STRING_LENGTH: u32 = 10_000;
+
+/// A problem with the meaning of the spec, like an undeclared variable, found before converting it to the IR.
+/// Warnings, like overlapping cases of a switch, are reported the same way, with a warning code.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SemanticError<'a> {
+    /// The part of the source with the problem, like the name of the variable
+    pub fragment: &'a str,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// The semantic errors of the root and of each feature, in the order of the features
+#[derive(Debug, PartialEq, Eq)]
+pub struct CheckResult<'a> {
+    pub root_errors: Vec<SemanticError<'a>>,
+    pub feature_errors: Vec<Vec<SemanticError<'a>>>,
+}
+
+/// The constants, variables and `let` bindings which can be used in a feature
+struct Scope<'a, 'b> {
+    /// A constant whose value couldn't be resolved is `None`, so it doesn't cause more errors
+    constants: Vec<(&'a str, Option<Num>)>,
+    variables: Vec<&'b VarNode<'a>>,
+    bindings: Vec<&'a str>,
+}
+
+impl<'a, 'b> Scope<'a, 'b> {
+    fn is_constant(&self, name: &str) -> bool {
+        self.constants
+            .iter()
+            .any(|(constant_name, _)| *constant_name == name)
+    }
+
+    /// Finds a variable, or a field of a record variable by its path, like `order.price`
+    fn find_variable(&self, var_name: &str) -> Option<&'b VarNode<'a>> {
+        let mut path = var_name.split('.');
+        let root_name = path.next()?;
+        let root = self
+            .variables
+            .iter()
+            .find(|var_node| var_node.var_name == root_name)
+            .copied()?;
+
+        path.try_fold(root, |var_node, field_name| {
+            var_node
+                .fields
+                .iter()
+                .find(|field| field.var_name == field_name)
+        })
+    }
+
+    fn find_type(&self, var_name: &str) -> Option<&'b Type> {
+        self.find_variable(var_name)
+            .map(|var_node| &var_node.var_type)
+    }
+}
+
+fn undefined_variable(var_name: &str) -> SemanticError {
+    SemanticError {
+        fragment: var_name,
+        code: code::UNDEFINED_VARIABLE,
+        message: format!("undefined variable `{var_name}`"),
+    }
+}
+
+const fn type_mismatch(var_name: &str, message: String) -> SemanticError {
+    SemanticError {
+        fragment: var_name,
+        code: code::TYPE_MISMATCH,
+        message,
+    }
+}
+
+const fn is_number(var_type: &Type) -> bool {
+    matches!(var_type, Type::Integer { .. } | Type::Float { .. })
+}
+
+fn resolve_number<'a>(
+    constants: &[(&'a str, Option<Num>)],
+    number: &ast::Number<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) -> Option<Num> {
+    match number {
+        ast::Number::Literal(value) => Some(*value),
+        ast::Number::Constant(name) => constants
+            .iter()
+            .find(|(constant_name, _)| constant_name == name)
+            .map_or_else(
+                || {
+                    errors.push(SemanticError {
+                        fragment: name,
+                        code: code::UNDEFINED_CONSTANT,
+                        message: format!("undefined constant `{name}`"),
+                    });
+                    None
+                },
+                |(_, value)| *value,
+            ),
+    }
+}
+
+/// `fragment` is where the error is reported, since the interval doesn't know where it is in the source
+fn check_interval<'a>(
+    constants: &[(&'a str, Option<Num>)],
+    interval: &IntervalNode<'a>,
+    fragment: &'a str,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let lo = resolve_number(constants, &interval.lo, errors);
+    let hi = resolve_number(constants, &interval.hi, errors);
+    let (Some(lo), Some(hi)) = (lo, hi) else {
+        return;
+    };
+
+    if lo > hi {
+        errors.push(SemanticError {
+            fragment,
+            code: code::INVALID_INTERVAL,
+            message: format!(
+                "invalid interval: the lower bound {lo} is greater than the upper bound {hi}"
+            ),
+        });
+    }
+}
+
+/// Adds the declared constants to the ones already in scope.
+/// A constant can be defined with the value of an earlier one.
+fn check_constants<'a>(
+    constants_in_scope: &[(&'a str, Option<Num>)],
+    const_nodes: &[ConstNode<'a>],
+    errors: &mut Vec<SemanticError<'a>>,
+) -> Vec<(&'a str, Option<Num>)> {
+    const_nodes
+        .iter()
+        .fold(constants_in_scope.to_vec(), |mut constants, const_node| {
+            if constants.iter().any(|(name, _)| *name == const_node.name) {
+                errors.push(SemanticError {
+                    fragment: const_node.name,
+                    code: code::DUPLICATE_DECLARATION,
+                    message: format!("constant `{}` is already defined", const_node.name),
+                });
+            }
+
+            let value = resolve_number(&constants, &const_node.value, errors);
+            constants.push((const_node.name, value));
+            constants
+        })
+}
+
+fn check_variables<'a>(scope: &Scope<'a, '_>, errors: &mut Vec<SemanticError<'a>>) {
+    for (i, var_node) in scope.variables.iter().enumerate() {
+        if scope.variables[..i]
+            .iter()
+            .any(|other| other.var_name == var_node.var_name)
+        {
+            errors.push(SemanticError {
+                fragment: var_node.var_name,
+                code: code::DUPLICATE_DECLARATION,
+                message: format!("variable `{}` is already declared", var_node.var_name),
+            });
+        }
+
+        if scope.is_constant(var_node.var_name) {
+            errors.push(SemanticError {
+                fragment: var_node.var_name,
+                code: code::DUPLICATE_DECLARATION,
+                message: format!(
+                    "`{}` is declared both as a variable and as a constant",
+                    var_node.var_name
+                ),
+            });
+        }
+
+        check_declaration(scope, var_node, errors);
+    }
+}
+
+/// Checks the domain of the variable, and the fields of a record variable
+fn check_declaration<'a>(
+    scope: &Scope<'a, '_>,
+    var_node: &VarNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    if let Some(domain) = &var_node.domain {
+        if is_number(&var_node.var_type) {
+            check_interval(&scope.constants, domain, var_node.var_name, errors);
+            check_domain_in_range(&scope.constants, var_node, domain, errors);
+        } else {
+            errors.push(type_mismatch(
+                var_node.var_name,
+                format!(
+                    "`{}` is not a number, it can't have a domain",
+                    var_node.var_name
+                ),
+            ));
+        }
+    }
+
+    if let Type::Float { precision } = var_node.var_type {
+        if precision <= Num::ZERO {
+            errors.push(SemanticError {
+                fragment: var_node.var_name,
+                code: code::INVALID_PRECISION,
+                message: format!(
+                    "the precision of `{}` is {precision}, it has to be greater than 0",
+                    var_node.var_name
+                ),
+            });
+        }
+    }
+
+    if let Type::Enum(members) = &var_node.var_type {
+        for (i, member) in members.iter().enumerate() {
+            if members[..i].contains(member) && !members[i + 1..].contains(member) {
+                errors.push(SemanticError {
+                    fragment: var_node.var_name,
+                    code: code::DUPLICATE_DECLARATION,
+                    message: format!(
+                        "member `{member}` is declared more than once in `{}`",
+                        var_node.var_name
+                    ),
+                });
+            }
+        }
+    }
+
+    for (i, field) in var_node.fields.iter().enumerate() {
+        if var_node.fields[..i]
+            .iter()
+            .any(|other| other.var_name == field.var_name)
+        {
+            errors.push(SemanticError {
+                fragment: field.var_name,
+                code: code::DUPLICATE_DECLARATION,
+                message: format!(
+                    "field `{}` is already declared in `{}`",
+                    field.var_name, var_node.var_name
+                ),
+            });
+        }
+
+        check_declaration(scope, field, errors);
+    }
+}
+
+/// The declared domain of a sized integer can't have values its type can't hold
+fn check_domain_in_range<'a>(
+    constants: &[(&'a str, Option<Num>)],
+    var_node: &VarNode<'a>,
+    domain: &IntervalNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let (Type::Integer { size: Some(size) }, Some(range), Some(values)) = (
+        &var_node.var_type,
+        var_node.var_type.range(),
+        resolve_interval(constants, domain),
+    ) else {
+        return;
+    };
+
+    if values
+        .restricted_to(&range.complement())
+        .has_value_with_precision(Num::ONE)
+    {
+        errors.push(SemanticError {
+            fragment: var_node.var_name,
+            code: code::INVALID_INTERVAL,
+            message: format!(
+                "the domain {values} of `{}` goes beyond {range}, the values of `{size}`",
+                var_node.var_name
+            ),
+        });
+    }
+}
+
+fn check_enum_members<'a>(
+    scope: &Scope<'a, '_>,
+    var_name: &'a str,
+    members: &[&'a str],
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    match scope.find_type(var_name) {
+        Some(Type::Enum(enum_members)) => {
+            for member in members {
+                if !enum_members.iter().any(|x| x == member) {
+                    errors.push(SemanticError {
+                        fragment: member,
+                        code: code::UNDEFINED_ENUM_MEMBER,
+                        message: format!(
+                            "`{member}` is not a member of the enum variable `{var_name}`"
+                        ),
+                    });
+                }
+            }
+        }
+        Some(_) => errors.push(type_mismatch(
+            var_name,
+            format!("`{var_name}` is not an enum variable"),
+        )),
+        None => errors.push(undefined_variable(var_name)),
+    }
+}
+
+/// Checks the terms the same way as `ast_to_ir` folds them, so a condition whose variables cancel out is found
+fn check_linear_condition<'a>(
+    scope: &Scope<'a, '_>,
+    cond: &ast::LinearCondition<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let errors_before = errors.len();
+    let mut coefficients: Vec<(&str, Option<Num>)> = Vec::new();
+
+    let lhs_terms = cond.lhs.terms.iter().map(|term| (term, Num::ONE));
+    let rhs_terms = cond.rhs.terms.iter().map(|term| (term, -Num::ONE));
+    for (term, sign) in lhs_terms.chain(rhs_terms) {
+        let coefficient = match term.coefficient_constant {
+            Some(name) => resolve_number(&scope.constants, &ast::Number::Constant(name), errors)
+                .map(|value| term.coefficient * value),
+            None => Some(term.coefficient),
+        };
+
+        if scope.is_constant(term.var_name) {
+            continue;
+        }
+
+        match scope.find_type(term.var_name) {
+            Some(var_type) if is_number(var_type) => {}
+            Some(_) => {
+                errors.push(type_mismatch(
+                    term.var_name,
+                    format!(
+                        "`{}` is not a number, it can't be used in arithmetic",
+                        term.var_name
+                    ),
+                ));
+                continue;
+            }
+            None => {
+                errors.push(undefined_variable(term.var_name));
+                continue;
+            }
+        }
+
+        match coefficients
+            .iter_mut()
+            .find(|(var_name, _)| *var_name == term.var_name)
+        {
+            Some((_, sum)) => *sum = sum.zip(coefficient).map(|(sum, x)| sum + sign * x),
+            None => coefficients.push((term.var_name, coefficient.map(|x| sign * x))),
+        }
+    }
+
+    let first_term = cond.lhs.terms.first().or_else(|| cond.rhs.terms.first());
+    if let Some(first_term) = first_term {
+        if errors.len() == errors_before
+            && coefficients.iter().all(|(_, sum)| *sum == Some(Num::ZERO))
+        {
+            errors.push(SemanticError {
+                fragment: first_term.var_name,
+                code: code::CONSTANT_CONDITION,
+                message: "the variables cancel each other out in the condition, it is always true or false".to_owned(),
+            });
+        }
+    }
+}
+
+fn check_relation_condition<'a>(
+    scope: &Scope<'a, '_>,
+    cond: &ast::RelationCondition<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let is_equality = matches!(cond.binary_op, BinaryOp::Equal | BinaryOp::NotEqual);
+
+    match (scope.find_type(cond.left), scope.find_type(cond.right)) {
+        // `tier == gold` compares an enum variable with one of its members
+        (Some(Type::Enum(_)), None) => {
+            if is_equality {
+                check_enum_members(scope, cond.left, &[cond.right], errors);
+            } else {
+                errors.push(type_mismatch(
+                    cond.left,
+                    format!(
+                        "enum variable `{}` can only be compared with == and !=",
+                        cond.left
+                    ),
+                ));
+            }
+        }
+        // `price >= DISCOUNT_THRESHOLD` compares a number variable with a constant
+        (Some(var_type), None) if is_number(var_type) => {
+            if !scope.is_constant(cond.right) {
+                errors.push(undefined_variable(cond.right));
+            }
+        }
+        (None, Some(var_type)) if is_number(var_type) => {
+            if !scope.is_constant(cond.left) {
+                errors.push(undefined_variable(cond.left));
+            }
+        }
+        (Some(Type::Bool), Some(Type::Bool)) if is_equality => {}
+        (Some(left), Some(right)) if is_number(left) && is_number(right) => {
+            check_linear_condition(
+                scope,
+                &ast::LinearCondition {
+                    lhs: variable_expression(cond.left),
+                    binary_op: cond.binary_op.clone(),
+                    rhs: variable_expression(cond.right),
+                },
+                errors,
+            );
+        }
+        (None, _) => errors.push(undefined_variable(cond.left)),
+        (_, None) => errors.push(undefined_variable(cond.right)),
+        (Some(_), Some(_)) => errors.push(type_mismatch(
+            cond.left,
+            format!(
+                "`{}` and `{}` can't be compared with {}",
+                cond.left, cond.right, cond.binary_op
+            ),
+        )),
+    }
+}
+
+fn variable_expression(var_name: &str) -> ast::LinearExpression {
+    ast::LinearExpression {
+        terms: vec![ast::LinearTerm {
+            coefficient: Num::ONE,
+            coefficient_constant: None,
+            var_name,
+        }],
+        constant: Num::ZERO,
+    }
+}
+
+/// The variable of `x % divisor == remainder` has to be an integer, the divisor a positive integer,
+/// and the remainder one the division can leave
+fn check_modulo_condition<'a>(
+    scope: &Scope<'a, '_>,
+    cond: &ast::ModuloCondition<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    match scope.find_type(cond.var_name) {
+        Some(Type::Integer { .. }) => {}
+        Some(_) => errors.push(type_mismatch(
+            cond.var_name,
+            format!(
+                "`{}` is not an integer, it can't be divided with a remainder",
+                cond.var_name
+            ),
+        )),
+        None => errors.push(undefined_variable(cond.var_name)),
+    }
+
+    let divisor = resolve_number(&scope.constants, &cond.divisor, errors);
+    let remainder = resolve_number(&scope.constants, &cond.remainder, errors);
+    let (Some(divisor), Some(remainder)) = (divisor, remainder) else {
+        return;
+    };
+
+    if divisor <= Num::ZERO || !divisor.is_multiple_of(Num::ONE) {
+        errors.push(SemanticError {
+            fragment: cond.var_name,
+            code: code::INVALID_DIVISOR,
+            message: format!("the divisor {divisor} is not a positive integer"),
+        });
+    } else if remainder < Num::ZERO || remainder >= divisor || !remainder.is_multiple_of(Num::ONE) {
+        errors.push(SemanticError {
+            fragment: cond.var_name,
+            code: code::CONSTANT_CONDITION,
+            message: format!(
+                "the remainder of dividing by {divisor} is never {remainder}, the condition is always true or false"
+            ),
+        });
+    }
+}
+
+fn check_condition<'a>(
+    scope: &Scope<'a, '_>,
+    condition: &ast::Condition<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    match condition {
+        ast::Condition::Bool(cond) => match scope.find_type(cond.var_name) {
+            Some(Type::Bool) => {}
+            Some(_) => errors.push(type_mismatch(
+                cond.var_name,
+                format!(
+                    "`{}` is not a bool variable, it can't be compared with {}",
+                    cond.var_name, cond.constant
+                ),
+            )),
+            None => errors.push(undefined_variable(cond.var_name)),
+        },
+        ast::Condition::Binary(cond) => match scope.find_type(cond.var_name) {
+            Some(var_type) if is_number(var_type) => {
+                check_precision(scope, cond.var_name, cond.constant, errors);
+            }
+            Some(_) => errors.push(type_mismatch(
+                cond.var_name,
+                format!(
+                    "`{}` is not a number, it can't be compared with {}",
+                    cond.var_name, cond.constant
+                ),
+            )),
+            None => errors.push(undefined_variable(cond.var_name)),
+        },
+        ast::Condition::Interval(cond) => {
+            match scope.find_type(cond.var_name) {
+                Some(var_type) if is_number(var_type) => {}
+                Some(_) => errors.push(type_mismatch(
+                    cond.var_name,
+                    format!(
+                        "`{}` is not a number, it can't be in an interval",
+                        cond.var_name
+                    ),
+                )),
+                None => errors.push(undefined_variable(cond.var_name)),
+            }
+            check_interval(&scope.constants, &cond.interval, cond.var_name, errors);
+            // The undefined constants are already reported by `check_interval`
+            for bound in [&cond.interval.lo, &cond.interval.hi] {
+                if let Some(bound) = resolve_number(&scope.constants, bound, &mut Vec::new()) {
+                    check_precision(scope, cond.var_name, bound, errors);
+                }
+            }
+        }
+        ast::Condition::Enum(cond) => {
+            check_enum_members(scope, cond.var_name, &cond.members, errors);
+        }
+        ast::Condition::Set(cond) => {
+            match scope.find_type(cond.var_name) {
+                Some(var_type) if is_number(var_type) => {}
+                Some(_) => errors.push(type_mismatch(
+                    cond.var_name,
+                    format!(
+                        "`{}` is not a number, it can't be in a set of numbers",
+                        cond.var_name
+                    ),
+                )),
+                None => errors.push(undefined_variable(cond.var_name)),
+            }
+            let var_type = scope.find_type(cond.var_name);
+            for value in &cond.values {
+                let Some(value) = resolve_number(&scope.constants, value, errors) else {
+                    continue;
+                };
+                // A member is a single value, it can't be rounded to the values of the variable
+                let message = match var_type {
+                    Some(Type::Integer { .. }) if !value.is_multiple_of(Num::ONE) => {
+                        format!("`{}` is an integer, it's never {value}", cond.var_name)
+                    }
+                    Some(Type::Float { precision }) if !value.is_multiple_of(*precision) => {
+                        format!(
+                            "`{}` has a precision of {precision}, it's never {value}",
+                            cond.var_name
+                        )
+                    }
+                    _ => continue,
+                };
+                errors.push(type_mismatch(cond.var_name, message));
+            }
+        }
+        ast::Condition::Modulo(cond) => check_modulo_condition(scope, cond, errors),
+        ast::Condition::Relation(cond) => check_relation_condition(scope, cond, errors),
+        ast::Condition::Linear(cond) => check_linear_condition(scope, cond, errors),
+        ast::Condition::Null(cond) => match scope.find_variable(cond.var_name) {
+            Some(var_node) if var_node.nullable => {}
+            Some(_) => errors.push(type_mismatch(
+                cond.var_name,
+                format!("`{}` is not nullable, it is never null", cond.var_name),
+            )),
+            None => errors.push(undefined_variable(cond.var_name)),
+        },
+        ast::Condition::Length(cond) => {
+            check_string_variable(scope, cond.var_name, "have a length", errors);
+            let lengths = match &cond.comparison {
+                ast::LengthComparison::Binary(_, length) => {
+                    resolve_number(&scope.constants, length, errors)
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                }
+                ast::LengthComparison::Interval(_, interval) => {
+                    check_interval(&scope.constants, interval, cond.var_name, errors);
+                    // The undefined constants are already reported by `check_interval`
+                    [&interval.lo, &interval.hi]
+                        .into_iter()
+                        .filter_map(|bound| {
+                            resolve_number(&scope.constants, bound, &mut Vec::new())
+                        })
+                        .collect()
+                }
+            };
+            if let Some(shortest) = lengths.iter().min().filter(|length| **length < Num::ZERO) {
+                errors.push(SemanticError {
+                    fragment: cond.var_name,
+                    code: code::NEGATIVE_LENGTH,
+                    message: format!(
+                        "the length of `{}` is never negative, it can't be compared with {shortest}",
+                        cond.var_name
+                    ),
+                });
+            }
+            if let Some(longest) = lengths
+                .iter()
+                .filter(|length| length.is_finite() && **length > Num::from(MAX_STRING_LENGTH))
+                .max()
+            {
+                errors.push(SemanticError {
+                    fragment: cond.var_name,
+                    code: code::LENGTH_TOO_LONG,
+                    message: format!(
+                        "the strings of `{}` can't be longer than {MAX_STRING_LENGTH} characters in the test cases, it can't be compared with {longest}",
+                        cond.var_name
+                    ),
+                });
+            }
+        }
+        ast::Condition::Pattern(cond) => {
+            check_string_variable(scope, cond.var_name, "match characters", errors);
+        }
+    }
+}
+
+/// A constant between the values the variable can take is rounded to them, so `p >= 199.95` is `p >= 200.0` for a `num(0.1)`
+fn check_precision<'a>(
+    scope: &Scope<'a, '_>,
+    var_name: &'a str,
+    value: Num,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let Some(precision) = scope.find_type(var_name).and_then(Type::get_precision) else {
+        return;
+    };
+
+    if value.is_finite() && !value.is_multiple_of(precision) {
+        errors.push(SemanticError {
+            fragment: var_name,
+            code: code::ROUNDED_CONSTANT,
+            message: format!(
+                "{value} is between the values of `{var_name}` with the precision of {precision}, it's rounded to them"
+            ),
+        });
+    }
+}
+
+fn check_string_variable<'a>(
+    scope: &Scope<'a, '_>,
+    var_name: &'a str,
+    action: &str,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    match scope.find_type(var_name) {
+        Some(Type::String) => {}
+        Some(_) => errors.push(type_mismatch(
+            var_name,
+            format!("`{var_name}` is not a string, it can't {action}"),
+        )),
+        None => errors.push(undefined_variable(var_name)),
+    }
+}
+
+/// The characters the string variables have to be made of, for the patterns and-ed together
+fn conjoined_patterns<'a>(conditions_node: &ConditionsNode<'a>) -> Vec<(&'a str, CharClass)> {
+    match conditions_node {
+        ConditionsNode::Expression(ast::Condition::Pattern(cond)) => {
+            vec![(cond.var_name, cond.class.clone())]
+        }
+        ConditionsNode::Group {
+            left,
+            right,
+            operator: BoolOp::And,
+        } => conjoined_patterns(left)
+            .into_iter()
+            .chain(conjoined_patterns(right))
+            .fold(Vec::new(), |mut patterns, (var_name, class)| {
+                match patterns.iter_mut().find(|(name, _)| *name == var_name) {
+                    Some((_, conjoined)) => *conjoined = conjoined.intersection(&class),
+                    None => patterns.push((var_name, class)),
+                }
+                patterns
+            }),
+        _ => Vec::new(),
+    }
+}
+
+/// Only the empty string has every character in classes without a common one,
+/// so the sides of `s matches "[0-9]" && s matches "[a-z]"` can't hold for any other string
+fn check_pattern_conjunction<'a>(
+    left: &ConditionsNode<'a>,
+    right: &ConditionsNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let left = conjoined_patterns(left);
+    for (var_name, class) in conjoined_patterns(right) {
+        let Some((_, left_class)) = left.iter().find(|(name, _)| *name == var_name) else {
+            continue;
+        };
+        // The empty sides themselves are reported where they are and-ed
+        if !class.is_empty() && !left_class.is_empty() && class.intersection(left_class).is_empty()
+        {
+            errors.push(SemanticError {
+                fragment: var_name,
+                code: code::UNSATISFIABLE_PATTERNS,
+                message: format!(
+                    "`{var_name}` has to match {left_class} and {class}, only the empty string has no character outside of them"
+                ),
+            });
+        }
+    }
+}
+
+fn check_conditions_node<'a>(
+    scope: &Scope<'a, '_>,
+    conditions_node: &ConditionsNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    match conditions_node {
+        ConditionsNode::Negated(node) => check_conditions_node(scope, node, errors),
+        ConditionsNode::Expression(condition) => check_condition(scope, condition, errors),
+        ConditionsNode::Group {
+            left,
+            right,
+            operator,
+        } => {
+            check_conditions_node(scope, left, errors);
+            check_conditions_node(scope, right, errors);
+            if *operator == BoolOp::And {
+                check_pattern_conjunction(left, right, errors);
+            }
+        }
+        ConditionsNode::Binding(name) if scope.bindings.contains(name) => {}
+        ConditionsNode::Binding(name) if scope.find_variable(name).is_some() => {
+            errors.push(type_mismatch(
+                name,
+                format!("`{name}` is a variable, it has to be compared with a value"),
+            ));
+        }
+        ConditionsNode::Binding(name) => errors.push(SemanticError {
+            fragment: name,
+            code: code::UNDEFINED_BINDING,
+            message: format!("undefined binding `{name}`"),
+        }),
+    }
+}
+
+/// A binding can only use the ones defined before it, so they can't refer to each other
+fn check_bindings<'a>(
+    scope: &mut Scope<'a, '_>,
+    let_nodes: &[LetNode<'a>],
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    for let_node in let_nodes {
+        check_conditions_node(scope, &let_node.conditions, errors);
+
+        if scope.bindings.contains(&let_node.name)
+            || scope.is_constant(let_node.name)
+            || scope.find_variable(let_node.name).is_some()
+        {
+            errors.push(SemanticError {
+                fragment: let_node.name,
+                code: code::DUPLICATE_DECLARATION,
+                message: format!("`{}` is already declared", let_node.name),
+            });
+        }
+        scope.bindings.push(let_node.name);
+    }
+}
+
+fn check_if_node<'a>(
+    scope: &Scope<'a, '_>,
+    if_node: &IfNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    check_conditions_node(scope, &if_node.conditions, errors);
+    for body_node in if_node.body.iter().flatten() {
+        check_if_node(scope, body_node, errors);
+    }
+
+    for else_if_node in &if_node.else_if {
+        check_conditions_node(scope, &else_if_node.conditions, errors);
+        for body_node in &else_if_node.body {
+            check_if_node(scope, body_node, errors);
+        }
+    }
+
+    for body_node in if_node
+        .else_node
+        .iter()
+        .flat_map(|else_node| &else_node.body)
+    {
+        check_if_node(scope, body_node, errors);
+    }
+}
+
+/// The values a switch variable can take. Enum members and bools are points at their index,
+/// so the cases of every type can be checked as intervals.
+struct SwitchDomain {
+    domain: MultiInterval,
+    precision: Num,
+    /// The names of the points, if the variable isn't a number
+    names: Vec<String>,
+}
+
+impl SwitchDomain {
+    fn new(constants: &[(&str, Option<Num>)], var_node: &VarNode) -> Self {
+        let points = |names: Vec<String>| Self {
+            domain: MultiInterval::new_closed(Num::ZERO, Num::from(names.len()) - Num::ONE)
+                .unwrap_or_else(|_| MultiInterval::new_empty()),
+            precision: Num::ONE,
+            names,
+        };
+
+        match &var_node.var_type {
+            Type::Bool => points(vec!["false".to_owned(), "true".to_owned()]),
+            Type::Enum(members) => points(members.clone()),
+            Type::Record | Type::String => {
+                unreachable!("A switch on a record or a string is reported before its cases")
+            }
+            Type::Integer { .. } | Type::Float { .. } => {
+                let domain = var_node
+                    .domain
+                    .as_ref()
+                    .and_then(|domain| resolve_interval(constants, domain))
+                    .unwrap_or_else(MultiInterval::new_unbounded);
+                let domain = match var_node.var_type.range() {
+                    Some(range) => domain.restricted_to(&range),
+                    None => domain,
+                };
+
+                Self {
+                    domain,
+                    precision: var_node.var_type.get_precision().unwrap_or(Num::ONE),
+                    names: Vec::new(),
+                }
+            }
+        }
+    }
+
+    fn describe(&self, values: &MultiInterval) -> String {
+        if self.names.is_empty() {
+            return values.to_string();
+        }
+
+        self.names
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                MultiInterval::new_closed_point(Num::from(*i))
+                    .restricted_to(values)
+                    .has_value_with_precision(Num::ONE)
+            })
+            .map(|(_, name)| name)
+            .join(", ")
+    }
+}
+
+/// The interval of the resolved bounds, or `None` if it's invalid, which is reported by `check_interval`
+fn resolve_interval(
+    constants: &[(&str, Option<Num>)],
+    interval: &IntervalNode,
+) -> Option<MultiInterval> {
+    let lo = resolve_number(constants, &interval.lo, &mut Vec::new())?;
+    let hi = resolve_number(constants, &interval.hi, &mut Vec::new())?;
+
+    MultiInterval::new(interval.lo_boundary, lo, hi, interval.hi_boundary).ok()
+}
+
+/// The values the case matches, or `None` if some of them are invalid
+fn check_case_values<'a>(
+    scope: &Scope<'a, '_>,
+    var_node: &VarNode<'a>,
+    case_node: &CaseNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) -> Option<MultiInterval> {
+    let var_name = var_node.var_name;
+    let mut values = Some(MultiInterval::new_empty());
+
+    for case_value in &case_node.values {
+        let value = match (&var_node.var_type, case_value) {
+            (Type::Integer { .. } | Type::Float { .. }, CaseValue::Interval(interval)) => {
+                check_interval(&scope.constants, interval, case_node.label, errors);
+                resolve_interval(&scope.constants, interval)
+            }
+            (Type::Integer { .. } | Type::Float { .. }, CaseValue::Number(number)) => {
+                Some(MultiInterval::new_closed_point(*number))
+            }
+            (Type::Integer { .. } | Type::Float { .. }, CaseValue::Name(name)) => {
+                resolve_number(&scope.constants, &ast::Number::Constant(name), errors)
+                    .map(MultiInterval::new_closed_point)
+            }
+            (Type::Enum(members), CaseValue::Name(name)) => {
+                check_enum_members(scope, var_name, &[name], errors);
+                members
+                    .iter()
+                    .position(|member| member == name)
+                    .map(|i| MultiInterval::new_closed_point(Num::from(i)))
+            }
+            (Type::Bool, CaseValue::Bool(value)) => {
+                Some(MultiInterval::new_closed_point(if *value {
+                    Num::ONE
+                } else {
+                    Num::ZERO
+                }))
+            }
+            (var_type, _) => {
+                let expected = match var_type {
+                    Type::Bool => "true or false",
+                    Type::Enum(_) => "members of the enum",
+                    Type::Integer { .. } | Type::Float { .. } => "numbers, constants or intervals",
+                    Type::Record | Type::String => {
+                        unreachable!(
+                            "A switch on a record or a string is reported before its cases"
+                        )
+                    }
+                };
+                errors.push(type_mismatch(
+                    case_node.label,
+                    format!("the cases of the switch on `{var_name}` can only be {expected}"),
+                ));
+                None
+            }
+        };
+
+        values = values
+            .zip(value)
+            .map(|(values, value)| values.union(&value));
+    }
+
+    values
+}
+
+/// Checks the cases, and warns if a case overlaps the earlier ones,
+/// or if some values aren't matched by any case of a switch without a default
+fn check_switch_node<'a>(
+    scope: &Scope<'a, '_>,
+    switch_node: &SwitchNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let bodies = switch_node
+        .cases
+        .iter()
+        .flat_map(|case_node| &case_node.body)
+        .chain(switch_node.default.iter().flat_map(|default| &default.body));
+    for body_node in bodies {
+        check_if_node(scope, body_node, errors);
+    }
+
+    let Some(var_node) = scope.find_variable(switch_node.var_name) else {
+        errors.push(undefined_variable(switch_node.var_name));
+        return;
+    };
+    if var_node.var_type == Type::Record {
+        errors.push(type_mismatch(
+            switch_node.var_name,
+            format!(
+                "`{}` is a record, only its fields can be switched on",
+                switch_node.var_name
+            ),
+        ));
+        return;
+    }
+    if var_node.var_type == Type::String {
+        errors.push(type_mismatch(
+            switch_node.var_name,
+            format!(
+                "`{}` is a string, it can't be switched on",
+                switch_node.var_name
+            ),
+        ));
+        return;
+    }
+    let switch_domain = SwitchDomain::new(&scope.constants, var_node);
+
+    let case_values = switch_node
+        .cases
+        .iter()
+        .map(|case_node| check_case_values(scope, var_node, case_node, errors))
+        .collect::<Option<Vec<_>>>();
+    let Some(case_values) = case_values else {
+        return;
+    };
+
+    let mut covered = MultiInterval::new_empty();
+    for (case_node, values) in switch_node.cases.iter().zip(case_values) {
+        let values = values.restricted_to(&switch_domain.domain);
+        let overlap = values.restricted_to(&covered);
+
+        if values.has_value_with_precision(switch_domain.precision)
+            && overlap.has_value_with_precision(switch_domain.precision)
+        {
+            let never_matches = !covered
+                .complement_within(&values)
+                .has_value_with_precision(switch_domain.precision);
+            errors.push(SemanticError {
+                fragment: case_node.label,
+                code: code::OVERLAPPING_CASES,
+                message: if never_matches {
+                    "the case never matches, its values are matched by earlier cases".to_owned()
+                } else {
+                    format!(
+                        "the case overlaps earlier cases in {}, which it doesn't match",
+                        switch_domain.describe(&overlap)
+                    )
+                },
+            });
+        }
+
+        covered = covered.union(&values);
+    }
+
+    let uncovered = covered.complement_within(&switch_domain.domain);
+    if switch_node.default.is_none() && uncovered.has_value_with_precision(switch_domain.precision)
+    {
+        errors.push(SemanticError {
+            fragment: switch_node.var_name,
+            code: code::UNCOVERED_VALUES,
+            message: format!(
+                "no case matches {} of `{}`, and the switch has no default",
+                switch_domain.describe(&uncovered),
+                switch_node.var_name
+            ),
+        });
+    } else if switch_node.default.is_none() && var_node.nullable {
+        errors.push(SemanticError {
+            fragment: switch_node.var_name,
+            code: code::UNCOVERED_VALUES,
+            message: format!(
+                "no case matches null of `{}`, and the switch has no default",
+                switch_node.var_name
+            ),
+        });
+    }
+}
+
+/// A use which doesn't match a template is left empty by the expansion, so only the use itself is reported
+fn check_instance<'a>(
+    templates: &[TemplateNode<'a>],
+    instance: &UseNode<'a>,
+    errors: &mut Vec<SemanticError<'a>>,
+) {
+    let Some(template) = templates
+        .iter()
+        .find(|template| template.name == instance.template)
+    else {
+        errors.push(SemanticError {
+            fragment: instance.template,
+            code: code::UNDEFINED_TEMPLATE,
+            message: format!("undefined template `{}`", instance.template),
+        });
+        return;
+    };
+
+    if template.params.len() != instance.args.len() {
+        errors.push(SemanticError {
+            fragment: instance.template,
+            code: code::ARGUMENT_COUNT_MISMATCH,
+            message: format!(
+                "template `{}` takes {} arguments, but {} are given",
+                template.name,
+                template.params.len(),
+                instance.args.len()
+            ),
+        });
+    }
+}
+
+fn check_feature<'a>(
+    root_constants: &[(&'a str, Option<Num>)],
+    root_variables: &[VarNode<'a>],
+    templates: &[TemplateNode<'a>],
+    feature_node: &FeatureNode<'a>,
+) -> Vec<SemanticError<'a>> {
+    let mut errors = Vec::new();
+
+    if let Some(instance) = &feature_node.instance {
+        check_instance(templates, instance, &mut errors);
+    }
+
+    let mut scope = Scope {
+        constants: check_constants(root_constants, &feature_node.constants, &mut errors),
+        variables: root_variables
+            .iter()
+            .chain(feature_node.variables.iter())
+            .collect(),
+        bindings: Vec::new(),
+    };
+
+    check_variables(&scope, &mut errors);
+    check_bindings(&mut scope, &feature_node.bindings, &mut errors);
+    for if_node in &feature_node.if_statements {
+        check_if_node(&scope, if_node, &mut errors);
+    }
+    for switch_node in &feature_node.switch_statements {
+        check_switch_node(&scope, switch_node, &mut errors);
+    }
+    for conditions in &feature_node.assumptions {
+        check_conditions_node(&scope, conditions, &mut errors);
+    }
+
+    errors
+}
+
+/// Finds every undeclared or mistyped variable, constant and enum member,
+/// so the conversion to the IR doesn't have to fail.
+pub fn check_root<'a>(root: &RootNode<'a>) -> CheckResult<'a> {
+    let mut root_errors = Vec::new();
+    let root_constants = check_constants(&[], &root.constants, &mut root_errors);
+    // The shared assumptions can only use the shared variables
+    let root_scope = Scope {
+        constants: root_constants.clone(),
+        variables: root.variables.iter().collect(),
+        bindings: Vec::new(),
+    };
+    for conditions in &root.assumptions {
+        check_conditions_node(&root_scope, conditions, &mut root_errors);
+    }
+    for (i, template) in root.templates.iter().enumerate() {
+        if root.templates[..i]
+            .iter()
+            .any(|other| other.name == template.name)
+        {
+            root_errors.push(SemanticError {
+                fragment: template.name,
+                code: code::DUPLICATE_DECLARATION,
+                message: format!("template `{}` is already defined", template.name),
+            });
+        }
+    }
+
+    let feature_errors = root
+        .features
+        .iter()
+        .map(|feature_node| {
+            check_feature(
+                &root_constants,
+                &root.variables,
+                &root.templates,
+                feature_node,
+            )
+        })
+        .collect();
+
+    CheckResult {
+        root_errors,
+        feature_errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::parser::{expand_templates, root};
+
+    fn error_messages(input: &str) -> Vec<(&str, &'static str, String)> {
+        let (_, mut root_node) = root(input).unwrap();
+        expand_templates(&mut root_node);
+        let CheckResult {
+            root_errors,
+            feature_errors,
+        } = check_root(&root_node);
+
+        root_errors
+            .into_iter()
+            .chain(feature_errors.into_iter().flatten())
+            .map(|error| (error.fragment, error.code, error.message))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::bool("var x: bool\nif(x == true)")]
+    #[case::number("var x: int\nvar y: num\nif(x > 10 && 5 <= y || x in [0, 5] && x + 2 * y < 10)")]
+    #[case::enum_member("var x: enum { a, b }\nif(x == a || x in {a, b})")]
+    #[case::constant(
+        "const LIMIT = 10\nvar x: int in [0, LIMIT]\nif(x > LIMIT && LIMIT * x < 100)"
+    )]
+    #[case::relation("var x: bool\nvar y: bool\nif(x != y)")]
+    #[case::nested(
+        "var x: int\nif(x > 5) { if(x < 10) } else if(x < 0) { if(x > -5) } else { if(x == 1) }"
+    )]
+    #[case::switch(
+        "const LIMIT = 10\nvar x: int in [0, 100]\nswitch(x) { case [0, LIMIT): case LIMIT, (10, 50]: if(x > 20) default: }"
+    )]
+    #[case::switch_covering_every_int(
+        "var x: int in [0, 5]\nswitch(x) { case [0, 2], 3: case (3, 5]: }"
+    )]
+    #[case::switch_on_enum("var x: enum { a, b, c }\nswitch(x) { case a, b: case c: }")]
+    #[case::switch_on_bool("var x: bool\nswitch(x) { case true: default: }")]
+    #[case::value_set("const LIMIT = 10\nvar x: num\nif(x in {3, LIMIT} || x not in {-1, 2.5})")]
+    #[case::modulo("const BATCH = 12\nvar x: int\nif(x % 4 == 0 && x % BATCH != 11)")]
+    #[case::sized_int(
+        "var x: u8 in [1, 255]\nvar y: i16\nswitch(x) { case [0, 100]: case (100, 255]: }"
+    )]
+    #[case::template(
+        "const LIMIT = 150\ntemplate discount(threshold) [ var price: num\nif(price >= threshold) ]\nuse discount(200)\nuse discount(LIMIT)"
+    )]
+    #[case::assumptions(
+        "var age: int\nassume(age >= 16)\n[ var service: int\nassume(service <= age - 16)\nif(service > 10) ]"
+    )]
+    #[case::bindings(
+        "var age: int\nvar service: int\nlet working_age = age >= 18 && age < 60\nlet veteran = working_age && service >= 30\nif(veteran || !working_age)"
+    )]
+    #[case::record(
+        "var order: { price: num in [0, 1000], customer: { vip: bool } }\nif(order.price > 10 && order.customer.vip == true)\nswitch(order.price) { case [0, 500]: case (500, 1000]: }"
+    )]
+    #[case::nullable(
+        "var coupon: int? in [0, 50]\nvar tier: enum { a, b }?\nif(coupon is null || coupon > 10 && tier is not null)\nswitch(tier) { case a: default: }"
+    )]
+    #[case::string(
+        "const MIN_LENGTH = 3\nvar username: string?\nif(len(username) >= MIN_LENGTH && !(username matches \"[a-z_]\") || len(username) in [3, 16])"
+    )]
+    #[case::overlapping_patterns(
+        "var x: string\nif(x matches \"[0-9a-f]\" && x matches \"[a-z]\" || x matches \"[A-Z]\")"
+    )]
+    fn test_check_root_no_errors(#[case] input: &str) {
+        assert_eq!(error_messages(input), Vec::new());
+    }
+
+    #[rstest]
+    #[case::undefined_variable("var x: int\nif(y > 5)", "y", code::UNDEFINED_VARIABLE)]
+    #[case::undefined_variable_in_body(
+        "var x: int\nif(x > 5) { if(y > 5) }",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::undefined_variable_in_else(
+        "var x: int\nif(x > 5) else { if(y > 5) }",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::undefined_constant("var x: int\nif(x > LIMIT)", "LIMIT", code::UNDEFINED_VARIABLE)]
+    #[case::undefined_constant_in_interval(
+        "var x: int\nif(x in [0, LIMIT])",
+        "LIMIT",
+        code::UNDEFINED_CONSTANT
+    )]
+    #[case::bool_in_interval("var x: bool\nif(x in [0, 5])", "x", code::TYPE_MISMATCH)]
+    #[case::bool_compared_with_number("var x: bool\nif(x < 5)", "x", code::TYPE_MISMATCH)]
+    #[case::number_compared_with_bool("var x: int\nif(x == true)", "x", code::TYPE_MISMATCH)]
+    #[case::bool_in_arithmetic("var x: bool\nvar y: int\nif(x + y < 5)", "x", code::TYPE_MISMATCH)]
+    #[case::enum_compared_with_number(
+        "var x: enum { a, b }\nvar y: int\nif(x == y)",
+        "x",
+        code::TYPE_MISMATCH
+    )]
+    #[case::enum_ordering("var x: enum { a, b }\nif(x < a)", "x", code::TYPE_MISMATCH)]
+    #[case::undefined_member("var x: enum { a, b }\nif(x == c)", "c", code::UNDEFINED_ENUM_MEMBER)]
+    #[case::number_as_enum("var x: int\nif(x in {a, b})", "x", code::TYPE_MISMATCH)]
+    #[case::bool_in_value_set("var x: bool\nif(x in {0, 1})", "x", code::TYPE_MISMATCH)]
+    #[case::enum_in_value_set(
+        "var x: enum { a, b }\nif(x not in {1, 2})",
+        "x",
+        code::TYPE_MISMATCH
+    )]
+    #[case::fraction_in_int_value_set("var x: int\nif(x in {1.5, 2})", "x", code::TYPE_MISMATCH)]
+    #[case::off_precision_value_set("var p: num(0.1)\nif(p in {1.55})", "p", code::TYPE_MISMATCH)]
+    #[case::rounded_constant("var p: num(0.1)\nif(p >= 199.95)", "p", code::ROUNDED_CONSTANT)]
+    #[case::rounded_interval_bound(
+        "const MAX = 2.25\nvar x: int\nif(x in [1, MAX])",
+        "x",
+        code::ROUNDED_CONSTANT
+    )]
+    #[case::fractional_constant_in_int_value_set(
+        "const HALF = 0.5\nvar x: int\nif(x not in {HALF})",
+        "x",
+        code::TYPE_MISMATCH
+    )]
+    #[case::undefined_constant_in_value_set(
+        "var x: int\nif(x in {1, LIMIT})",
+        "LIMIT",
+        code::UNDEFINED_CONSTANT
+    )]
+    #[case::num_modulo("var x: num\nif(x % 2 == 0)", "x", code::TYPE_MISMATCH)]
+    #[case::undefined_divisor("var x: int\nif(x % BATCH == 0)", "BATCH", code::UNDEFINED_CONSTANT)]
+    #[case::zero_divisor("var x: int\nif(x % 0 == 0)", "x", code::INVALID_DIVISOR)]
+    #[case::fractional_divisor("var x: int\nif(x % 2.5 == 0)", "x", code::INVALID_DIVISOR)]
+    #[case::remainder_too_big("var x: int\nif(x % 4 == 4)", "x", code::CONSTANT_CONDITION)]
+    #[case::negative_remainder("var x: int\nif(x % 4 != -1)", "x", code::CONSTANT_CONDITION)]
+    #[case::duplicate_variable(
+        "var x: int\nvar x: bool\nif(x > 5)",
+        "x",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::duplicate_constant(
+        "const X = 1\nconst X = 2\nvar y: int\nif(y > X)",
+        "X",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::variable_as_constant(
+        "const x = 1\nvar x: int\nif(x > 5)",
+        "x",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::bool_domain("var x: bool in [0, 1]\nif(x == true)", "x", code::TYPE_MISMATCH)]
+    #[case::invalid_domain(
+        "const MAX = -1\nvar x: int in [0, MAX]\nif(x > 5)",
+        "x",
+        code::INVALID_INTERVAL
+    )]
+    #[case::domain_beyond_type("var x: u8 in [0, 300]\nif(x > 5)", "x", code::INVALID_INTERVAL)]
+    #[case::cancelled_out("var x: int\nif(x + 1 < x)", "x", code::CONSTANT_CONDITION)]
+    #[case::undefined_switch_variable(
+        "var x: int\nswitch(y) { default: }",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::undefined_constant_in_case(
+        "var x: int\nswitch(x) { case [0, LIMIT): default: }",
+        "LIMIT",
+        code::UNDEFINED_CONSTANT
+    )]
+    #[case::undefined_variable_in_case(
+        "var x: int\nswitch(x) { case 1: if(y > 5) default: }",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::bool_case_of_number(
+        "var x: int\nswitch(x) { case true: default: }",
+        "true",
+        code::TYPE_MISMATCH
+    )]
+    #[case::number_case_of_enum(
+        "var x: enum { a, b }\nswitch(x) { case a, 1: default: }",
+        "a, 1",
+        code::TYPE_MISMATCH
+    )]
+    #[case::undefined_member_in_case(
+        "var x: enum { a, b }\nswitch(x) { case c: default: }",
+        "c",
+        code::UNDEFINED_ENUM_MEMBER
+    )]
+    #[case::overlapping_cases(
+        "var x: int\nswitch(x) { case [0, 10): case [5, 20): default: }",
+        "[5, 20)",
+        code::OVERLAPPING_CASES
+    )]
+    #[case::uncovered_values(
+        "var x: int in [0, 10]\nswitch(x) { case [0, 5): case (5, 10]: }",
+        "x",
+        code::UNCOVERED_VALUES
+    )]
+    #[case::uncovered_null(
+        "var x: bool?\nswitch(x) { case true: case false: }",
+        "x",
+        code::UNCOVERED_VALUES
+    )]
+    #[case::uncovered_members(
+        "var x: enum { a, b, c }\nswitch(x) { case a, c: }",
+        "x",
+        code::UNCOVERED_VALUES
+    )]
+    #[case::undefined_template(
+        "template discount(threshold) [ var price: num\nif(price >= threshold) ]\nuse shipping(10)",
+        "shipping",
+        code::UNDEFINED_TEMPLATE
+    )]
+    #[case::argument_count_mismatch(
+        "template discount(threshold) [ var price: num\nif(price >= threshold) ]\nuse discount(10, 20)",
+        "discount",
+        code::ARGUMENT_COUNT_MISMATCH
+    )]
+    #[case::duplicate_template(
+        "template a(x) [ var y: int\nif(y > x) ]\ntemplate a(x) [ var y: int\nif(y < x) ]\nuse a(1)",
+        "a",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::parameter_shadowing_constant(
+        "const x = 1\ntemplate a(x) [ var y: int\nif(y > x) ]\nuse a(2)",
+        "x",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::undefined_variable_in_assumption(
+        "var x: int\nassume(y > 5)\nif(x > 5)",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::feature_variable_in_shared_assumption(
+        "var x: int\nassume(y > x)\n[ var y: int\nif(y > 5) ]",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::undefined_binding("var x: int\nif(big)", "big", code::UNDEFINED_BINDING)]
+    #[case::binding_used_before_definition(
+        "var x: int\nlet a = b && x > 1\nlet b = x < 5\nif(a)",
+        "b",
+        code::UNDEFINED_BINDING
+    )]
+    #[case::recursive_binding(
+        "var x: int\nlet a = a || x > 1\nif(a)",
+        "a",
+        code::UNDEFINED_BINDING
+    )]
+    #[case::variable_as_binding("var x: bool\nif(x)", "x", code::TYPE_MISMATCH)]
+    #[case::binding_shadowing_variable(
+        "var x: int\nlet x = x > 5\nif(x)",
+        "x",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::error_in_binding_reported_at_definition(
+        "var x: int\nlet big = y > 10\nif(big)\nif(!big && x > 5)",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::undefined_field(
+        "var order: { price: num }\nif(order.weight > 5)",
+        "order.weight",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::field_of_non_record(
+        "var price: num\nif(price.cents > 5)",
+        "price.cents",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::record_compared_with_number(
+        "var order: { price: num }\nif(order > 5)",
+        "order",
+        code::TYPE_MISMATCH
+    )]
+    #[case::switch_on_record(
+        "var order: { price: num }\nswitch(order) { default: }",
+        "order",
+        code::TYPE_MISMATCH
+    )]
+    #[case::duplicate_field(
+        "var order: { price: num, price: int }\nif(order.price > 5)",
+        "price",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::zero_precision("var p: num(0.0)\nif(p > 1)", "p", code::INVALID_PRECISION)]
+    #[case::negative_precision("var p: num(-0.5)\nif(p > 1)", "p", code::INVALID_PRECISION)]
+    #[case::duplicate_enum_member(
+        "var t: enum { a, a, b }\nif(t == a)",
+        "t",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::duplicate_enum_member_of_field(
+        "var order: { tier: enum { gold, silver, gold } }\nif(order.tier == gold)",
+        "tier",
+        code::DUPLICATE_DECLARATION
+    )]
+    #[case::null_check_of_non_nullable("var x: int\nif(x is null)", "x", code::TYPE_MISMATCH)]
+    #[case::null_check_of_undefined_variable(
+        "var x: int?\nif(y is not null)",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::length_of_non_string("var x: int\nif(len(x) > 5)", "x", code::TYPE_MISMATCH)]
+    #[case::pattern_of_undefined_variable(
+        "var x: string\nif(y matches \"[0-9]\")",
+        "y",
+        code::UNDEFINED_VARIABLE
+    )]
+    #[case::undefined_length_constant(
+        "var x: string\nif(len(x) <= MAX_LENGTH)",
+        "MAX_LENGTH",
+        code::UNDEFINED_CONSTANT
+    )]
+    #[case::string_compared_with_number("var x: string\nif(x > 5)", "x", code::TYPE_MISMATCH)]
+    #[case::switch_on_string("var x: string\nswitch(x) { default: }", "x", code::TYPE_MISMATCH)]
+    #[case::negative_length("var x: string\nif(len(x) == -1)", "x", code::NEGATIVE_LENGTH)]
+    #[case::too_long_length("var x: string\nif(len(x) > 10000000000)", "x", code::LENGTH_TOO_LONG)]
+    #[case::too_long_length_interval(
+        "const MAX_LENGTH = 20000\nvar x: string\nif(len(x) in [3, MAX_LENGTH])",
+        "x",
+        code::LENGTH_TOO_LONG
+    )]
+    #[case::negative_length_interval(
+        "var x: string\nif(len(x) in [-5, 3])",
+        "x",
+        code::NEGATIVE_LENGTH
+    )]
+    #[case::disjoint_patterns(
+        "var x: string\nif(x matches \"[0-9]\" && x matches \"[a-z]\")",
+        "x",
+        code::UNSATISFIABLE_PATTERNS
+    )]
+    #[case::disjoint_nested_patterns(
+        "var x: string\nif(x matches \"[0-9a-f]\" && (len(x) > 2 && x matches \"[g-z_]\"))",
+        "x",
+        code::UNSATISFIABLE_PATTERNS
+    )]
+    fn test_check_root_error(
+        #[case] input: &str,
+        #[case] fragment: &str,
+        #[case] expected_code: &str,
+    ) {
+        let errors = error_messages(input);
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(fragment, code, _)| (*fragment, *code))
+                .collect::<Vec<_>>(),
+            vec![(fragment, expected_code)],
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_check_root_errors_per_feature() {
+        let input = "const A = B\nvar x: int\n[ if(x > 5) ]\n[ if(y > 5) ]";
+        let (_, root_node) = root(input).unwrap();
+        let CheckResult {
+            root_errors,
+            feature_errors,
+        } = check_root(&root_node);
+
+        assert_eq!(
+            root_errors
+                .iter()
+                .map(|error| error.fragment)
+                .collect::<Vec<_>>(),
+            vec!["B"]
+        );
+        assert_eq!(
+            feature_errors
+                .iter()
+                .map(|errors| errors.iter().map(|error| error.fragment).collect())
+                .collect::<Vec<Vec<_>>>(),
+            vec![vec![], vec!["y"]]
+        );
+    }
+
+    #[test]
+    fn test_check_root_switch_warnings() {
+        let input = "var x: num(0.5) in [0, 10]\nvar y: enum { a, b, c }\n[ switch(x) { case [0, 5]: case 2, 3: case [4, 6): } ]\n[ switch(y) { case a: } ]";
+
+        assert_eq!(
+            error_messages(input)
+                .into_iter()
+                .map(|(_, _, message)| message)
+                .collect::<Vec<_>>(),
+            vec![
+                "the case never matches, its values are matched by earlier cases".to_owned(),
+                "the case overlaps earlier cases in [4, 5], which it doesn't match".to_owned(),
+                "no case matches [6, 10] of `x`, and the switch has no default".to_owned(),
+                "no case matches b, c of `y`, and the switch has no default".to_owned(),
+            ]
+        );
+    }
+}
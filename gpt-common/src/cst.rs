@@ -0,0 +1,393 @@
+//! A lossless concrete syntax tree, which keeps the comments and the layout of the source.
+//!
+//! Writing the tree back gives the exact source it was parsed from, so tools like the formatter can work on it.
+
+use std::fmt::{self, Display};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TriviaKind {
+    Whitespace,
+    /// `// comment` without the line break after it
+    LineComment,
+    /// `/* comment */`
+    BlockComment,
+}
+
+/// The parts of the source between the tokens, which the parser throws away
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Trivia<'a> {
+    pub kind: TriviaKind,
+    pub text: &'a str,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TokenKind {
+    /// A keyword or a name, like `if`, `var` or `price`
+    Word,
+    Number,
+    /// A text between double quotes, with the quotes
+    String,
+    /// An operator or a bracket, like `&&`, `<=` or `(`
+    Punctuation,
+    /// A character which can't start any token
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// The comments and whitespace before the token
+    pub leading_trivia: Vec<Trivia<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GroupKind {
+    /// `[ ... ]` around a feature
+    Feature,
+    /// `{ ... }` around the body of an if, else if or else
+    Body,
+    /// `( ... )` around conditions or the precision of a type
+    Parentheses,
+    /// `[0, 10)` after `in`, `not in` or in the values of a case
+    Interval,
+    /// `{ bronze, silver }` after `enum`, `in` or `not in`
+    MemberSet,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CstNode<'a> {
+    Token(Token<'a>),
+    /// The first and the last child are the brackets, the last one is missing if the group isn't closed
+    Group {
+        kind: GroupKind,
+        children: Vec<Self>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cst<'a> {
+    pub nodes: Vec<CstNode<'a>>,
+    /// The comments and whitespace after the last token
+    pub trailing_trivia: Vec<Trivia<'a>>,
+}
+
+/// The longer ones come first, so `<=>` isn't split into `<=` and `>`
+const PUNCTUATIONS: [&str; 27] = [
+    "<=>", "&&", "||", "==", "!=", "<=", ">=", "=>", "<", ">", "=", "!", "^", "+", "-", "*", "%",
+    "@", ",", ":", "?", "(", ")", "[", "]", "{", "}",
+];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The length of the trivia at the start of the input, if there is any
+fn trivia(input: &str) -> Option<(TriviaKind, usize)> {
+    if input.starts_with("//") {
+        return Some((
+            TriviaKind::LineComment,
+            input.find('\n').unwrap_or(input.len()),
+        ));
+    }
+    if input.starts_with("/*") {
+        return Some((
+            TriviaKind::BlockComment,
+            input.find("*/").map_or(input.len(), |end| end + 2),
+        ));
+    }
+
+    let whitespace_length = input.len() - input.trim_start().len();
+    (whitespace_length > 0).then_some((TriviaKind::Whitespace, whitespace_length))
+}
+
+/// The length of the token at the start of the input, which has no trivia at its start
+fn token(input: &str) -> (TokenKind, usize) {
+    let first = input.chars().next().expect("The input isn't empty");
+
+    if first.is_ascii_digit() {
+        let length = input
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(input.len());
+        (TokenKind::Number, length)
+    } else if is_word_char(first) {
+        // A path to a field of a record, like `order.price`, is a single word
+        let length = input
+            .find(|c| !(is_word_char(c) || c == '.'))
+            .unwrap_or(input.len());
+        (TokenKind::Word, length)
+    } else if first == '"' {
+        let length = input[1..]
+            .find(['"', '\n'])
+            .map_or(input.len(), |end| end + 2);
+        (TokenKind::String, length.min(input.len()))
+    } else if let Some(punctuation) = PUNCTUATIONS.iter().find(|x| input.starts_with(*x)) {
+        (TokenKind::Punctuation, punctuation.len())
+    } else {
+        (TokenKind::Unknown, first.len_utf8())
+    }
+}
+
+/// Splits the input into tokens, with the trivia attached to the token after it
+fn tokenize(input: &str) -> (Vec<Token>, Vec<Trivia>) {
+    let mut tokens = Vec::new();
+    let mut leading_trivia = Vec::new();
+    let mut input = input;
+
+    while !input.is_empty() {
+        if let Some((kind, length)) = trivia(input) {
+            leading_trivia.push(Trivia {
+                kind,
+                text: &input[..length],
+            });
+            input = &input[length..];
+            continue;
+        }
+
+        let (kind, length) = token(input);
+        tokens.push(Token {
+            kind,
+            text: &input[..length],
+            leading_trivia: std::mem::take(&mut leading_trivia),
+        });
+        input = &input[length..];
+    }
+
+    (tokens, leading_trivia)
+}
+
+/// Builds the tree of groups from the tokens.
+/// Brackets which don't close the innermost group are kept as simple tokens.
+fn build_tree(tokens: Vec<Token>) -> Vec<CstNode> {
+    // The open groups, the last one is the innermost, the first one is the root
+    let mut stack: Vec<(Option<GroupKind>, Vec<CstNode>)> = vec![(None, Vec::new())];
+    let mut previous_text = "";
+
+    for token in tokens {
+        let text = token.text;
+        let innermost = stack.last().and_then(|(kind, _)| *kind);
+        let after_in = matches!(previous_text, "in" | "enum" | ":");
+
+        let opened = match text {
+            "[" | "(" if matches!(previous_text, "in" | "case" | ",") => Some(GroupKind::Interval),
+            "(" => Some(GroupKind::Parentheses),
+            "[" => Some(GroupKind::Feature),
+            "{" if after_in => Some(GroupKind::MemberSet),
+            "{" => Some(GroupKind::Body),
+            _ => None,
+        };
+        let closes = matches!(
+            (innermost, text),
+            (Some(GroupKind::Interval), "]" | ")")
+                | (Some(GroupKind::Parentheses), ")")
+                | (Some(GroupKind::Feature), "]")
+                | (Some(GroupKind::Body | GroupKind::MemberSet), "}")
+        );
+
+        if let Some(kind) = opened {
+            stack.push((Some(kind), vec![CstNode::Token(token)]));
+        } else if closes {
+            let (kind, mut children) = stack.pop().expect("The innermost group is on the stack");
+            children.push(CstNode::Token(token));
+            stack
+                .last_mut()
+                .expect("The root is never closed")
+                .1
+                .push(CstNode::Group {
+                    kind: kind.expect("The root is never closed"),
+                    children,
+                });
+        } else {
+            stack
+                .last_mut()
+                .expect("The root is never closed")
+                .1
+                .push(CstNode::Token(token));
+        }
+
+        previous_text = text;
+    }
+
+    // The groups which aren't closed end at the end of the input
+    while stack.len() > 1 {
+        let (kind, children) = stack.pop().expect("There are at least two groups");
+        stack
+            .last_mut()
+            .expect("The root is never closed")
+            .1
+            .push(CstNode::Group {
+                kind: kind.expect("The root is never closed"),
+                children,
+            });
+    }
+
+    stack.pop().expect("The root is never closed").1
+}
+
+/// Parses any input into a tree, even the ones with syntax errors
+pub fn parse_cst(input: &str) -> Cst {
+    let (tokens, trailing_trivia) = tokenize(input);
+
+    Cst {
+        nodes: build_tree(tokens),
+        trailing_trivia,
+    }
+}
+
+impl Display for Trivia<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for trivia in &self.leading_trivia {
+            write!(f, "{trivia}")?;
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Display for CstNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Token(token) => write!(f, "{token}"),
+            Self::Group { children, .. } => {
+                for child in children {
+                    write!(f, "{child}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes back the exact source the tree was parsed from
+impl Display for Cst<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{node}")?;
+        }
+        for trivia in &self.trailing_trivia {
+            write!(f, "{trivia}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("")]
+    #[case("   \n")]
+    #[case(
+        "/* Shipping fee */\nvar x: num(0.1) in [0, 10)\n\nif(x >= 5 && x not in (7,8]) // R1\n"
+    )]
+    #[case(
+        "[\n  var tier: enum { bronze, gold }\n  if(tier in {gold}) => \"free\" {\n  } else {}\n]"
+    )]
+    #[case("if(x > ) { ] \"unterminated\n/* unterminated")]
+    #[case("if(árvíztűrő == true) § ")]
+    fn test_parse_cst_lossless(#[case] input: &str) {
+        assert_eq!(parse_cst(input).to_string(), input);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let (tokens, trailing_trivia) = tokenize("// R1\nif(x<=-2.5<=>y^z%2) /* end */");
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| (token.kind, token.text))
+                .collect::<Vec<_>>(),
+            vec![
+                (TokenKind::Word, "if"),
+                (TokenKind::Punctuation, "("),
+                (TokenKind::Word, "x"),
+                (TokenKind::Punctuation, "<="),
+                (TokenKind::Punctuation, "-"),
+                (TokenKind::Number, "2.5"),
+                (TokenKind::Punctuation, "<=>"),
+                (TokenKind::Word, "y"),
+                (TokenKind::Punctuation, "^"),
+                (TokenKind::Word, "z"),
+                (TokenKind::Punctuation, "%"),
+                (TokenKind::Number, "2"),
+                (TokenKind::Punctuation, ")"),
+            ]
+        );
+        assert_eq!(
+            tokens[0].leading_trivia,
+            vec![
+                Trivia {
+                    kind: TriviaKind::LineComment,
+                    text: "// R1"
+                },
+                Trivia {
+                    kind: TriviaKind::Whitespace,
+                    text: "\n"
+                }
+            ]
+        );
+        assert_eq!(
+            trailing_trivia,
+            vec![
+                Trivia {
+                    kind: TriviaKind::Whitespace,
+                    text: " "
+                },
+                Trivia {
+                    kind: TriviaKind::BlockComment,
+                    text: "/* end */"
+                }
+            ]
+        );
+    }
+
+    fn group_kinds(nodes: &[CstNode]) -> Vec<GroupKind> {
+        nodes
+            .iter()
+            .flat_map(|node| match node {
+                CstNode::Token(_) => Vec::new(),
+                CstNode::Group { kind, children } => {
+                    let mut kinds = vec![*kind];
+                    kinds.append(&mut group_kinds(children));
+                    kinds
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_cst_groups() {
+        let cst = parse_cst(
+            "[ var x: enum { a, b }\nvar y: num(0.1) in [0, 1)\nif((y in (0, 1]) && x in {a}) { } ]\nswitch(y) { case [0, 1), (2, 3]: }",
+        );
+
+        assert_eq!(
+            group_kinds(&cst.nodes),
+            vec![
+                GroupKind::Feature,
+                GroupKind::MemberSet,
+                GroupKind::Parentheses,
+                GroupKind::Interval,
+                GroupKind::Parentheses,
+                GroupKind::Parentheses,
+                GroupKind::Interval,
+                GroupKind::MemberSet,
+                GroupKind::Body,
+                GroupKind::Parentheses,
+                GroupKind::Body,
+                GroupKind::Interval,
+                GroupKind::Interval,
+            ]
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::{fmt::Display, ops::Range};
+
+use nom::error::{ErrorKind, VerboseError, VerboseErrorKind};
+use serde::Serialize;
+
+/// The code of a diagnostic, so tools can tell the kinds of problems apart
+pub mod code {
+    /// The input can't be parsed at the location
+    pub const UNEXPECTED_INPUT: &str = "E0001";
+    /// A specific character was expected at the location
+    pub const EXPECTED_CHARACTER: &str = "E0002";
+    /// The input ended before the end of a statement
+    pub const UNEXPECTED_END_OF_INPUT: &str = "E0003";
+    /// There is input after the last statement, which can't be parsed
+    pub const EXPECTED_END_OF_INPUT: &str = "E0004";
+    /// A number has too many digits to be represented, like `99999999999999999999999999999999`,
+    /// or `0.000000000000000000000000000000001`
+    pub const NUMBER_OUT_OF_RANGE: &str = "E0005";
+    /// A variable is used, but it isn't declared
+    pub const UNDEFINED_VARIABLE: &str = "E0101";
+    /// A constant is used, but it isn't defined
+    pub const UNDEFINED_CONSTANT: &str = "E0102";
+    /// An enum variable is compared with a member it doesn't have
+    pub const UNDEFINED_ENUM_MEMBER: &str = "E0103";
+    /// A variable is used in a way its type doesn't allow, like a bool variable in an interval
+    pub const TYPE_MISMATCH: &str = "E0104";
+    /// A variable, a constant or a template is declared more than once
+    pub const DUPLICATE_DECLARATION: &str = "E0105";
+    /// The lower bound of an interval is greater than its upper bound
+    pub const INVALID_INTERVAL: &str = "E0106";
+    /// The variables of a condition cancel each other out, so it doesn't depend on them
+    pub const CONSTANT_CONDITION: &str = "E0107";
+    /// The divisor of a modulo condition isn't a positive integer
+    pub const INVALID_DIVISOR: &str = "E0108";
+    /// A template is used, but it isn't defined
+    pub const UNDEFINED_TEMPLATE: &str = "E0109";
+    /// A template is used with a different number of arguments than its parameters
+    pub const ARGUMENT_COUNT_MISMATCH: &str = "E0110";
+    /// A name is used as a condition, but it isn't defined with `let`
+    pub const UNDEFINED_BINDING: &str = "E0111";
+    /// The length of a string is compared with a negative number
+    pub const NEGATIVE_LENGTH: &str = "E0112";
+    /// A string has to match character classes without a common character, which only the empty string does
+    pub const UNSATISFIABLE_PATTERNS: &str = "E0113";
+    /// The length of a string is compared with a number too large to make strings of that length
+    pub const LENGTH_TOO_LONG: &str = "E0114";
+    /// The precision of a number type isn't positive, like `num(0)`
+    pub const INVALID_PRECISION: &str = "E0115";
+    /// A file can't be read, like an imported file which doesn't exist
+    pub const UNREADABLE_FILE: &str = "E0201";
+    /// A file imports itself, directly or through the files it imports
+    pub const IMPORT_CYCLE: &str = "E0202";
+    /// A case of a switch matches values of earlier cases, which it never gets
+    pub const OVERLAPPING_CASES: &str = "W0101";
+    /// Some values of the variable of a switch without a default aren't matched by any of its cases
+    pub const UNCOVERED_VALUES: &str = "W0102";
+    /// A constant of a condition is between the values the variable can take with its precision, so it's rounded to them
+    pub const ROUNDED_CONSTANT: &str = "W0103";
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    /// The codes of warnings start with `W`, and the codes of errors with `E`
+    pub fn of_code(code: &str) -> Self {
+        if code.starts_with('W') {
+            Self::Warning
+        } else {
+            Self::Error
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem in a source file, with the location where it is
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    /// The byte range of the problematic part of the source
+    pub span: Range<usize>,
+    /// The line of the start of the span, starting from 1
+    pub line: usize,
+    /// The column of the start of the span in characters, starting from 1
+    pub column: usize,
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic for `span`, calculating its line and column in `source`
+    pub fn new(
+        file: &str,
+        source: &str,
+        span: Range<usize>,
+        severity: Severity,
+        code: &'static str,
+        message: String,
+    ) -> Self {
+        let before_span = &source[..span.start];
+        let line = before_span.matches('\n').count() + 1;
+        let column = before_span
+            .rsplit_once('\n')
+            .map_or(before_span, |(_, line)| line)
+            .chars()
+            .count()
+            + 1;
+
+        Self {
+            file: file.to_owned(),
+            span,
+            line,
+            column,
+            severity,
+            code,
+            message,
+        }
+    }
+
+    /// Creates a diagnostic for `fragment`, which has to be a part of `source`, like the name of a variable in the AST
+    pub fn for_fragment(
+        file: &str,
+        source: &str,
+        fragment: &str,
+        severity: Severity,
+        code: &'static str,
+        message: String,
+    ) -> Option<Self> {
+        let start = (fragment.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+        let end = start + fragment.len();
+
+        (end <= source.len()).then(|| Self::new(file, source, start..end, severity, code, message))
+    }
+
+    /// Converts the error of the parser into a diagnostic pointing to the token where parsing failed
+    pub fn from_parse_error(file: &str, source: &str, error: &VerboseError<&str>) -> Self {
+        let (remaining, kind) = error
+            .errors
+            .iter()
+            .find(|(_, kind)| !matches!(kind, VerboseErrorKind::Context(_)))
+            .or_else(|| error.errors.first())
+            .expect("A parse error has at least one error in it");
+        let context = error.errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(context) => Some(*context),
+            _ => None,
+        });
+
+        let start = source.len() - remaining.len();
+        let token = next_token(remaining);
+        let found = if remaining.is_empty() {
+            "end of input".to_owned()
+        } else {
+            format!("'{token}'")
+        };
+
+        let (code, message) = match kind {
+            VerboseErrorKind::Char(expected) => (
+                code::EXPECTED_CHARACTER,
+                format!("expected '{expected}', found {found}"),
+            ),
+            VerboseErrorKind::Nom(ErrorKind::TooLarge) => (
+                code::NUMBER_OUT_OF_RANGE,
+                format!("{found} has too many digits to be a number"),
+            ),
+            VerboseErrorKind::Nom(ErrorKind::Eof) => (
+                code::EXPECTED_END_OF_INPUT,
+                format!("expected end of input, found {found}"),
+            ),
+            _ if remaining.is_empty() => (
+                code::UNEXPECTED_END_OF_INPUT,
+                "unexpected end of input".to_owned(),
+            ),
+            _ => (code::UNEXPECTED_INPUT, format!("unexpected {found}")),
+        };
+        // The number is wrong by itself, wherever it is
+        let message = match context {
+            Some(context) if code != code::NUMBER_OUT_OF_RANGE => format!("{message} in {context}"),
+            _ => message,
+        };
+
+        Self::new(
+            file,
+            source,
+            start..start + token.len(),
+            Severity::Error,
+            code,
+            message,
+        )
+    }
+}
+
+/// The text until the next whitespace, or the next character if it isn't alphanumeric
+fn next_token(input: &str) -> &str {
+    match input.chars().next() {
+        None => "",
+        Some(c) if c.is_alphanumeric() || c == '_' || c == '.' => {
+            let end = input
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(input.len());
+            &input[..end]
+        }
+        Some(c) => &input[..c.len_utf8()],
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}[{}]: {}",
+            self.file, self.line, self.column, self.severity, self.code, self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("x > 5", 0, 1, 1)]
+    #[case("x > 5", 4, 1, 5)]
+    #[case("var x: int\nif(x > 5)", 11, 2, 1)]
+    #[case("var x: int\nif(x > 5)", 18, 2, 8)]
+    #[case("// árvíztűrő\nx", 17, 2, 1)]
+    fn test_diagnostic_position(
+        #[case] source: &str,
+        #[case] start: usize,
+        #[case] line: usize,
+        #[case] column: usize,
+    ) {
+        let diagnostic = Diagnostic::new(
+            "input.gpt",
+            source,
+            start..start,
+            Severity::Error,
+            code::UNEXPECTED_INPUT,
+            String::new(),
+        );
+
+        assert_eq!((diagnostic.line, diagnostic.column), (line, column));
+    }
+
+    #[test]
+    fn test_for_fragment() {
+        let source = "var x: int\nif(price > 5)";
+        let diagnostic = Diagnostic::for_fragment(
+            "input.gpt",
+            source,
+            &source[14..19],
+            Severity::Error,
+            code::UNDEFINED_VARIABLE,
+            "undefined variable `price`".to_owned(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            (diagnostic.span, diagnostic.line, diagnostic.column),
+            (14..19, 2, 4)
+        );
+        assert_eq!(
+            Diagnostic::for_fragment(
+                "input.gpt",
+                source,
+                "price",
+                Severity::Error,
+                code::UNDEFINED_VARIABLE,
+                String::new()
+            ),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case("price", "price")]
+    #[case("12.5)", "12.5")]
+    #[case(") {", ")")]
+    #[case("", "")]
+    fn test_next_token(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(next_token(input), expected);
+    }
+
+    #[test]
+    fn test_from_parse_error() {
+        let source = "var x: int\nif(x > 5 {";
+        let error = VerboseError {
+            errors: vec![
+                (&source[20..], VerboseErrorKind::Char(')')),
+                (&source[11..], VerboseErrorKind::Context("if statement")),
+            ],
+        };
+
+        assert_eq!(
+            Diagnostic::from_parse_error("input.gpt", source, &error),
+            Diagnostic {
+                file: "input.gpt".to_owned(),
+                span: 20..21,
+                line: 2,
+                column: 10,
+                severity: Severity::Error,
+                code: code::EXPECTED_CHARACTER,
+                message: "expected ')', found '{' in if statement".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_parse_error_number_out_of_range() {
+        let source = "var l: int\nif(l > 99999999999999999999999999999999)";
+        let error = VerboseError {
+            errors: vec![
+                (&source[18..], VerboseErrorKind::Nom(ErrorKind::TooLarge)),
+                (&source[14..], VerboseErrorKind::Context("condition")),
+            ],
+        };
+
+        let diagnostic = Diagnostic::from_parse_error("input.gpt", source, &error);
+
+        assert_eq!(diagnostic.span, 18..50);
+        assert_eq!(
+            diagnostic.to_string(),
+            "input.gpt:2:8: error[E0005]: '99999999999999999999999999999999' has too many digits to be a number"
+        );
+    }
+
+    #[test]
+    fn test_from_parse_error_end_of_input() {
+        let source = "if(x >";
+        let error = VerboseError {
+            errors: vec![(&source[6..], VerboseErrorKind::Nom(ErrorKind::Tag))],
+        };
+
+        let diagnostic = Diagnostic::from_parse_error("input.gpt", source, &error);
+
+        assert_eq!(diagnostic.span, 6..6);
+        assert_eq!(diagnostic.code, code::UNEXPECTED_END_OF_INPUT);
+        assert_eq!(
+            diagnostic.to_string(),
+            "input.gpt:1:7: error[E0003]: unexpected end of input"
+        );
+    }
+}
@@ -1,11 +1,19 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Display},
+    ops::{Deref, DerefMut},
 };
 
-use serde::Serialize;
+use serde::{ser::SerializeMap, Serialize};
 
-use crate::interval::{Intersectable, Interval, MultiInterval};
+use crate::{
+    bva::Bva,
+    interval::{Intersectable, Interval, MultiInterval},
+    number::Num,
+    parser::ast::CharClass,
+    test_value_generator::test_value_for_string,
+    util::UniquesVec,
+};
 
 #[derive(Clone, PartialEq, Eq, Debug, Copy)]
 pub enum BoolExpression {
@@ -34,22 +42,295 @@ pub enum Expression {
     //   MissingVariable,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct IntervalDTO {
     pub interval: MultiInterval,
-    pub precision: f32,
+    pub precision: Num,
+    pub is_constant: bool,
+    /// The values the variable can take, test values are never chosen outside of it
+    pub domain: MultiInterval,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EnumDTO {
+    /// The members which satisfy the condition
+    pub values: Vec<String>,
+    /// Every member of the enum type
+    pub members: Vec<String>,
     pub is_constant: bool,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// `x in {3, 7}` or `x not in {3, 7}`, tested with one value per member
+/// and a few representative non-members instead of the boundaries of every member
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SetDTO {
+    /// Sorted and without duplicates
+    pub values: Vec<Num>,
+    pub is_member: bool,
+    pub precision: Num,
+    pub is_constant: bool,
+    pub domain: MultiInterval,
+}
+
+impl SetDTO {
+    /// The values of the set inside the domain, as single points
+    pub fn members(&self) -> MultiInterval {
+        MultiInterval::from_intervals(
+            self.values
+                .iter()
+                .map(|value| Interval::new_closed_point(*value))
+                .collect(),
+        )
+        .restricted_to(&self.domain)
+    }
+
+    /// The values of the domain around the set: the ones below the smallest member,
+    /// the ones in the middle gap and the ones above the largest member
+    pub fn non_members(&self) -> MultiInterval {
+        let gaps = self
+            .members()
+            .complement_within(&self.domain)
+            .calc_in(self.precision)
+            .intervals;
+
+        MultiInterval::from_intervals(match gaps.len() {
+            0..=3 => gaps,
+            len => vec![gaps[0], gaps[len / 2], gaps[len - 1]],
+        })
+    }
+
+    /// The values satisfying the condition and the ones which don't
+    pub fn satisfying_and_violating(&self) -> (MultiInterval, MultiInterval) {
+        if self.is_member {
+            (self.members(), self.non_members())
+        } else {
+            (self.non_members(), self.members())
+        }
+    }
+}
+
+/// `x % divisor == remainder`, or `!=` if `should_equal` is false
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ModuloDTO {
+    pub divisor: Num,
+    pub remainder: Num,
+    pub should_equal: bool,
+}
+
+impl ModuloDTO {
+    pub fn holds_for(&self, value: Num) -> bool {
+        (value.rem_euclid(self.divisor) == self.remainder) == self.should_equal
+    }
+}
+
+/// The most values searched for a representative from each boundary,
+/// so a huge divisor doesn't stall the generation
+const MAX_SEARCH_LENGTH: usize = 100_000;
+
+/// An interval with modulo conditions on top of it, like `year in [1900, 2100] && year % 4 == 0`.
+///
+/// It is tested with the values nearest to the boundaries of the interval,
+/// which satisfy or violate the modulo conditions.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PeriodicDTO {
+    pub interval: MultiInterval,
+    pub modulos: Vec<ModuloDTO>,
+    pub precision: Num,
+    pub is_constant: bool,
+    pub domain: MultiInterval,
+}
+
+impl PeriodicDTO {
+    pub fn holds_for(&self, value: Num) -> bool {
+        self.modulos.iter().all(|modulo| modulo.holds_for(value))
+    }
+
+    /// The values satisfying every modulo condition nearest to each boundary of the interval,
+    /// and for every modulo condition the nearest values violating only that one
+    pub fn satisfying_and_violating(&self) -> (MultiInterval, MultiInterval) {
+        let mut satisfying = Vec::new();
+        let mut violating = Vec::new();
+        // A whole period of the modulo conditions
+        let search_length = self
+            .modulos
+            .iter()
+            .map(|modulo| modulo.divisor / self.precision)
+            .product::<Num>()
+            .min(Num::from(MAX_SEARCH_LENGTH))
+            .to_f64() as usize;
+
+        for interval in self
+            .interval
+            .restricted_to(&self.domain)
+            .calc_in(self.precision)
+            .intervals
+        {
+            for (start, step) in self.search_starts(&interval) {
+                let values = (0..search_length)
+                    .map(|i| start + Num::from(i) * step)
+                    .take_while(|value| interval.contains_point(*value));
+
+                satisfying.extend(values.clone().find(|value| self.holds_for(*value)));
+                for (i, modulo) in self.modulos.iter().enumerate() {
+                    violating.extend(values.clone().find(|value| {
+                        !modulo.holds_for(*value)
+                            && self
+                                .modulos
+                                .iter()
+                                .enumerate()
+                                .all(|(j, other)| i == j || other.holds_for(*value))
+                    }));
+                }
+            }
+        }
+
+        let points = |values: Vec<Num>| {
+            MultiInterval::from_intervals(
+                values.into_iter().map(Interval::new_closed_point).collect(),
+            )
+        };
+
+        (points(satisfying), points(violating))
+    }
+
+    /// Where the searches for the representatives start and the steps they take:
+    /// up from the lower bound and down from the upper bound, or both ways from 0 if neither is finite
+    fn search_starts(&self, interval: &Interval) -> Vec<(Num, Num)> {
+        if !interval.lo.is_finite() && !interval.hi.is_finite() {
+            return vec![
+                (Num::ZERO, self.precision),
+                (-self.precision, -self.precision),
+            ];
+        }
+
+        interval
+            .lo
+            .is_finite()
+            .then_some((interval.lo, self.precision))
+            .into_iter()
+            .chain(
+                interval
+                    .hi
+                    .is_finite()
+                    .then_some((interval.hi, -self.precision)),
+            )
+            .collect()
+    }
+}
+
+/// Every character of the string is in the class, or some character isn't if `matches` is false
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PatternDTO {
+    pub class: CharClass,
+    pub matches: bool,
+}
+
+/// A string variable, whose length is one of the `lengths`, and whose characters satisfy the patterns.
+/// The lengths are tested like an interval of integers, and the strings are made up from them.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StringDTO {
+    pub lengths: MultiInterval,
+    pub patterns: Vec<PatternDTO>,
+    pub is_constant: bool,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Input {
     Bool(BoolDTO),
     Interval(IntervalDTO),
+    Enum(EnumDTO),
+    Set(SetDTO),
+    Periodic(PeriodicDTO),
+    String(StringDTO),
+    /// `x is null`, which is tested with null instead of a value, and with one of the values it takes otherwise
+    Null(Box<Self>),
+}
+
+/// `left == right` or `left != right` between two bool variables
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BoolRelationDTO {
+    pub left: String,
+    pub right: String,
+    pub should_equal: bool,
+}
+
+/// `coefficient * var_name`, where `precision` and `domain` are the precision and the domain of the variable
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LinearTermDTO {
+    pub var_name: String,
+    pub coefficient: Num,
+    pub precision: Num,
+    pub domain: MultiInterval,
+}
+
+/// The sum of the terms has to be in the `interval`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LinearDTO {
+    pub terms: Vec<LinearTermDTO>,
+    pub interval: MultiInterval,
+}
+
+impl LinearTermDTO {
+    /// The smallest change of the term, by changing its variable by its precision
+    pub fn step(&self) -> Num {
+        self.coefficient.abs() * self.precision
+    }
+}
+
+impl LinearDTO {
+    /// The step between the values the sum can take, which every step of a term is a multiple of.
+    ///
+    /// Example: the sum of `2 * x + 3 * y` of integers can take any integer, like `1` for `x = 2` and `y = -1`
+    pub fn precision(&self) -> Num {
+        self.terms
+            .iter()
+            .map(LinearTermDTO::step)
+            .fold(Num::ZERO, Num::gcd)
+    }
+}
+
+/// A condition between multiple variables
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum RelationDTO {
+    Bool(BoolRelationDTO),
+    Linear(LinearDTO),
+}
+
+/// A bound of the condition of a variable, which comes from a named constant, like `DISCOUNT_THRESHOLD` of `price >= DISCOUNT_THRESHOLD`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ConstantDTO {
+    pub var_name: String,
+    pub name: String,
+    pub value: Num,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// One way to satisfy the assumptions of a feature, like `service <= age - 16`, which aren't tested themselves
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AssumptionDTO {
+    pub inputs: HashMap<String, Input>,
+    pub relations: Vec<RelationDTO>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct NTupleInput {
     pub inputs: HashMap<String, Input>,
+    pub relations: Vec<RelationDTO>,
+    /// The expected outcomes of the branch, like `discount = 10`
+    pub expected: Vec<String>,
+    /// The IDs of the requirements the branch and its feature trace to
+    pub requirements: Vec<String>,
+    /// The template instantiations the feature comes from, like `discount(200)`
+    pub instances: Vec<String>,
+    /// The names of the `let` bindings the branch uses, like `working_age`
+    pub bindings: Vec<String>,
+    /// The sized integer variables of the inputs, like a `u8`, tested at the limits of their domain
+    pub sized_variables: Vec<String>,
+    /// The nullable variables of the inputs and the relations, like an `int?`, tested with null as well
+    pub nullable_variables: Vec<String>,
+    /// The assumptions which the n-tuple can satisfy, the test cases have to satisfy one of them if there are any
+    pub assumptions: Vec<AssumptionDTO>,
+    /// The bounds of the conditions of the inputs, which come from named constants
+    pub constants: Vec<ConstantDTO>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -57,9 +338,19 @@ pub enum Output<T>
 where
     T: Intersectable,
 {
+    /// Any value can be used, written as `*`
     MissingVariable,
+    /// The variable has to be null
+    Null,
     Bool(bool),
     Interval(T),
+    /// Any of the listed enum members can be used
+    Enum(Vec<String>),
+    /// A string with a length in `lengths`, made up by `test_value_generator::test_value_for_string`
+    Text {
+        lengths: T,
+        patterns: Vec<PatternDTO>,
+    },
 }
 
 impl<T> Intersectable for Output<T>
@@ -70,6 +361,15 @@ where
         match (self, other) {
             (Self::Bool(this), Self::Bool(that)) => this == that,
             (Self::Interval(this), Self::Interval(that)) => this.intersects_with(that),
+            (Self::Enum(this), Self::Enum(that)) => this.iter().any(|x| that.contains(x)),
+            (Self::Null, Self::Null) => true,
+            (
+                Self::Text { lengths, patterns },
+                Self::Text {
+                    lengths: other_lengths,
+                    patterns: other_patterns,
+                },
+            ) => patterns == other_patterns && lengths.intersects_with(other_lengths),
             (_, _) => false,
         }
     }
@@ -80,34 +380,257 @@ where
             (Self::Interval(this), Self::Interval(that)) => {
                 this.intersect(that).map(Self::Interval)
             }
+            (Self::Enum(this), Self::Enum(that)) => {
+                let members = this
+                    .iter()
+                    .filter(|x| that.contains(x))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if members.is_empty() {
+                    None
+                } else {
+                    Some(Self::Enum(members))
+                }
+            }
             (Self::MissingVariable, Self::MissingVariable) => Some(Self::MissingVariable),
+            (Self::Null, Self::Null) => Some(Self::Null),
+            (
+                Self::Text { lengths, patterns },
+                Self::Text {
+                    lengths: other_lengths,
+                    patterns: other_patterns,
+                },
+            ) if patterns == other_patterns => {
+                lengths.intersect(other_lengths).map(|lengths| Self::Text {
+                    lengths,
+                    patterns: patterns.clone(),
+                })
+            }
             (_, _) => None,
         }
     }
 }
 
-impl<T> Serialize for Output<T>
-where
-    T: Intersectable + Serialize,
-{
+/// Strings are written as the made up string, so only single intervals can be written
+impl Serialize for Output<Interval> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         match self {
-            Self::MissingVariable => serializer.serialize_none(),
+            Self::MissingVariable => serializer.serialize_str("*"),
+            Self::Null => serializer.serialize_none(),
             Self::Bool(bool) => serializer.serialize_bool(*bool),
             Self::Interval(interval) => interval.serialize(serializer),
+            Self::Enum(members) => match &members[..] {
+                [member] => serializer.serialize_str(member),
+                members => serializer.collect_str(&format_args!("{{{}}}", members.join(", "))),
+            },
+            Self::Text { lengths, patterns } => serializer
+                .serialize_str(&test_value_for_string(lengths, patterns).unwrap_or_default()),
         }
     }
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Clone)]
 pub struct NTupleOutput {
     pub outputs: HashMap<String, Output<MultiInterval>>,
 }
 
-pub type NTupleSingleInterval = HashMap<String, Output<Interval>>;
+/// A test case, where every variable takes a single interval.
+///
+/// It dereferences to the values of the variables.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct NTupleSingleInterval {
+    pub values: HashMap<String, Output<Interval>>,
+    /// The expected outcomes of the branches the test case satisfies
+    pub expected: Vec<String>,
+    /// The IDs of the requirements the test case exercises
+    pub requirements: Vec<String>,
+    /// The template instantiations the test case is generated for, like `discount(200)`
+    pub instances: Vec<String>,
+    /// The names of the `let` bindings whose boundaries the test case exercises, like `working_age`
+    pub bindings: Vec<String>,
+    /// The values at the named constants of the conditions, like `price = DISCOUNT_THRESHOLD (ON)`
+    pub constants: Vec<NamedValue>,
+}
+
+/// A value of a test case at a named constant of a condition of its variable.
+/// It is the ON point of the condition if the value satisfies it, and the OFF point if it doesn't.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct NamedValue {
+    pub var_name: String,
+    pub name: String,
+    pub is_on: bool,
+}
+
+impl NamedValue {
+    /// `ON` or `OFF`
+    pub const fn point(&self) -> &'static str {
+        if self.is_on {
+            "ON"
+        } else {
+            "OFF"
+        }
+    }
+}
+
+impl Display for NamedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {} ({})", self.var_name, self.name, self.point())
+    }
+}
+
+impl NTupleSingleInterval {
+    /// The test case, serialized with the values of the number variables written with the decimals of their precision.
+    ///
+    /// `precisions` is the name and the precision of every number variable.
+    pub fn with_precisions<'a>(&'a self, precisions: &'a [(String, Num)]) -> impl Serialize + 'a {
+        WithPrecisions {
+            test_case: self,
+            precisions,
+        }
+    }
+}
+
+/// The test case without precisions, so the values are written with their own decimals
+impl Serialize for NTupleSingleInterval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.with_precisions(&[]).serialize(serializer)
+    }
+}
+
+struct WithPrecisions<'a> {
+    test_case: &'a NTupleSingleInterval,
+    precisions: &'a [(String, Num)],
+}
+
+/// The value of a variable, or the values of the fields of a record variable as a nested object
+enum Field<'a> {
+    Output(&'a Output<Interval>),
+    Text(String),
+    Record(Vec<(&'a str, Self)>),
+}
+
+impl Serialize for Field<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Output(output) => output.serialize(serializer),
+            Self::Text(text) => serializer.serialize_str(text),
+            Self::Record(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, field) in fields {
+                    map.serialize_entry(name, field)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Adds the value of the variable to the fields, in the record of each part of its path, like `order.price`
+fn insert_field<'a>(fields: &mut Vec<(&'a str, Field<'a>)>, var_name: &'a str, field: Field<'a>) {
+    let Some((record_name, field_name)) = var_name.split_once('.') else {
+        fields.push((var_name, field));
+        return;
+    };
+
+    if let Some((_, Field::Record(record))) =
+        fields.iter_mut().find(|(name, _)| *name == record_name)
+    {
+        insert_field(record, field_name, field);
+    } else {
+        let mut record = Vec::new();
+        insert_field(&mut record, field_name, field);
+        fields.push((record_name, Field::Record(record)));
+    }
+}
+
+impl Serialize for WithPrecisions<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut fields = Vec::new();
+        for (var_name, output) in self.test_case.iter() {
+            let precision = self
+                .precisions
+                .iter()
+                .find(|(name, _)| name == var_name)
+                .map(|(_, precision)| *precision);
+
+            let field = match (output, precision) {
+                (Output::Interval(interval), Some(precision)) => {
+                    Field::Text(interval.display_with_precision(precision).to_string())
+                }
+                (output, _) => Field::Output(output),
+            };
+            insert_field(&mut fields, var_name, field);
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        for (name, field) in &fields {
+            map.serialize_entry(name, field)?;
+        }
+        if !self.test_case.expected.is_empty() {
+            map.serialize_entry("expected", &self.test_case.expected)?;
+        }
+        if !self.test_case.requirements.is_empty() {
+            map.serialize_entry("requirements", &self.test_case.requirements)?;
+        }
+        if !self.test_case.instances.is_empty() {
+            map.serialize_entry("instances", &self.test_case.instances)?;
+        }
+        if !self.test_case.bindings.is_empty() {
+            map.serialize_entry("bindings", &self.test_case.bindings)?;
+        }
+        if !self.test_case.constants.is_empty() {
+            let constants = self
+                .test_case
+                .constants
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            map.serialize_entry("constants", &constants)?;
+        }
+
+        map.end()
+    }
+}
+
+impl Deref for NTupleSingleInterval {
+    type Target = HashMap<String, Output<Interval>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl DerefMut for NTupleSingleInterval {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
+impl FromIterator<(String, Output<Interval>)> for NTupleSingleInterval {
+    fn from_iter<I: IntoIterator<Item = (String, Output<Interval>)>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+            expected: Vec::new(),
+            requirements: Vec::new(),
+            instances: Vec::new(),
+            bindings: Vec::new(),
+            constants: Vec::new(),
+        }
+    }
+}
 
 impl Intersectable for NTupleSingleInterval {
     fn intersects_with(&self, other: &Self) -> bool {
@@ -132,7 +655,7 @@ impl Intersectable for NTupleSingleInterval {
             .chain(other.keys())
             .collect::<HashSet<&String>>();
 
-        let intersected_outputs: Self = var_names_in_both.iter().filter_map(|var_name| {
+        let mut intersected_outputs: Self = var_names_in_both.iter().filter_map(|var_name| {
             let var_name = (*var_name).clone();
             let intersection = match (self.get(&*var_name), other.get(&*var_name)) {
                 (None, None) => panic!("in NTuple intersection, variable name should be at least in one of the maps, because we use keys from the maps"),
@@ -146,6 +669,44 @@ impl Intersectable for NTupleSingleInterval {
         .map(|(var_name, input)| (var_name, input))
         .collect();
 
+        // The intersection satisfies the branches of both test cases
+        intersected_outputs.expected = self
+            .expected
+            .iter()
+            .chain(other.expected.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .uniques();
+        intersected_outputs.requirements = self
+            .requirements
+            .iter()
+            .chain(other.requirements.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .uniques();
+        intersected_outputs.instances = self
+            .instances
+            .iter()
+            .chain(other.instances.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .uniques();
+        intersected_outputs.bindings = self
+            .bindings
+            .iter()
+            .chain(other.bindings.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .uniques();
+        // The single values are the same in both, so the names of either are right
+        intersected_outputs.constants = self
+            .constants
+            .iter()
+            .chain(other.constants.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .uniques();
+
         Some(intersected_outputs)
     }
 }
@@ -171,10 +732,18 @@ impl Debug for NTupleOutput {
 pub(crate) mod tests {
     use std::collections::HashMap;
 
+    use pretty_assertions::assert_eq;
     use rstest::rstest;
+    use serde_json::json;
 
-    use super::{Input, NTupleInput, NTupleOutput, NTupleSingleInterval, Output};
-    use crate::interval::{test::int, Intersectable, Interval, MultiInterval};
+    use super::{
+        Input, NTupleInput, NTupleOutput, NTupleSingleInterval, NamedValue, Output, PatternDTO,
+    };
+    use crate::{
+        interval::{test::int, Intersectable, Interval, MultiInterval},
+        number::num,
+        parser::ast::CharClass,
+    };
 
     pub fn create_ntuple_input(inputs: Vec<(&str, Input)>) -> NTupleInput {
         NTupleInput {
@@ -182,6 +751,15 @@ pub(crate) mod tests {
                 .into_iter()
                 .map(|(var_name, input)| (var_name.to_owned(), input))
                 .collect::<HashMap<_, _>>(),
+            relations: Vec::new(),
+            expected: Vec::new(),
+            requirements: Vec::new(),
+            instances: Vec::new(),
+            bindings: Vec::new(),
+            sized_variables: Vec::new(),
+            nullable_variables: Vec::new(),
+            assumptions: Vec::new(),
+            constants: Vec::new(),
         }
     }
 
@@ -200,7 +778,7 @@ pub(crate) mod tests {
         outputs
             .into_iter()
             .map(|(var_name, output)| (var_name.to_owned(), output))
-            .collect::<HashMap<_, _>>()
+            .collect()
     }
 
     #[rstest]
@@ -227,6 +805,11 @@ pub(crate) mod tests {
         ("y", Output::Bool(true))
     ],vec![])]
     #[case::both_empty(vec![], vec![])]
+    #[case::enum_common_member(vec![
+        ("x", Output::Enum(vec!["bronze".to_owned(), "gold".to_owned()])),
+    ],vec![
+        ("x", Output::Enum(vec!["gold".to_owned()])),
+    ])]
     fn test_ntuple_intersects_with(
         #[case] left: Vec<(&str, Output<Interval>)>,
         #[case] right: Vec<(&str, Output<Interval>)>,
@@ -243,6 +826,11 @@ pub(crate) mod tests {
         ("x", Output::Interval(int("[10, 20]"))),
         ("y", Output::Bool(false))
     ])]
+    #[case::enum_no_common_member(vec![
+        ("x", Output::Enum(vec!["bronze".to_owned(), "silver".to_owned()])),
+    ],vec![
+        ("x", Output::Enum(vec!["gold".to_owned()])),
+    ])]
     fn test_ntuple_not_intersects_with(
         #[case] left: Vec<(&str, Output<Interval>)>,
         #[case] right: Vec<(&str, Output<Interval>)>,
@@ -250,4 +838,85 @@ pub(crate) mod tests {
         assert!(!create_ntuple_single_interval(left)
             .intersects_with(&create_ntuple_single_interval(right)));
     }
+
+    #[test]
+    fn test_serialize_records() {
+        let mut test_case = create_ntuple_single_interval(vec![
+            ("order.price", Output::Interval(int("[10, 20]"))),
+            ("order.customer.vip", Output::Bool(true)),
+            ("order.customer.tier", Output::Enum(vec!["gold".to_owned()])),
+            ("quantity", Output::Interval(int("[3, 3]"))),
+        ]);
+        test_case.requirements = vec!["R1".to_owned()];
+        let precisions = [("order.price".to_owned(), num!(0.1))];
+
+        assert_eq!(
+            serde_json::to_value(test_case.with_precisions(&precisions)).unwrap(),
+            json!({
+                "order": {
+                    "price": "[10.0, 20.0]",
+                    "customer": { "vip": true, "tier": "gold" }
+                },
+                "quantity": "[3, 3]",
+                "requirements": ["R1"]
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(&test_case).unwrap()["order"]["price"],
+            json!("[10, 20]")
+        );
+    }
+
+    #[test]
+    fn test_serialize_named_values() {
+        let mut test_case =
+            create_ntuple_single_interval(vec![("price", Output::Interval(int("[200, 200]")))]);
+        test_case.constants = vec![NamedValue {
+            var_name: "price".to_owned(),
+            name: "DISCOUNT_THRESHOLD".to_owned(),
+            is_on: true,
+        }];
+
+        assert_eq!(
+            serde_json::to_value(&test_case).unwrap(),
+            json!({
+                "price": "[200, 200]",
+                "constants": ["price = DISCOUNT_THRESHOLD (ON)"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_strings() {
+        let test_case = create_ntuple_single_interval(vec![(
+            "postcode",
+            Output::Text {
+                lengths: int("[4, 8]"),
+                patterns: vec![PatternDTO {
+                    class: CharClass {
+                        ranges: vec![('0', '9')],
+                    },
+                    matches: true,
+                }],
+            },
+        )]);
+
+        assert_eq!(
+            serde_json::to_value(&test_case).unwrap(),
+            json!({ "postcode": "0000" })
+        );
+    }
+
+    #[test]
+    fn test_serialize_null_and_missing_variable() {
+        let test_case = create_ntuple_single_interval(vec![
+            ("coupon", Output::Null),
+            ("price", Output::MissingVariable),
+        ]);
+
+        assert_eq!(
+            serde_json::to_value(&test_case).unwrap(),
+            json!({ "coupon": null, "price": "*" })
+        );
+    }
 }
@@ -0,0 +1,443 @@
+//! Prints the specs in one canonical style, keeping their comments.
+
+use crate::{
+    cst::{parse_cst, CstNode, GroupKind, Token, TokenKind, Trivia, TriviaKind},
+    loader::{parse_source_files, SourceFile},
+    parser::root_recovering,
+    prelude::{GPTError, Result},
+};
+
+const INDENTATION: &str = "    ";
+
+/// Conditions on longer lines are split into one line for each operand of their boolean operators
+const MAX_LINE_WIDTH: usize = 100;
+
+/// The keywords which start a new statement on a new line
+const STATEMENT_KEYWORDS: [&str; 8] = [
+    "import", "const", "var", "let", "switch", "template", "use", "assume",
+];
+
+#[derive(Clone)]
+struct Printer<'a> {
+    output: String,
+    indentation_level: usize,
+    /// Nothing is written on the current line yet, so it has to be indented before writing
+    at_line_start: bool,
+    /// An empty line can be kept before the next line, which isn't the case right after a `[` or a `{`
+    can_have_empty_line: bool,
+    /// The next token has to start a new line, because the previous one is followed by a line comment
+    needs_line_break: bool,
+    /// The next token is written without a space before it
+    needs_no_space: bool,
+    /// The indentation of the statements in the innermost body, where the cases of a switch start
+    body_indentation_level: usize,
+    /// The `:` after the values of a case is not written yet
+    in_case_header: bool,
+    /// The `[` of a template is written on the line of its name and parameters
+    in_template_header: bool,
+    previous: Option<&'a Token<'a>>,
+}
+
+impl<'a> Printer<'a> {
+    const fn new() -> Self {
+        Self {
+            output: String::new(),
+            indentation_level: 0,
+            at_line_start: true,
+            can_have_empty_line: false,
+            needs_line_break: false,
+            needs_no_space: false,
+            body_indentation_level: 0,
+            in_case_header: false,
+            in_template_header: false,
+            previous: None,
+        }
+    }
+
+    fn line_break(&mut self, with_empty_line: bool) {
+        if self.output.is_empty() {
+            return;
+        }
+
+        if !self.at_line_start {
+            self.output.push('\n');
+        }
+        if with_empty_line && self.can_have_empty_line {
+            self.output.push('\n');
+        }
+        self.at_line_start = true;
+        self.needs_line_break = false;
+    }
+
+    fn write(&mut self, text: &str, with_space: bool) {
+        if self.at_line_start {
+            self.output
+                .push_str(&INDENTATION.repeat(self.indentation_level));
+        } else if with_space && !self.output.is_empty() {
+            self.output.push(' ');
+        }
+
+        self.output.push_str(text);
+        self.at_line_start = false;
+        self.can_have_empty_line = true;
+        self.needs_no_space = false;
+    }
+
+    /// Writes the comments of the trivia, and returns the number of line breaks after the last one
+    fn write_trivia(&mut self, trivia: &[Trivia]) -> usize {
+        let mut line_breaks = 0;
+
+        for trivia in trivia {
+            match trivia.kind {
+                TriviaKind::Whitespace => line_breaks += trivia.text.matches('\n').count(),
+                TriviaKind::LineComment | TriviaKind::BlockComment => {
+                    if line_breaks > 0 {
+                        self.line_break(line_breaks > 1);
+                    }
+                    self.write(trivia.text, true);
+                    self.needs_line_break = trivia.kind == TriviaKind::LineComment;
+                    line_breaks = 0;
+                }
+            }
+        }
+
+        line_breaks
+    }
+
+    /// Writes the token after its comments, either on a new line or after the previous token
+    fn write_token(&mut self, token: &'a Token<'a>, starts_line: bool, with_space: bool) {
+        let line_breaks = self.write_trivia(&token.leading_trivia);
+        self.write_token_text(token, starts_line, line_breaks > 1, with_space);
+    }
+
+    fn write_token_text(
+        &mut self,
+        token: &'a Token<'a>,
+        starts_line: bool,
+        with_empty_line: bool,
+        with_space: bool,
+    ) {
+        if starts_line || self.needs_line_break {
+            self.line_break(starts_line && with_empty_line);
+        }
+        self.write(token.text, with_space && !self.needs_no_space);
+        self.previous = Some(token);
+    }
+
+    fn previous_text(&self) -> &str {
+        self.previous.map_or("", |token| token.text)
+    }
+
+    /// Whether a `-` after the previous token negates the next value, instead of subtracting it
+    fn is_minus_unary(&self) -> bool {
+        match self.previous {
+            None => true,
+            Some(previous) => {
+                previous.text == "case"
+                    || previous.kind == TokenKind::Punctuation
+                        && !matches!(previous.text, ")" | "]" | "}")
+            }
+        }
+    }
+
+    fn print_nodes(&mut self, nodes: &'a [CstNode<'a>], in_statements: bool) {
+        for node in nodes {
+            match node {
+                CstNode::Token(token) => self.print_token(token, in_statements),
+                CstNode::Group { kind, children } => self.print_group(*kind, children),
+            }
+        }
+    }
+
+    fn print_token(&mut self, token: &'a Token<'a>, in_statements: bool) {
+        let previous_text = self.previous_text();
+        let starts_line = in_statements
+            && match token.text {
+                "if" => previous_text != "else",
+                "else" => previous_text != "}",
+                // The requirements of a statement are written on the lines before it
+                "case" | "default" | "@" => true,
+                text => STATEMENT_KEYWORDS.contains(&text),
+            };
+        let is_case = in_statements && matches!(token.text, "case" | "default");
+        // The cases are indented like the statements of the switch's body, and the statements after them one level more
+        if is_case {
+            self.indentation_level = self.body_indentation_level;
+        }
+        // The `?` of a nullable type is written right after it, like `int?`
+        let with_space = !matches!(token.text, "," | ":" | "?");
+
+        let is_unary = token.text == "!" || (token.text == "-" && self.is_minus_unary());
+
+        self.write_token(token, starts_line, with_space);
+        // The value after a negating `!` or `-` is written right after it, like `req` after `@`
+        self.needs_no_space = is_unary || token.text == "@";
+
+        if in_statements && token.text == "template" {
+            self.in_template_header = true;
+        }
+        if is_case {
+            self.in_case_header = true;
+        } else if self.in_case_header && token.text == ":" {
+            self.in_case_header = false;
+            self.indentation_level = self.body_indentation_level + 1;
+        }
+    }
+
+    fn print_group(&mut self, kind: GroupKind, children: &'a [CstNode<'a>]) {
+        let Some((CstNode::Token(open), rest)) = children.split_first() else {
+            unreachable!("A group starts with its opening bracket");
+        };
+        // The group isn't closed at the end of the input
+        let (close, inner) = match rest.split_last() {
+            Some((CstNode::Token(close), inner)) if matches!(close.text, ")" | "]" | "}") => {
+                (Some(close), inner)
+            }
+            _ => (None, rest),
+        };
+
+        match kind {
+            GroupKind::Feature | GroupKind::Body => {
+                let starts_line = kind == GroupKind::Feature && !self.in_template_header;
+                self.in_template_header = false;
+                self.write_token(open, starts_line, true);
+                self.can_have_empty_line = false;
+
+                let outer_levels = (self.indentation_level, self.body_indentation_level);
+                self.indentation_level += 1;
+                self.body_indentation_level = self.indentation_level;
+                self.print_nodes(inner, true);
+                // The comments before the closing bracket are still indented like the statements
+                if let Some(close) = close {
+                    self.write_trivia(&close.leading_trivia);
+                }
+                (self.indentation_level, self.body_indentation_level) = outer_levels;
+
+                if let Some(close) = close {
+                    self.write_token_text(close, true, false, false);
+                }
+            }
+            GroupKind::Parentheses if self.previous_text() == "if" => {
+                let mut on_one_line = self.clone();
+                on_one_line.print_parentheses(open, inner, close, false);
+
+                if on_one_line.current_line_width() <= MAX_LINE_WIDTH {
+                    *self = on_one_line;
+                } else {
+                    self.print_parentheses(open, inner, close, true);
+                }
+            }
+            GroupKind::Parentheses | GroupKind::Interval => {
+                self.print_parentheses(open, inner, close, false);
+            }
+            GroupKind::MemberSet => {
+                self.write_token(open, false, true);
+                self.print_nodes(inner, false);
+                if let Some(close) = close {
+                    self.write_token(close, false, true);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Printer<'a> {
+    /// Prints `( ... )` or an interval, with the operands of the boolean operators on separate lines if it's split
+    fn print_parentheses(
+        &mut self,
+        open: &'a Token<'a>,
+        inner: &'a [CstNode<'a>],
+        close: Option<&'a Token<'a>>,
+        is_split: bool,
+    ) {
+        // `if(` and `num(` are written together, but `in (` and `case (` have a space
+        let with_space = matches!(self.previous_text(), "in" | "case")
+            || !self
+                .previous
+                .is_some_and(|previous| previous.kind == TokenKind::Word);
+        self.write_token(open, false, with_space);
+        self.needs_no_space = true;
+
+        if is_split {
+            self.indentation_level += 1;
+            self.needs_line_break = true;
+        }
+        for node in inner {
+            match node {
+                CstNode::Token(token) => {
+                    self.print_token(token, false);
+                    self.needs_line_break |=
+                        is_split && matches!(token.text, "&&" | "||" | "^" | "=>" | "<=>");
+                }
+                CstNode::Group { kind, children } => self.print_group(*kind, children),
+            }
+        }
+        if is_split {
+            self.indentation_level -= 1;
+            self.needs_line_break = true;
+        }
+
+        if let Some(close) = close {
+            self.write_token(close, false, false);
+        }
+    }
+
+    /// The width of the last line of the output in characters
+    fn current_line_width(&self) -> usize {
+        self.output
+            .rsplit_once('\n')
+            .map_or(self.output.as_str(), |(_, line)| line)
+            .chars()
+            .count()
+    }
+}
+
+/// Prints the source in the canonical style, keeping its comments
+fn print_source(source: &str) -> String {
+    let cst = parse_cst(source);
+    let mut printer = Printer::new();
+
+    printer.print_nodes(&cst.nodes, true);
+    printer.write_trivia(&cst.trailing_trivia);
+
+    if !printer.output.is_empty() {
+        printer.output.push('\n');
+    }
+
+    printer.output
+}
+
+/// Formats the source in the canonical style, failing if it has syntax errors.
+///
+/// The formatted source is parsed again, and it's only returned if it means the same as the original one.
+pub fn format_source(path: &str, source: &str) -> Result<String> {
+    parse_source_files(&[SourceFile {
+        path: path.to_owned(),
+        source: source.to_owned(),
+    }])?;
+
+    let formatted = print_source(source);
+
+    if root_recovering(&formatted) == root_recovering(source) {
+        Ok(formatted)
+    } else {
+        Err(GPTError::FormatError(format!(
+            "formatting {path} would change its meaning"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::spacing(
+        "var x:int in[0,10)\nvar y :num( 0.1 )\nif(x>=-5&&!(y<2*x-1))",
+        "var x: int in [0, 10)\nvar y: num(0.1)\nif(x >= -5 && !(y < 2 * x - 1))\n"
+    )]
+    #[case::members(
+        "var tier:enum{bronze,gold}\nif( tier not in{gold} )=>\"no discount\"",
+        "var tier: enum { bronze, gold }\nif(tier not in { gold }) => \"no discount\"\n"
+    )]
+    #[case::statements_on_new_lines(
+        "var x: int var y: bool if(x > 5) if(y == true)",
+        "var x: int\nvar y: bool\nif(x > 5)\nif(y == true)\n"
+    )]
+    #[case::bodies(
+        "if(x > 5) { if(y == true) }\nelse if(x < 2) {\n\n\n  if(y == false) } else {}",
+        "if(x > 5) {\n    if(y == true)\n} else if(x < 2) {\n    if(y == false)\n} else {\n}\n"
+    )]
+    #[case::else_without_bodies(
+        "if(x > 5) else if(x < 2)\n\n\n\nelse",
+        "if(x > 5)\nelse if(x < 2)\n\nelse\n"
+    )]
+    #[case::features(
+        "const LIMIT = 10\n[ var x: int\n\nif(x > LIMIT) ]\n\n\n[\nvar y: bool\n]",
+        "const LIMIT = 10\n[\n    var x: int\n\n    if(x > LIMIT)\n]\n\n[\n    var y: bool\n]\n"
+    )]
+    #[case::comments(
+        "/* Tickets */\n\n\n// R1\nvar age: int // in years\nif(age < 6 /* free */) {\n// R1.1\n}\n// end\n",
+        "/* Tickets */\n\n// R1\nvar age: int // in years\nif(age < 6 /* free */) {\n    // R1.1\n}\n// end\n"
+    )]
+    #[case::long_condition(
+        "if(first_variable > 5 && second_variable < 10 || (third_variable == true && fourth_variable != 100.5))",
+        "if(\n    first_variable > 5 &&\n    second_variable < 10 ||\n    (third_variable == true && fourth_variable != 100.5)\n)\n"
+    )]
+    #[case::operators(
+        "if(x>5=>y==true^z<1<=>!(y==false))",
+        "if(x > 5 => y == true ^ z < 1 <=> !(y == false))\n"
+    )]
+    #[case::modulo(
+        "if(year%4==0&&year % BATCH!=1)",
+        "if(year % 4 == 0 && year % BATCH != 1)\n"
+    )]
+    #[case::requirements(
+        "@req( \"F1\" )[ @req(\"R1\",\"R1-1\") if(x > 5) { @req(\"R2\") if(y == true) } @req(\"R3\") else ]",
+        "@req(\"F1\")\n[\n    @req(\"R1\", \"R1-1\")\n    if(x > 5) {\n        @req(\"R2\")\n        if(y == true)\n    }\n    @req(\"R3\")\n    else\n]\n"
+    )]
+    #[case::switch(
+        "switch (x) { case [0,10)=>\"low\" : case -1 ,(20, 30]:if(y == true) default :\n// rest\n}",
+        "switch(x) {\n    case [0, 10) => \"low\":\n    case -1, (20, 30]:\n        if(y == true)\n    default:\n        // rest\n}\n"
+    )]
+    #[case::templates(
+        "@req(\"R3\") template discount( threshold )[ var price: num\nif(price >= threshold) ]\n@req(\"R1\") use discount( 200 ) use discount(150)",
+        "@req(\"R3\")\ntemplate discount(threshold) [\n    var price: num\n    if(price >= threshold)\n]\n@req(\"R1\")\nuse discount(200)\nuse discount(150)\n"
+    )]
+    #[case::assumptions(
+        "var x: int var y: int assume( x>=0&&y<=x )[ if(y > 5) ]",
+        "var x: int\nvar y: int\nassume(x >= 0 && y <= x)\n[\n    if(y > 5)\n]\n"
+    )]
+    #[case::bindings(
+        "var x: int let small=x<5 let big = !small&&x>10 if(small||big)",
+        "var x: int\nlet small = x < 5\nlet big = !small && x > 10\nif(small || big)\n"
+    )]
+    #[case::records(
+        "var order:{price:num( 0.1 )in[0,1000],customer :{vip:bool}} if(order.price>10&&order.customer.vip==true)",
+        "var order: { price: num(0.1) in [0, 1000], customer: { vip: bool } }\nif(order.price > 10 && order.customer.vip == true)\n"
+    )]
+    #[case::nullable(
+        "var coupon:int ? in[0,50]\nif(coupon is null||coupon is not null)",
+        "var coupon: int? in [0, 50]\nif(coupon is null || coupon is not null)\n"
+    )]
+    #[case::strings(
+        "var name:string?\nif(len( name )in[3,16]&&name matches\"[a-z_]\")",
+        "var name: string?\nif(len(name) in [3, 16] && name matches \"[a-z_]\")\n"
+    )]
+    fn test_format_source(#[case] source: &str, #[case] expected: &str) {
+        assert_eq!(format_source("input.gpt", source).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(include_str!("../../examples/gpt-lang/complex_or.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/coupon_discount.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/discount_outcomes.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/else_if.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/free_shipping.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/imports/shipping.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/paid_vacation_days.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/paid_vacation_days_assumed.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/paid_vacation_days_with_bindings.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/regional_discount.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/shipping_with_records.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/signup_form.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/smelting.gpt"))]
+    #[case(include_str!("../../examples/gpt-lang/ticket_price.gpt"))]
+    fn test_format_source_idempotent(#[case] source: &str) {
+        let formatted = format_source("input.gpt", source).unwrap();
+
+        assert_eq!(format_source("input.gpt", &formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_syntax_error() {
+        let error = format_source("input.gpt", "var x: int\nif(x > )").unwrap_err();
+
+        assert!(matches!(error, GPTError::ParseError(_)));
+        assert_eq!(error.diagnostics()[0].line, 2);
+    }
+}
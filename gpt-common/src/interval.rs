@@ -2,6 +2,8 @@ use std::{cmp::Ordering, fmt};
 
 use serde::{Serialize, Serializer};
 
+use crate::number::Num;
+
 pub trait Intersectable {
     fn intersects_with(&self, other: &Self) -> bool;
 
@@ -31,46 +33,58 @@ impl Boundary {
 }
 
 /// Represents one interval with boundaries, a low value and a high value
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub struct Interval {
     pub lo_boundary: Boundary,
-    pub lo: f32,
-    pub hi: f32,
+    pub lo: Num,
+    pub hi: Num,
     pub hi_boundary: Boundary,
 }
 
 impl Interval {
-    pub fn contains_point(&self, point: f32) -> bool {
+    pub fn contains_point(&self, point: Num) -> bool {
         !self.is_empty()
             && ((self.lo < point && point < self.hi)
                 || (self.lo == point && self.lo_boundary == Boundary::Closed)
                 || (self.hi == point && self.hi_boundary == Boundary::Closed))
     }
 
-    pub fn contains(&self, _other: &Self) -> bool {
-        todo!()
+    /// Whether every value of `other` is in this interval
+    pub fn contains(&self, other: &Self) -> bool {
+        if other.is_empty() {
+            return true;
+        }
+
+        let lo_contained = self.lo < other.lo
+            || (self.lo == other.lo
+                && (self.lo_boundary == Boundary::Closed || other.lo_boundary == Boundary::Open));
+        let hi_contained = other.hi < self.hi
+            || (self.hi == other.hi
+                && (self.hi_boundary == Boundary::Closed || other.hi_boundary == Boundary::Open));
+
+        !self.is_empty() && lo_contained && hi_contained
     }
 
     /// Creates an interval. If lo or hi would be infinity, that side will be open, no matter what boundary was passed to it,
     /// because that is the semantically correct way to handle it.
     pub fn new(
         lo_boundary: Boundary,
-        lo: f32,
-        hi: f32,
+        lo: Num,
+        hi: Num,
         hi_boundary: Boundary,
     ) -> Result<Self, IntervalError> {
         if lo > hi {
             Err(IntervalError::LoIsGreaterThanHi)
         } else {
             Ok(Self {
-                lo_boundary: if lo == f32::NEG_INFINITY {
+                lo_boundary: if lo == Num::NEG_INFINITY {
                     Boundary::Open
                 } else {
                     lo_boundary
                 },
                 lo,
                 hi,
-                hi_boundary: if hi == f32::INFINITY {
+                hi_boundary: if hi == Num::INFINITY {
                     Boundary::Open
                 } else {
                     hi_boundary
@@ -79,11 +93,11 @@ impl Interval {
         }
     }
 
-    pub fn new_closed(lo: f32, hi: f32) -> Result<Self, IntervalError> {
+    pub fn new_closed(lo: Num, hi: Num) -> Result<Self, IntervalError> {
         Self::new(Boundary::Closed, lo, hi, Boundary::Closed)
     }
 
-    pub const fn new_closed_point(point: f32) -> Self {
+    pub const fn new_closed_point(point: Num) -> Self {
         Self {
             lo_boundary: Boundary::Closed,
             lo: point,
@@ -104,7 +118,7 @@ impl Interval {
     }
 
     fn lo_cmp(&self, other: &Self) -> Ordering {
-        match self.lo.partial_cmp(&other.lo).unwrap() {
+        match self.lo.cmp(&other.lo) {
             std::cmp::Ordering::Equal => match (self.lo_boundary, other.lo_boundary) {
                 (Boundary::Open, Boundary::Closed) => std::cmp::Ordering::Greater,
                 (Boundary::Closed, Boundary::Open) => std::cmp::Ordering::Less,
@@ -116,7 +130,7 @@ impl Interval {
     }
 
     fn hi_cmp(&self, other: &Self) -> Ordering {
-        match self.hi.partial_cmp(&other.hi).unwrap() {
+        match self.hi.cmp(&other.hi) {
             std::cmp::Ordering::Equal => match (self.hi_boundary, other.hi_boundary) {
                 (Boundary::Open, Boundary::Closed) => std::cmp::Ordering::Less,
                 (Boundary::Closed, Boundary::Open) => std::cmp::Ordering::Greater,
@@ -132,8 +146,8 @@ impl Interval {
             return MultiInterval {
                 intervals: vec![Self {
                     lo_boundary: Boundary::Open,
-                    lo: f32::NEG_INFINITY,
-                    hi: f32::INFINITY,
+                    lo: Num::NEG_INFINITY,
+                    hi: Num::INFINITY,
                     hi_boundary: Boundary::Open,
                 }],
             };
@@ -141,20 +155,20 @@ impl Interval {
 
         let mut new_intervals = Vec::new();
 
-        if self.lo != f32::NEG_INFINITY {
+        if self.lo != Num::NEG_INFINITY {
             new_intervals.push(Self {
                 lo_boundary: Boundary::Open,
-                lo: f32::NEG_INFINITY,
+                lo: Num::NEG_INFINITY,
                 hi: self.lo,
                 hi_boundary: self.lo_boundary.inverse(),
             });
         }
 
-        if self.hi != f32::INFINITY {
+        if self.hi != Num::INFINITY {
             new_intervals.push(Self {
                 lo_boundary: self.hi_boundary.inverse(),
                 lo: self.hi,
-                hi: f32::INFINITY,
+                hi: Num::INFINITY,
                 hi_boundary: Boundary::Open,
             });
         }
@@ -203,25 +217,20 @@ impl Intersectable for Interval {
     }
 }
 
-impl fmt::Display for Interval {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Interval {
+    /// Writes the interval like its `Display`, with the values formatted by `format_value`
+    fn write_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        format_value: impl Fn(Num) -> String,
+    ) -> fmt::Result {
         let lo_boundary = match self.lo_boundary {
             Boundary::Open => "(",
             Boundary::Closed => "[",
         };
 
-        let lo = if self.lo == f32::NEG_INFINITY {
-            "-Inf".to_owned()
-        } else {
-            self.lo.to_string()
-        };
-
-        let hi = if self.hi == f32::INFINITY {
-            "Inf".to_owned()
-        } else {
-            self.hi.to_string()
-        };
-
+        let lo = format_value(self.lo);
+        let hi = format_value(self.hi);
         let hi_boundary = match self.hi_boundary {
             Boundary::Open => ")",
             Boundary::Closed => "]",
@@ -229,6 +238,26 @@ impl fmt::Display for Interval {
 
         write!(f, "{lo_boundary}{lo}, {hi}{hi_boundary}")
     }
+
+    /// The interval with its values written with the decimals of the precision, like `[199.90, Inf)`
+    pub fn display_with_precision(&self, precision: Num) -> impl fmt::Display + '_ {
+        struct WithPrecision<'a>(&'a Interval, Num);
+
+        impl fmt::Display for WithPrecision<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0
+                    .write_with(f, |value| value.format_with_precision(self.1))
+            }
+        }
+
+        WithPrecision(self, precision)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with(f, |value| value.to_string())
+    }
 }
 
 impl fmt::Debug for Interval {
@@ -246,7 +275,7 @@ impl Serialize for Interval {
     }
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Clone)]
 pub struct MultiInterval {
     /// `intervals` is always sorted in ascending order and there are no overlapping intervals
     pub(crate) intervals: Vec<Interval>,
@@ -264,8 +293,8 @@ pub enum IntervalError {
 impl MultiInterval {
     pub fn new(
         lo_boundary: Boundary,
-        lo: f32,
-        hi: f32,
+        lo: Num,
+        hi: Num,
         hi_boundary: Boundary,
     ) -> Result<Self, IntervalError> {
         Ok(Self {
@@ -293,23 +322,28 @@ impl MultiInterval {
         }
     }
 
-    pub fn new_closed(lo: f32, hi: f32) -> Result<Self, IntervalError> {
+    pub fn new_closed(lo: Num, hi: Num) -> Result<Self, IntervalError> {
         Self::new(Boundary::Closed, lo, hi, Boundary::Closed)
     }
 
-    pub fn new_closed_point(num: f32) -> Self {
+    pub fn new_closed_point(num: Num) -> Self {
         Self::new(Boundary::Closed, num, num, Boundary::Closed)
             .expect("Closed point creation should not cause any errors")
     }
 
-    fn highest_hi(&self) -> f32 {
+    /// `(-Inf, Inf)`, the domain of variables without a declared domain
+    pub fn new_unbounded() -> Self {
+        Self::new_empty().complement()
+    }
+
+    fn highest_hi(&self) -> Num {
         self.intervals
             .last()
             .expect("Interval should always contain an interval")
             .hi
     }
 
-    fn lowest_lo(&self) -> f32 {
+    fn lowest_lo(&self) -> Num {
         self.intervals
             .first()
             .expect("Interval should always contain an interval")
@@ -330,7 +364,7 @@ impl MultiInterval {
             .lo_boundary
     }
 
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.intervals.is_empty()
     }
 
@@ -338,14 +372,45 @@ impl MultiInterval {
         self.intervals.len() == 1 && self.intervals[0].is_single_point()
     }
 
+    /// The values of this interval, which are also in the `domain`
+    #[must_use]
+    pub fn restricted_to(&self, domain: &Self) -> Self {
+        self.intersect(domain).unwrap_or_else(Self::new_empty)
+    }
+
+    /// The values of the `domain`, which are not in this interval
+    #[must_use]
+    pub fn complement_within(&self, domain: &Self) -> Self {
+        self.complement().restricted_to(domain)
+    }
+
+    /// Whether a multiple of `precision` is in the interval, like an integer for a precision of 1.
+    /// `(9, 10)` is not empty, but an int variable can't take any of its values.
+    pub fn has_value_with_precision(&self, precision: Num) -> bool {
+        self.intervals.iter().any(|interval| {
+            if interval.lo == Num::NEG_INFINITY {
+                return !interval.is_empty();
+            }
+
+            let first = interval.lo.ceil_to(precision);
+            let first = if first == interval.lo && interval.lo_boundary == Boundary::Open {
+                first.add_steps(1, precision)
+            } else {
+                first
+            };
+
+            interval.contains_point(first)
+        })
+    }
+
     #[must_use]
     pub fn complement(&self) -> Self {
         if self.intervals.is_empty() {
             return Self {
                 intervals: vec![Interval {
                     lo_boundary: Boundary::Open,
-                    lo: f32::NEG_INFINITY,
-                    hi: f32::INFINITY,
+                    lo: Num::NEG_INFINITY,
+                    hi: Num::INFINITY,
                     hi_boundary: Boundary::Open,
                 }],
             };
@@ -353,10 +418,10 @@ impl MultiInterval {
 
         let mut new_intervals = Vec::new();
 
-        if self.lowest_lo() != f32::NEG_INFINITY {
+        if self.lowest_lo() != Num::NEG_INFINITY {
             new_intervals.push(Interval {
                 lo_boundary: Boundary::Open,
-                lo: f32::NEG_INFINITY,
+                lo: Num::NEG_INFINITY,
                 hi: self.lowest_lo(),
                 hi_boundary: self.lowest_boundary().inverse(),
             });
@@ -376,14 +441,16 @@ impl MultiInterval {
                         hi_boundary: b.lo_boundary.inverse(),
                     }
                 })
+                // There is nothing between bordering intervals, like [0, 10) [10, 20)
+                .filter(|x| !x.is_empty())
                 .collect(),
         );
 
-        if self.highest_hi() != f32::INFINITY {
+        if self.highest_hi() != Num::INFINITY {
             new_intervals.push(Interval {
                 lo_boundary: self.highest_boundary().inverse(),
                 lo: self.highest_hi(),
-                hi: f32::INFINITY,
+                hi: Num::INFINITY,
                 hi_boundary: Boundary::Open,
             });
         }
@@ -400,33 +467,21 @@ impl MultiInterval {
         // Sort the intervals
         self.intervals.sort_by(Interval::lo_cmp);
 
-        // Merging overlapping intervals
-        if self.intervals.len() >= 2 {
-            // Start at the back and go backwards, because then we can remove indicies easily from the end
-            for i in (0..=(self.intervals.len() - 2)).rev() {
-                let (left, right) = (self.intervals[i], self.intervals[i + 1]);
-
-                // left.lo <= right.lo beacuse of the sort
-                if left.intersects_with(&right) {
-                    if left.hi_cmp(&right) == Ordering::Greater {
-                        self.intervals[i] = Interval {
-                            lo_boundary: left.lo_boundary,
-                            lo: left.lo,
-                            hi: left.hi,
-                            hi_boundary: left.hi_boundary,
-                        };
-                    } else {
-                        self.intervals[i] = Interval {
-                            lo_boundary: left.lo_boundary,
-                            lo: left.lo,
-                            hi: right.hi,
-                            hi_boundary: right.hi_boundary,
-                        };
+        // Merging overlapping intervals, an interval can overlap several of the ones after it
+        let mut merged: Vec<Interval> = Vec::with_capacity(self.intervals.len());
+        for interval in self.intervals.drain(..) {
+            match merged.last_mut() {
+                // last.lo <= interval.lo beacuse of the sort
+                Some(last) if last.intersects_with(&interval) => {
+                    if last.hi_cmp(&interval) != Ordering::Greater {
+                        last.hi = interval.hi;
+                        last.hi_boundary = interval.hi_boundary;
                     }
-                    self.intervals.remove(i + 1);
                 }
+                _ => merged.push(interval),
             }
         }
+        self.intervals = merged;
     }
 }
 
@@ -453,10 +508,7 @@ impl Intersectable for MultiInterval {
             .flatten()
             .collect();
 
-        intersected_intervals.sort_unstable_by(|a, b| {
-            a.lo.partial_cmp(&b.lo)
-                .expect("f32::NaN should not be the lo value of intervals")
-        });
+        intersected_intervals.sort_unstable_by_key(|interval| interval.lo);
 
         if intersected_intervals.is_empty() {
             None
@@ -525,7 +577,8 @@ pub(crate) mod test {
     use rstest::rstest;
     use Ordering::{Equal, Greater, Less};
 
-    use super::{Intersectable, Interval, MultiInterval};
+    use super::{Intersectable, Interval, MultiInterval, Unionable};
+    use crate::number::{num, Num};
     use crate::parser::interval;
 
     pub fn int(input: &str) -> Interval {
@@ -560,14 +613,14 @@ pub(crate) mod test {
     }
 
     #[rstest]
-    #[case("[5, 10]", 4.0, false)]
-    #[case("(5, 10]", 5.0, false)]
-    #[case("[5, 10]", 5.0, true)]
-    #[case("[5, 10]", 7.0, true)]
-    #[case("[5, 10]", 10.0, true)]
-    #[case("[5, 10)", 10.0, false)]
-    #[case("[5, 10)", 11.0, false)]
-    fn test_contains_point(#[case] interval: Interval, #[case] point: f32, #[case] expected: bool) {
+    #[case("[5, 10]", num!(4.0), false)]
+    #[case("(5, 10]", num!(5.0), false)]
+    #[case("[5, 10]", num!(5.0), true)]
+    #[case("[5, 10]", num!(7.0), true)]
+    #[case("[5, 10]", num!(10.0), true)]
+    #[case("[5, 10)", num!(10.0), false)]
+    #[case("[5, 10)", num!(11.0), false)]
+    fn test_contains_point(#[case] interval: Interval, #[case] point: Num, #[case] expected: bool) {
         assert_eq!(
             interval.contains_point(point),
             expected,
@@ -575,6 +628,27 @@ pub(crate) mod test {
         );
     }
 
+    #[rstest]
+    #[case("[5, 10]", "[6, 9]", true)]
+    #[case("[5, 10]", "[5, 10]", true)]
+    #[case("(5, 10)", "[5, 10)", false)]
+    #[case("(5, 10)", "(5, 10)", true)]
+    #[case("[5, 10)", "[5, 10]", false)]
+    #[case("(-Inf, 0)", "(-Inf, -2]", true)]
+    #[case("[5, 10]", "[8, 12]", false)]
+    #[case("[5, 10]", "(7, 7)", true)]
+    fn test_interval_contains(
+        #[case] interval: Interval,
+        #[case] other: Interval,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            interval.contains(&other),
+            expected,
+            "Interval.contains failed: {interval}.contains({other}) should be {expected}",
+        );
+    }
+
     #[rstest]
     // self.hi equals other.lo
     #[case("[0, 10]", "[10, 20]", true)]
@@ -705,6 +779,22 @@ pub(crate) mod test {
         );
     }
 
+    #[rstest]
+    #[case("[199.9, Inf)", num!(0.01), "[199.90, Inf)")]
+    #[case("(-Inf, 200]", num!(0.1), "(-Inf, 200.0]")]
+    #[case("[3, 4]", num!(1.0), "[3, 4]")]
+    #[case("(5.05, 6)", num!(0.1), "(5.05, 6.0)")]
+    fn test_interval_display_with_precision(
+        #[case] interval: Interval,
+        #[case] precision: Num,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            interval.display_with_precision(precision).to_string(),
+            expected
+        );
+    }
+
     #[rstest]
     #[case("(0,0)", true)]
     #[case("(0,0]", true)]
@@ -856,6 +946,54 @@ pub(crate) mod test {
         );
     }
 
+    #[rstest]
+    #[case::unbounded("(-Inf, 20)", "(-Inf, Inf)", "[20, Inf)")]
+    #[case::closed_domain("(-Inf, 20)", "[0, 130]", "[20, 130]")]
+    #[case::outside_of_domain("[200, Inf)", "[0, 130]", "[0, 130]")]
+    #[case::whole_domain("[0, 130]", "[0, 130]", "")]
+    #[case::split("[10, 20]", "[0, 130]", "[0, 10) (20, 130]")]
+    #[case::bordering("[0, 10) [10, 20)", "[0, 130]", "[20, 130]")]
+    fn test_multiinterval_complement_within(
+        #[case] interval: MultiInterval,
+        #[case] domain: MultiInterval,
+        #[case] expected: MultiInterval,
+    ) {
+        assert_eq!(interval.complement_within(&domain), expected);
+    }
+
+    #[rstest]
+    #[case::disjoint("[0, 1]", "(2, 3)", "[0, 1] (2, 3)")]
+    #[case::overlapping("[0, 2]", "(1, 3)", "[0, 3)")]
+    #[case::covering_several("[0, 5]", "[2, 2] [3, 3] [4, 6)", "[0, 6)")]
+    #[case::bridging("[0, 1] [2, 3]", "[1, 2]", "[0, 3]")]
+    fn test_multiinterval_union(
+        #[case] left: MultiInterval,
+        #[case] right: MultiInterval,
+        #[case] expected: MultiInterval,
+    ) {
+        assert_eq!(left.union(&right), expected);
+    }
+
+    #[rstest]
+    #[case("[0, 10)", num!(1.0), true)]
+    #[case("(9, 10)", num!(1.0), false)]
+    #[case("(9, 10]", num!(1.0), true)]
+    #[case("(9, 10) (10, 11)", num!(1.0), false)]
+    #[case("(9, 10) (10, 12)", num!(1.0), true)]
+    #[case("(0.1, 0.2)", num!(0.1), false)]
+    #[case("(0.1, 0.25)", num!(0.1), true)]
+    #[case("(0.2, 0.3]", num!(0.1), true)]
+    #[case("(-Inf, -5.5)", num!(1.0), true)]
+    #[case("(5.5, Inf)", num!(1.0), true)]
+    #[case("", num!(1.0), false)]
+    fn test_multiinterval_has_value_with_precision(
+        #[case] interval: MultiInterval,
+        #[case] precision: Num,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(interval.has_value_with_precision(precision), expected);
+    }
+
     #[test]
     fn test_multiinterval_axioms() {
         let input1 = multiint("[-42, 3) (3, 67) (100, 101) [205, 607] (700, Inf)");
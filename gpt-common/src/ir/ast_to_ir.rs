@@ -1,8 +1,14 @@
 use super::{IntervalCondition, Predicate};
 use crate::{
-    interval::{Boundary, MultiInterval},
+    interval::{Boundary, MultiInterval, Unionable},
     ir,
-    parser::ast::{self, BinaryOp, BoolOp, ConstantPosition, ElseNode, EqOp, IfNode, RootNode},
+    number::Num,
+    parser::ast::{
+        self, BinaryOp, BoolOp, CaseValue, ConstantPosition, ElseNode, EqOp, IfNode, IntervalOp,
+        RootNode, SwitchNode, Type,
+    },
+    relation::snap_to_precision,
+    util::UniquesVec,
 };
 
 const fn resolve_bool_condition(eq_op: &EqOp, bool_val: bool) -> bool {
@@ -18,17 +24,17 @@ const fn resolve_bool_condition(eq_op: &EqOp, bool_val: bool) -> bool {
     }
 }
 
-fn binary_op_to_interval(binop: &BinaryOp, num: f32) -> MultiInterval {
+pub fn binary_op_to_interval(binop: &BinaryOp, num: Num) -> MultiInterval {
     use Boundary::{Closed, Open};
 
     match binop {
         BinaryOp::NotEqual => MultiInterval::new_closed_point(num).complement(),
         x => {
             let (lo_boundary, lo, hi, hi_boundary) = match x {
-                BinaryOp::LessThan => (Open, f32::NEG_INFINITY, num, Open),
-                BinaryOp::GreaterThan => (Open, num, f32::INFINITY, Open),
-                BinaryOp::LessThanEqualTo => (Open, f32::NEG_INFINITY, num, Closed),
-                BinaryOp::GreaterThanEqualTo => (Closed, num, f32::INFINITY, Open),
+                BinaryOp::LessThan => (Open, Num::NEG_INFINITY, num, Open),
+                BinaryOp::GreaterThan => (Open, num, Num::INFINITY, Open),
+                BinaryOp::LessThanEqualTo => (Open, Num::NEG_INFINITY, num, Closed),
+                BinaryOp::GreaterThanEqualTo => (Closed, num, Num::INFINITY, Open),
                 BinaryOp::Equal => (Closed, num, num, Closed),
                 _ => unreachable!(),
             };
@@ -48,7 +54,98 @@ fn convert_bool_condition(cond: &ast::BoolCondition) -> ir::Condition {
     })
 }
 
-fn convert_binary_condition(cond: &ast::BinaryCondition) -> ir::Condition {
+fn find_constant(constants: &[ir::Constant], name: &str) -> Option<Num> {
+    constants
+        .iter()
+        .find(|constant| constant.name == name)
+        .map(|constant| constant.value)
+}
+
+/// The constants with the names, like `DISCOUNT_THRESHOLD` of `price >= DISCOUNT_THRESHOLD`,
+/// so the test cases at their values can be named after them. The other names are skipped.
+fn named_constants<'a>(
+    constants: &[ir::Constant],
+    names: impl IntoIterator<Item = &'a str>,
+) -> Vec<ir::Constant> {
+    names
+        .into_iter()
+        .filter_map(|name| constants.iter().find(|constant| constant.name == name))
+        .cloned()
+        .collect::<Vec<_>>()
+        .uniques()
+}
+
+/// The names of the constants among the bounds of the interval
+fn interval_constant_names<'a>(interval: &ast::IntervalNode<'a>) -> Vec<&'a str> {
+    [&interval.lo, &interval.hi]
+        .into_iter()
+        .filter_map(|number| match number {
+            ast::Number::Literal(_) => None,
+            ast::Number::Constant(name) => Some(*name),
+        })
+        .collect()
+}
+
+fn resolve_number(constants: &[ir::Constant], number: &ast::Number) -> Num {
+    match number {
+        ast::Number::Literal(value) => *value,
+        ast::Number::Constant(name) => find_constant(constants, name)
+            // Reported as E0102 by `checker::check_root` before the conversion
+            .unwrap_or_else(|| unreachable!("Undefined constant: {name}")),
+    }
+}
+
+fn resolve_interval(constants: &[ir::Constant], interval: &ast::IntervalNode) -> MultiInterval {
+    let lo = resolve_number(constants, &interval.lo);
+    let hi = resolve_number(constants, &interval.hi);
+
+    MultiInterval::new(interval.lo_boundary, lo, hi, interval.hi_boundary)
+        // Reported as E0106 by `checker::check_root` before the conversion
+        .unwrap_or_else(|_| {
+            unreachable!(
+                "Invalid interval: the lower bound {lo} is greater than the upper bound {hi}"
+            )
+        })
+}
+
+/// Undefined variables are reported when the conditions are converted to `NTupleInput`s
+fn find_domain(variables: &[ir::Variable], var_name: &str) -> MultiInterval {
+    variables
+        .iter()
+        .find(|variable| variable.var_name == var_name)
+        .map_or_else(MultiInterval::new_unbounded, ir::Variable::domain)
+}
+
+/// The values of the interval the variable can take with its precision,
+/// like `[200.0, Inf)` for `p >= 199.95`, where `p` is a `num(0.1)`
+fn snap_to_variable(
+    variables: &[ir::Variable],
+    var_name: &str,
+    interval: &MultiInterval,
+) -> MultiInterval {
+    let Some(precision) = variables
+        .iter()
+        .find(|variable| variable.var_name == var_name)
+        .and_then(|variable| variable.var_type.get_precision())
+    else {
+        return interval.clone();
+    };
+
+    MultiInterval::from_intervals(
+        interval
+            .intervals
+            .iter()
+            .filter_map(|interval| snap_to_precision(interval, precision))
+            .collect(),
+    )
+}
+
+/// `named` are the constants the compared number comes from
+fn convert_binary_condition(
+    variables: &[ir::Variable],
+    cond: &ast::BinaryCondition,
+    named: Vec<ir::Constant>,
+) -> ir::Condition {
     // createUnaryIntervalDTO expects the constant to be on the right, like: x < 0
     // If it was inputted on the left like 0 > x we should flip it to be x < 0
     let binary_op = if cond.constant_position == ConstantPosition::LeftHandSide {
@@ -57,46 +154,419 @@ fn convert_binary_condition(cond: &ast::BinaryCondition) -> ir::Condition {
         cond.binary_op.clone()
     };
 
+    let domain = find_domain(variables, cond.var_name);
+
     ir::Condition::Interval(IntervalCondition {
         var_name: cond.var_name.to_owned(),
-        interval: binary_op_to_interval(&binary_op, cond.constant),
+        interval: snap_to_variable(
+            variables,
+            cond.var_name,
+            &binary_op_to_interval(&binary_op, cond.constant).restricted_to(&domain),
+        ),
+        domain,
+        constants: named,
     })
 }
 
-fn convert_interval_condition(cond: &ast::IntervalCondition) -> ir::Condition {
+fn convert_interval_condition(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    cond: &ast::IntervalCondition,
+) -> ir::Condition {
+    let domain = find_domain(variables, cond.var_name);
+    let interval = resolve_interval(constants, &cond.interval);
+
     ir::Condition::Interval(ir::IntervalCondition {
         var_name: cond.var_name.to_owned(),
-        interval: match cond.interval_op {
-            ast::IntervalOp::In => cond.interval.clone(),
-            ast::IntervalOp::NotIn => cond.interval.complement(),
-        },
+        interval: snap_to_variable(
+            variables,
+            cond.var_name,
+            &match cond.interval_op {
+                ast::IntervalOp::In => interval.restricted_to(&domain),
+                ast::IntervalOp::NotIn => interval.complement_within(&domain),
+            },
+        ),
+        domain,
+        constants: named_constants(constants, interval_constant_names(&cond.interval)),
     })
 }
 
-fn convert_condition_node(conditions_node: &ast::ConditionsNode) -> ir::Predicate {
-    match conditions_node {
-        ast::ConditionsNode::Negated(cond) => {
-            ir::Predicate::Negated(Box::new(convert_condition_node(cond)))
+fn convert_length_condition(
+    constants: &[ir::Constant],
+    cond: &ast::LengthCondition,
+) -> ir::Condition {
+    let domain = ir::LengthCondition::domain();
+    let lengths = match &cond.comparison {
+        ast::LengthComparison::Binary(binary_op, length) => {
+            binary_op_to_interval(binary_op, resolve_number(constants, length))
+                .restricted_to(&domain)
+        }
+        ast::LengthComparison::Interval(IntervalOp::In, interval) => {
+            resolve_interval(constants, interval).restricted_to(&domain)
         }
-        ast::ConditionsNode::Expression(cond) => ir::Predicate::Expression(match cond {
-            ast::Condition::Bool(cond) => convert_bool_condition(cond),
-            ast::Condition::Binary(cond) => convert_binary_condition(cond),
-            ast::Condition::Interval(cond) => convert_interval_condition(cond),
-        }),
+        ast::LengthComparison::Interval(IntervalOp::NotIn, interval) => {
+            resolve_interval(constants, interval).complement_within(&domain)
+        }
+    };
+
+    ir::Condition::Length(ir::LengthCondition {
+        var_name: cond.var_name.to_owned(),
+        lengths,
+    })
+}
+
+fn convert_set_condition(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    cond: &ast::SetCondition,
+) -> ir::Condition {
+    let mut values = cond
+        .values
+        .iter()
+        .map(|value| resolve_number(constants, value))
+        .collect::<Vec<_>>();
+    values.sort();
+    values.dedup();
+
+    ir::Condition::Set(ir::SetCondition {
+        var_name: cond.var_name.to_owned(),
+        values,
+        is_member: cond.interval_op == IntervalOp::In,
+        domain: find_domain(variables, cond.var_name),
+    })
+}
+
+fn convert_modulo_condition(
+    constants: &[ir::Constant],
+    cond: &ast::ModuloCondition,
+) -> ir::Condition {
+    ir::Condition::Modulo(ir::ModuloCondition {
+        var_name: cond.var_name.to_owned(),
+        divisor: resolve_number(constants, &cond.divisor),
+        remainder: resolve_number(constants, &cond.remainder),
+        should_equal: cond.eq_op == EqOp::Equal,
+    })
+}
+
+fn convert_enum_condition(variables: &[ir::Variable], cond: &ast::EnumCondition) -> ir::Condition {
+    let members = match variables
+        .iter()
+        .find(|variable| variable.var_name == cond.var_name)
+        .map(|variable| &variable.var_type)
+    {
+        Some(Type::Enum(members)) => members,
+        // Reported as E0104 and E0101 by `checker::check_root` before the conversion
+        Some(_) => unreachable!("Type error: {} is not an enum variable", cond.var_name),
+        None => unreachable!("Undefined variable: {}", cond.var_name),
+    };
+
+    if let Some(member) = cond
+        .members
+        .iter()
+        .find(|member| !members.iter().any(|x| x == *member))
+    {
+        // Reported as E0103 by `checker::check_root` before the conversion
+        unreachable!(
+            "Undefined member {member} of enum variable {}",
+            cond.var_name
+        );
+    }
+
+    let values = members
+        .iter()
+        .filter(|member| {
+            let is_listed = cond.members.contains(&member.as_str());
+            match cond.interval_op {
+                IntervalOp::In => is_listed,
+                IntervalOp::NotIn => !is_listed,
+            }
+        })
+        .cloned()
+        .collect();
+
+    ir::Condition::Enum(ir::EnumCondition {
+        var_name: cond.var_name.to_owned(),
+        values,
+        members: members.clone(),
+    })
+}
+
+fn convert_relation_condition(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    cond: &ast::RelationCondition,
+) -> ir::Condition {
+    let find_type = |var_name: &str| {
+        variables
+            .iter()
+            .find(|variable| variable.var_name == var_name)
+            .map(|variable| &variable.var_type)
+    };
+
+    match (find_type(cond.left), find_type(cond.right)) {
+        // `tier == gold` compares an enum variable with one of its members
+        (Some(Type::Enum(_)), None) => {
+            let interval_op = match cond.binary_op {
+                BinaryOp::Equal => IntervalOp::In,
+                BinaryOp::NotEqual => IntervalOp::NotIn,
+                // Reported as E0104 by `checker::check_root` before the conversion
+                _ => unreachable!(
+                    "Type error: enum variable {} can only be compared with == and !=",
+                    cond.left
+                ),
+            };
+
+            convert_enum_condition(
+                variables,
+                &ast::EnumCondition {
+                    var_name: cond.left,
+                    interval_op,
+                    members: vec![cond.right],
+                },
+            )
+        }
+        // `price >= DISCOUNT_THRESHOLD` compares a number variable with a constant
+        (Some(Type::Integer { .. } | Type::Float { .. }), None) => convert_binary_condition(
+            variables,
+            &ast::BinaryCondition {
+                var_name: cond.left,
+                constant_position: ConstantPosition::RightHandSide,
+                // Reported as E0101 by `checker::check_root` before the conversion
+                constant: find_constant(constants, cond.right)
+                    .unwrap_or_else(|| unreachable!("Undefined variable: {}", cond.right)),
+                binary_op: cond.binary_op.clone(),
+            },
+            named_constants(constants, [cond.right]),
+        ),
+        (None, Some(Type::Integer { .. } | Type::Float { .. })) => convert_binary_condition(
+            variables,
+            &ast::BinaryCondition {
+                var_name: cond.right,
+                constant_position: ConstantPosition::LeftHandSide,
+                // Reported as E0101 by `checker::check_root` before the conversion
+                constant: find_constant(constants, cond.left)
+                    .unwrap_or_else(|| unreachable!("Undefined variable: {}", cond.left)),
+                binary_op: cond.binary_op.clone(),
+            },
+            named_constants(constants, [cond.left]),
+        ),
+        (Some(Type::Bool), Some(Type::Bool))
+            if matches!(cond.binary_op, BinaryOp::Equal | BinaryOp::NotEqual) =>
+        {
+            ir::Condition::Relation(ir::RelationCondition {
+                left: cond.left.to_owned(),
+                binary_op: cond.binary_op.clone(),
+                right: cond.right.to_owned(),
+            })
+        }
+        (
+            Some(Type::Integer { .. } | Type::Float { .. }),
+            Some(Type::Integer { .. } | Type::Float { .. }),
+        ) => {
+            let variable = |var_name| ast::LinearExpression {
+                terms: vec![ast::LinearTerm {
+                    coefficient: Num::ONE,
+                    coefficient_constant: None,
+                    var_name,
+                }],
+                constant: Num::ZERO,
+            };
+
+            convert_linear_condition(
+                variables,
+                constants,
+                &ast::LinearCondition {
+                    lhs: variable(cond.left),
+                    binary_op: cond.binary_op.clone(),
+                    rhs: variable(cond.right),
+                },
+            )
+        }
+        // Reported as E0101 and E0104 by `checker::check_root` before the conversion
+        (None, _) => unreachable!("Undefined variable: {}", cond.left),
+        (_, None) => unreachable!("Undefined variable: {}", cond.right),
+        (Some(_), Some(_)) => unreachable!(
+            "Type error: {} and {} can't be compared with {}",
+            cond.left, cond.right, cond.binary_op
+        ),
+    }
+}
+
+/// Moves every variable to the left and every constant to the right hand side,
+/// so `x + 10 < 2 * y` becomes `x - 2 * y < -10`
+fn convert_linear_condition(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    cond: &ast::LinearCondition,
+) -> ir::Condition {
+    let mut terms: Vec<ir::LinearTerm> = Vec::new();
+    let mut constant = cond.rhs.constant - cond.lhs.constant;
+
+    let lhs_terms = cond.lhs.terms.iter().map(|term| (term, Num::ONE));
+    let rhs_terms = cond.rhs.terms.iter().map(|term| (term, -Num::ONE));
+    for (term, sign) in lhs_terms.chain(rhs_terms) {
+        let coefficient = match term.coefficient_constant {
+            Some(name) => {
+                term.coefficient * resolve_number(constants, &ast::Number::Constant(name))
+            }
+            None => term.coefficient,
+        };
+
+        if let Some(value) = find_constant(constants, term.var_name) {
+            constant -= sign * coefficient * value;
+            continue;
+        }
+
+        match variables
+            .iter()
+            .find(|variable| variable.var_name == term.var_name)
+            .map(|variable| &variable.var_type)
+        {
+            Some(Type::Integer { .. } | Type::Float { .. }) => (),
+            // Reported as E0104 and E0101 by `checker::check_root` before the conversion
+            Some(_) => unreachable!(
+                "Type error: {} is not a number, it can't be used in arithmetic",
+                term.var_name
+            ),
+            None => unreachable!("Undefined variable: {}", term.var_name),
+        }
+
+        match terms
+            .iter_mut()
+            .find(|ir_term| ir_term.var_name == term.var_name)
+        {
+            Some(ir_term) => ir_term.coefficient += sign * coefficient,
+            None => terms.push(ir::LinearTerm {
+                var_name: term.var_name.to_owned(),
+                coefficient: sign * coefficient,
+            }),
+        }
+    }
+
+    terms.retain(|term| term.coefficient != Num::ZERO);
+    if terms.is_empty() {
+        // Reported as E0107 by `checker::check_root` before the conversion
+        unreachable!(
+            "The variables cancel each other out in the condition, it is always true or false"
+        );
+    }
+
+    ir::Condition::Linear(ir::LinearCondition {
+        terms,
+        binary_op: cond.binary_op.clone(),
+        constant,
+    })
+}
+
+/// A condition on a nullable variable only holds if the variable isn't null,
+/// so `coupon > 5` becomes `coupon is not null && coupon > 5`, and its negation tests null as well
+fn guard_nullable(variables: &[ir::Variable], condition: ir::Condition) -> Predicate {
+    if matches!(condition, ir::Condition::Null(_)) {
+        return Predicate::Expression(condition);
+    }
+
+    variables
+        .iter()
+        .filter(|variable| {
+            variable.nullable && condition.variables().contains(&variable.var_name.as_str())
+        })
+        .fold(
+            Predicate::Expression(condition.clone()),
+            |predicate, variable| {
+                Predicate::group(
+                    Predicate::Expression(ir::Condition::Null(ir::NullCondition {
+                        var_name: variable.var_name.clone(),
+                        is_null: false,
+                    })),
+                    BoolOp::And,
+                    predicate,
+                )
+            },
+        )
+}
+
+fn convert_condition_node(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    bindings: &[ast::LetNode],
+    conditions_node: &ast::ConditionsNode,
+) -> ir::Predicate {
+    match conditions_node {
+        ast::ConditionsNode::Negated(cond) => ir::Predicate::Negated(Box::new(
+            convert_condition_node(variables, constants, bindings, cond),
+        )),
+        ast::ConditionsNode::Expression(cond) => guard_nullable(
+            variables,
+            match cond {
+                ast::Condition::Bool(cond) => convert_bool_condition(cond),
+                ast::Condition::Binary(cond) => {
+                    convert_binary_condition(variables, cond, Vec::new())
+                }
+                ast::Condition::Interval(cond) => {
+                    convert_interval_condition(variables, constants, cond)
+                }
+                ast::Condition::Enum(cond) => convert_enum_condition(variables, cond),
+                ast::Condition::Set(cond) => convert_set_condition(variables, constants, cond),
+                ast::Condition::Modulo(cond) => convert_modulo_condition(constants, cond),
+                ast::Condition::Relation(cond) => {
+                    convert_relation_condition(variables, constants, cond)
+                }
+                ast::Condition::Linear(cond) => {
+                    convert_linear_condition(variables, constants, cond)
+                }
+                ast::Condition::Null(cond) => ir::Condition::Null(ir::NullCondition {
+                    var_name: cond.var_name.to_owned(),
+                    is_null: cond.is_null,
+                }),
+                ast::Condition::Length(cond) => convert_length_condition(constants, cond),
+                ast::Condition::Pattern(cond) => ir::Condition::Pattern(ir::PatternCondition {
+                    var_name: cond.var_name.to_owned(),
+                    class: cond.class.clone(),
+                    matches: true,
+                }),
+            },
+        ),
         ast::ConditionsNode::Group {
             operator,
             left,
             right,
         } => ir::Predicate::Group {
-            left: Box::new(convert_condition_node(left)),
-            right: Box::new(convert_condition_node(right)),
+            left: Box::new(convert_condition_node(variables, constants, bindings, left)),
+            right: Box::new(convert_condition_node(
+                variables, constants, bindings, right,
+            )),
             operator: *operator,
         },
+        ast::ConditionsNode::Binding(name) => {
+            let let_node = bindings
+                .iter()
+                .find(|let_node| let_node.name == *name)
+                // Reported as E0111 by `checker::check_root` before the conversion
+                .unwrap_or_else(|| unreachable!("Undefined binding: {name}"));
+
+            ir::Predicate::Derived {
+                predicate: Box::new(convert_condition_node(
+                    variables,
+                    constants,
+                    bindings,
+                    &let_node.conditions,
+                )),
+                name: (*name).to_owned(),
+            }
+        }
     }
 }
 
-fn traverse_body(body: &[IfNode], initial_conditions: &Predicate) -> Vec<Predicate> {
-    let body_conditions = body.iter().flat_map(traverse_if_node);
+fn traverse_body(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    bindings: &[ast::LetNode],
+    body: &[IfNode],
+    initial_conditions: &Predicate,
+) -> Vec<Predicate> {
+    let body_conditions = body
+        .iter()
+        .flat_map(|if_node| traverse_if_node(variables, constants, bindings, if_node));
     body_conditions
         .map(|body_condition| ir::Predicate::Group {
             left: Box::new(initial_conditions.clone()),
@@ -106,85 +576,476 @@ fn traverse_body(body: &[IfNode], initial_conditions: &Predicate) -> Vec<Predica
         .collect()
 }
 
-fn traverse_if_node(if_node: &ast::IfNode) -> Vec<ir::Predicate> {
-    let initial_conditions = convert_condition_node(&if_node.conditions);
+/// Attaches the expected outcome of the branch to its predicate
+fn annotate(predicate: Predicate, outcome: &Option<ast::Outcome>) -> Predicate {
+    match outcome {
+        Some(outcome) => Predicate::Annotated {
+            predicate: Box::new(predicate),
+            outcome: outcome.to_string(),
+        },
+        None => predicate,
+    }
+}
+
+/// Attaches the requirements of the branch or the feature to its predicate
+fn trace(predicate: Predicate, requirements: &[&str]) -> Predicate {
+    if requirements.is_empty() {
+        return predicate;
+    }
+
+    Predicate::Traced {
+        predicate: Box::new(predicate),
+        requirements: requirements.iter().map(|id| (*id).to_owned()).collect(),
+    }
+}
+
+/// `!(a || b || ...)` of the conditions of the earlier branches, so a branch only holds where none
+/// of the earlier ones do
+fn none_of(conditions: &[Predicate]) -> Option<Predicate> {
+    conditions
+        .iter()
+        .cloned()
+        .reduce(|left, right| Predicate::Group {
+            left: Box::new(left),
+            right: Box::new(right),
+            operator: BoolOp::Or,
+        })
+        .map(|earlier| Predicate::Negated(Box::new(earlier)))
+}
 
-    let mut predicates_so_far = match &if_node.body {
-        None => vec![initial_conditions],
-        Some(body) if body.is_empty() => vec![initial_conditions],
-        // TODO: We need the initial conditions as well as the body.
-        Some(body) => traverse_body(body, &initial_conditions), // TODO: the body of the if doesn't have to be negated onto the else ifs
+fn traverse_if_node(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    bindings: &[ast::LetNode],
+    if_node: &ast::IfNode,
+) -> Vec<ir::Predicate> {
+    let expand_body = |branch: Predicate, body: &[IfNode]| match body {
+        [] => vec![branch],
+        body => traverse_body(variables, constants, bindings, body, &branch),
     };
 
+    // The conditions of the branches before the bodies are expanded,
+    // the bodies don't change which branch is taken
+    let mut earlier_conditions = vec![convert_condition_node(
+        variables,
+        constants,
+        bindings,
+        &if_node.conditions,
+    )];
+
+    let mut predicates = expand_body(
+        trace(
+            annotate(earlier_conditions[0].clone(), &if_node.outcome),
+            &if_node.requirements,
+        ),
+        if_node.body.as_deref().unwrap_or_default(),
+    );
+
     for else_if_node in &if_node.else_if {
-        let initial_conditions = convert_condition_node(&else_if_node.conditions);
-        let previous_negated_plus_initial_conditions: Vec<Predicate> = predicates_so_far
-            .iter()
-            .map(|p| ir::Predicate::Group {
-                left: Box::new(ir::Predicate::Negated(Box::new(p.clone()))),
-                right: Box::new(initial_conditions.clone()),
-                operator: BoolOp::And,
-            })
-            .collect();
+        let conditions =
+            convert_condition_node(variables, constants, bindings, &else_if_node.conditions);
+        let branch = Predicate::Group {
+            left: Box::new(none_of(&earlier_conditions).expect("The if is always there")),
+            right: Box::new(conditions.clone()),
+            operator: BoolOp::And,
+        };
+
+        predicates.append(&mut expand_body(
+            trace(
+                annotate(branch, &else_if_node.outcome),
+                &else_if_node.requirements,
+            ),
+            &else_if_node.body,
+        ));
+        earlier_conditions.push(conditions);
+    }
+
+    if let Some(else_node) = &if_node.else_node {
+        let branch = none_of(&earlier_conditions).expect("The if is always there");
+
+        predicates.append(&mut expand_body(
+            trace(
+                annotate(branch, &else_node.outcome),
+                &else_node.requirements,
+            ),
+            &else_node.body,
+        ));
+    }
 
-        let mut else_if_predicates = match &else_if_node.body[..] {
-            [] => previous_negated_plus_initial_conditions,
-            body => previous_negated_plus_initial_conditions
+    predicates
+}
+
+/// The values a switch variable can take. Enum members and bools are points at their index,
+/// so the cases of every type can be split up as intervals.
+fn switch_domain(variable: &ir::Variable) -> MultiInterval {
+    let points = |count: usize| {
+        MultiInterval::new_closed(Num::ZERO, Num::from(count) - Num::ONE)
+            .unwrap_or_else(|_| MultiInterval::new_empty())
+    };
+
+    match &variable.var_type {
+        Type::Bool => points(2),
+        Type::Enum(members) => points(members.len()),
+        Type::Integer { .. } | Type::Float { .. } => variable.domain(),
+        Type::Record => unreachable!("Records are flattened into their fields"),
+        Type::String => unreachable!("A switch on a string is reported by `checker::check_root`"),
+    }
+}
+
+fn convert_case_values(
+    constants: &[ir::Constant],
+    variable: &ir::Variable,
+    case_values: &[CaseValue],
+) -> MultiInterval {
+    case_values
+        .iter()
+        .fold(MultiInterval::new_empty(), |values, case_value| {
+            let value = match (&variable.var_type, case_value) {
+                (_, CaseValue::Interval(interval)) => resolve_interval(constants, interval),
+                (_, CaseValue::Number(number)) => MultiInterval::new_closed_point(*number),
+                (_, CaseValue::Bool(value)) => {
+                    MultiInterval::new_closed_point(if *value { Num::ONE } else { Num::ZERO })
+                }
+                (Type::Enum(members), CaseValue::Name(name)) => members
+                    .iter()
+                    .position(|member| member == name)
+                    .map(|i| MultiInterval::new_closed_point(Num::from(i)))
+                    // Reported as E0103 by `checker::check_root` before the conversion
+                    .unwrap_or_else(|| {
+                        unreachable!(
+                            "Undefined member {name} of enum variable {}",
+                            variable.var_name
+                        )
+                    }),
+                (_, CaseValue::Name(name)) => MultiInterval::new_closed_point(resolve_number(
+                    constants,
+                    &ast::Number::Constant(name),
+                )),
+            };
+
+            values.union(&value)
+        })
+}
+
+/// The names of the constants the case values come from, like `LIMIT` of `case [0, LIMIT)`
+fn case_constant_names<'a>(case_values: &[CaseValue<'a>]) -> Vec<&'a str> {
+    case_values
+        .iter()
+        .flat_map(|case_value| match case_value {
+            CaseValue::Interval(interval) => interval_constant_names(interval),
+            CaseValue::Name(name) => vec![*name],
+            CaseValue::Number(_) | CaseValue::Bool(_) => Vec::new(),
+        })
+        .collect()
+}
+
+/// The condition of the variable taking one of the values of the switch domain.
+/// `named` are the constants the values of the cases come from.
+fn switch_values_predicate(
+    variable: &ir::Variable,
+    values: &MultiInterval,
+    named: &[ir::Constant],
+) -> Predicate {
+    let has_point = |i: usize| {
+        MultiInterval::new_closed_point(Num::from(i))
+            .restricted_to(values)
+            .has_value_with_precision(Num::ONE)
+    };
+
+    match &variable.var_type {
+        Type::Integer { .. } | Type::Float { .. } => {
+            Predicate::Expression(ir::Condition::Interval(IntervalCondition {
+                var_name: variable.var_name.clone(),
+                interval: values.clone(),
+                domain: variable.domain(),
+                constants: named.to_vec(),
+            }))
+        }
+        Type::Enum(members) => Predicate::Expression(ir::Condition::Enum(ir::EnumCondition {
+            var_name: variable.var_name.clone(),
+            values: members
                 .iter()
-                .flat_map(|p| traverse_body(body, p))
+                .enumerate()
+                .filter(|(i, _)| has_point(*i))
+                .map(|(_, member)| member.clone())
                 .collect(),
-        };
+            members: members.clone(),
+        })),
+        Type::Record => unreachable!("Records are flattened into their fields"),
+        Type::String => unreachable!("A switch on a string is reported by `checker::check_root`"),
+        Type::Bool => [false, true]
+            .into_iter()
+            .filter(|value| has_point(usize::from(*value)))
+            .map(|value| {
+                Predicate::Expression(ir::Condition::Bool(ir::BoolCondition {
+                    var_name: variable.var_name.clone(),
+                    should_equal_to: value,
+                }))
+            })
+            .reduce(|left, right| Predicate::Group {
+                left: Box::new(left),
+                right: Box::new(right),
+                operator: BoolOp::Or,
+            })
+            .expect("A case of a bool switch has at least one value"),
+    }
+}
+
+/// The predicate of the values a case matches, or none if they are all taken by earlier cases
+fn case_predicate(
+    variable: &ir::Variable,
+    values: &MultiInterval,
+    named: &[ir::Constant],
+) -> Option<Predicate> {
+    let precision = variable.var_type.get_precision().unwrap_or(Num::ONE);
+    // A case which never matches is reported by `checker::check_root`
+    values
+        .has_value_with_precision(precision)
+        .then(|| switch_values_predicate(variable, values, named))
+}
 
-        predicates_so_far.append(&mut else_if_predicates);
+fn traverse_switch_branch(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    bindings: &[ast::LetNode],
+    predicate: Predicate,
+    outcome: &Option<ast::Outcome>,
+    body: &[IfNode],
+) -> Vec<Predicate> {
+    let predicate = annotate(predicate, outcome);
+    if body.is_empty() {
+        vec![predicate]
+    } else {
+        traverse_body(variables, constants, bindings, body, &predicate)
     }
+}
 
-    let mut else_predicates = {
-        let previous_negated: Vec<Predicate> = predicates_so_far
+/// Every case only gets the values which the earlier cases don't match, and the default gets the rest,
+/// so the branches are disjoint without negating the earlier ones
+fn traverse_switch_node(
+    variables: &[ir::Variable],
+    constants: &[ir::Constant],
+    bindings: &[ast::LetNode],
+    switch_node: &SwitchNode,
+) -> Vec<Predicate> {
+    let variable = variables
+        .iter()
+        .find(|variable| variable.var_name == switch_node.var_name)
+        // Reported as E0101 by `checker::check_root` before the conversion
+        .unwrap_or_else(|| unreachable!("Undefined variable: {}", switch_node.var_name));
+    let domain = switch_domain(variable);
+    // Every branch borders the values of the others, so the constants of every case can be the boundaries of each
+    let named = named_constants(
+        constants,
+        switch_node
+            .cases
             .iter()
-            .map(|p| ir::Predicate::Negated(Box::new(p.clone())))
-            .collect();
+            .flat_map(|case_node| case_constant_names(&case_node.values)),
+    );
 
-        match &if_node.else_node {
-            None => vec![],
-            Some(ElseNode { body }) if body.is_empty() => previous_negated,
-            Some(ElseNode { body }) => previous_negated
-                .iter()
-                .flat_map(|if_predicate| traverse_body(body, if_predicate))
-                .collect(),
+    let mut covered = MultiInterval::new_empty();
+    let mut predicates = Vec::new();
+    for case_node in &switch_node.cases {
+        let values =
+            convert_case_values(constants, variable, &case_node.values).restricted_to(&domain);
+        let matched = covered.complement_within(&values);
+        covered = covered.union(&values);
+
+        if let Some(predicate) = case_predicate(variable, &matched, &named) {
+            predicates.append(&mut traverse_switch_branch(
+                variables,
+                constants,
+                bindings,
+                predicate,
+                &case_node.outcome,
+                &case_node.body,
+            ));
         }
-    };
+    }
 
-    predicates_so_far.append(&mut else_predicates);
+    if let Some(ElseNode { outcome, body, .. }) = &switch_node.default {
+        if let Some(predicate) =
+            case_predicate(variable, &covered.complement_within(&domain), &named)
+        {
+            predicates.append(&mut traverse_switch_branch(
+                variables, constants, bindings, predicate, outcome, body,
+            ));
+        }
 
-    predicates_so_far
+        // Null isn't a value of the domain, so no case matches it
+        if variable.nullable {
+            let predicate = Predicate::Expression(ir::Condition::Null(ir::NullCondition {
+                var_name: variable.var_name.clone(),
+                is_null: true,
+            }));
+            predicates.append(&mut traverse_switch_branch(
+                variables, constants, bindings, predicate, outcome, body,
+            ));
+        }
+    }
+
+    predicates
+}
+
+/// A record variable is flattened into its fields, which are named by their paths, like `order.price`
+fn convert_variables(
+    constants: &[ir::Constant],
+    var_name: &str,
+    var_node: &ast::VarNode,
+) -> Vec<ir::Variable> {
+    if var_node.var_type == Type::Record {
+        return var_node
+            .fields
+            .iter()
+            .flat_map(|field| {
+                convert_variables(constants, &format!("{var_name}.{}", field.var_name), field)
+            })
+            .collect();
+    }
+
+    vec![convert_variable(constants, var_name, var_node)]
 }
 
-fn convert_variable(var_node: &ast::VarNode) -> ir::Variable {
+fn convert_variable(
+    constants: &[ir::Constant],
+    var_name: &str,
+    var_node: &ast::VarNode,
+) -> ir::Variable {
+    if var_node.domain.is_some() && var_node.var_type.get_precision().is_none() {
+        // Reported as E0104 by `checker::check_root` before the conversion
+        unreachable!("Type error: {var_name} is not a number, it can't have a domain");
+    }
+
+    let declared = var_node
+        .domain
+        .as_ref()
+        .map(|domain| resolve_interval(constants, domain));
+
+    // The declared domain of a sized integer is limited to the values of its type
+    let domain = match (declared, var_node.var_type.range()) {
+        (Some(declared), Some(range)) => Some(declared.restricted_to(&range)),
+        (declared, range) => declared.or(range),
+    };
+
     ir::Variable {
-        var_name: var_node.var_name.to_owned(),
-        var_type: var_node.var_type,
+        var_name: var_name.to_owned(),
+        var_type: var_node.var_type.clone(),
+        nullable: var_node.nullable,
+        domain,
     }
 }
 
-fn traverse_feature_node(feature_node: &ast::FeatureNode) -> ir::Feature {
-    let variables = feature_node
-        .variables
+/// Adds the declared constants to the ones already in scope.
+/// A constant can be defined with the value of an earlier one.
+fn convert_constants(
+    constants_in_scope: &[ir::Constant],
+    const_nodes: &[ast::ConstNode],
+) -> Vec<ir::Constant> {
+    const_nodes
         .iter()
-        .map(convert_variable)
+        .fold(constants_in_scope.to_vec(), |mut constants, const_node| {
+            if find_constant(&constants, const_node.name).is_some() {
+                // Reported as E0105 by `checker::check_root` before the conversion
+                unreachable!("Constant {} is already defined", const_node.name);
+            }
+
+            let value = resolve_number(&constants, &const_node.value);
+            constants.push(ir::Constant {
+                name: const_node.name.to_owned(),
+                value,
+            });
+            constants
+        })
+}
+
+/// The variables and the assumptions shared by every feature are declared in the feature as well
+fn traverse_feature_node(
+    root_constants: &[ir::Constant],
+    root_variables: &[ast::VarNode],
+    root_assumptions: &[ast::ConditionsNode],
+    feature_node: &ast::FeatureNode,
+) -> ir::Feature {
+    let constants = convert_constants(root_constants, &feature_node.constants);
+
+    let variables: Vec<ir::Variable> = root_variables
+        .iter()
+        .chain(feature_node.variables.iter())
+        .flat_map(|var_node| convert_variables(&constants, var_node.var_name, var_node))
         .collect();
 
+    if let Some(variable) = variables.iter().enumerate().find_map(|(i, variable)| {
+        variables[..i]
+            .iter()
+            .any(|other| other.var_name == variable.var_name)
+            .then_some(variable)
+    }) {
+        // Reported as E0105 by `checker::check_root` before the conversion
+        unreachable!("Variable {} is already declared", variable.var_name);
+    }
+
+    if let Some(variable) = variables
+        .iter()
+        .find(|variable| find_constant(&constants, &variable.var_name).is_some())
+    {
+        // Reported as E0105 by `checker::check_root` before the conversion
+        unreachable!(
+            "{} is declared both as a variable and as a constant",
+            variable.var_name
+        );
+    }
+
     let predicates = feature_node
         .if_statements
         .iter()
-        .flat_map(traverse_if_node)
+        .flat_map(|if_node| {
+            traverse_if_node(&variables, &constants, &feature_node.bindings, if_node)
+        })
+        .chain(
+            feature_node
+                .switch_statements
+                .iter()
+                .flat_map(|switch_node| {
+                    traverse_switch_node(
+                        &variables,
+                        &constants,
+                        &feature_node.bindings,
+                        switch_node,
+                    )
+                }),
+        )
+        .map(|predicate| trace(predicate, &feature_node.requirements))
+        .collect();
+
+    let assumptions = root_assumptions
+        .iter()
+        .chain(feature_node.assumptions.iter())
+        .map(|conditions| {
+            convert_condition_node(&variables, &constants, &feature_node.bindings, conditions)
+        })
         .collect();
 
     ir::Feature {
+        constants,
         variables,
         predicates,
+        assumptions,
+        instance: feature_node.instance.as_ref().map(ToString::to_string),
     }
 }
 
+/// The features are expected to be checked by `checker::check_root`, which reports every error the conversion can't handle
 pub fn convert_ast_to_ir<'a>(root: &'a RootNode<'a>) -> Vec<ir::Feature> {
-    root.features.iter().map(traverse_feature_node).collect()
+    let root_constants = convert_constants(&[], &root.constants);
+
+    root.features
+        .iter()
+        .map(|feature_node| {
+            traverse_feature_node(
+                &root_constants,
+                &root.variables,
+                &root.assumptions,
+                feature_node,
+            )
+        })
+        .collect()
 }
@@ -1,10 +1,19 @@
 use std::collections::HashMap;
 
-use super::{BoolCondition, Condition, Feature, IntervalCondition};
+use super::{
+    ast_to_ir::binary_op_to_interval, BoolCondition, Condition, EnumCondition, Feature,
+    IntervalCondition, LengthCondition, ModuloCondition, NullCondition, PatternCondition,
+    Predicate, SetCondition,
+};
 use crate::{
-    dto::{BoolDTO, BoolExpression, Input, IntervalDTO, NTupleInput},
-    interval::Intersectable,
+    dto::{
+        AssumptionDTO, BoolDTO, BoolExpression, BoolRelationDTO, ConstantDTO, EnumDTO, Input,
+        IntervalDTO, LinearDTO, LinearTermDTO, ModuloDTO, NTupleInput, PatternDTO, PeriodicDTO,
+        RelationDTO, SetDTO, StringDTO,
+    },
+    interval::{Intersectable, MultiInterval, Unionable},
     ir,
+    parser::ast::{BinaryOp, BoolOp, Type},
     util::UniquesVec,
 };
 
@@ -27,16 +36,308 @@ fn convert_interval_dto(variable: &ir::Variable, condition: &ir::IntervalConditi
         interval: condition.interval.clone(),
         precision,
         is_constant: false,
+        domain: condition.domain.clone(),
+    }
+}
+
+fn convert_enum_dto(condition: ir::EnumCondition) -> EnumDTO {
+    EnumDTO {
+        values: condition.values,
+        members: condition.members,
+        is_constant: false,
+    }
+}
+
+fn convert_set_dto(variable: &ir::Variable, condition: ir::SetCondition) -> SetDTO {
+    let precision = variable.var_type.get_precision().expect("Type error: when converting a set dto in convert_set_dto, the variable type doesn't have a precision!");
+
+    SetDTO {
+        values: condition.values,
+        is_member: condition.is_member,
+        precision,
+        is_constant: false,
+        domain: condition.domain,
+    }
+}
+
+/// Both the set condition and another condition of the same variable, or `None` if they contradict.
+/// Excluded values of a `not in` set become holes in an interval.
+fn intersect_set_condition(set: &SetCondition, other: &Condition) -> Option<Condition> {
+    let (values, is_member) = match other {
+        Condition::Set(other) => match (set.is_member, other.is_member) {
+            (true, true) => (
+                set.values
+                    .iter()
+                    .filter(|value| other.values.contains(value))
+                    .copied()
+                    .collect::<Vec<_>>(),
+                true,
+            ),
+            (true, false) | (false, true) => {
+                let (included, excluded) = if set.is_member {
+                    (set, other)
+                } else {
+                    (other, set)
+                };
+                (
+                    included
+                        .values
+                        .iter()
+                        .filter(|value| !excluded.values.contains(value))
+                        .copied()
+                        .collect(),
+                    true,
+                )
+            }
+            (false, false) => {
+                let mut values = [set.values.as_slice(), other.values.as_slice()].concat();
+                values.sort();
+                values.dedup();
+                (values, false)
+            }
+        },
+        Condition::Interval(IntervalCondition {
+            interval,
+            domain,
+            constants,
+            ..
+        }) => {
+            if !set.is_member {
+                let interval = set.points().complement_within(interval);
+                return (!interval.is_empty()).then(|| {
+                    Condition::Interval(IntervalCondition {
+                        var_name: set.var_name.clone(),
+                        interval,
+                        domain: domain.clone(),
+                        constants: constants.clone(),
+                    })
+                });
+            }
+
+            (
+                set.values
+                    .iter()
+                    .filter(|value| {
+                        interval
+                            .intervals
+                            .iter()
+                            .any(|interval| interval.contains_point(**value))
+                    })
+                    .copied()
+                    .collect(),
+                true,
+            )
+        }
+        other => panic!("Mismatched types in predicate! Variable {} has conditions of different kinds! {set:#?} and {other:#?}", set.var_name),
+    };
+
+    (!is_member || !values.is_empty()).then(|| {
+        Condition::Set(SetCondition {
+            var_name: set.var_name.clone(),
+            values,
+            is_member,
+            domain: set.domain.clone(),
+        })
+    })
+}
+
+/// Attaches the modulo conditions of a variable to its other condition after they are intersected.
+/// Only the members of a set satisfying them are kept, or `None` if there are none.
+fn convert_periodic_input(
+    variable: &ir::Variable,
+    condition: Option<&Condition>,
+    modulos: &[ModuloCondition],
+) -> Option<Input> {
+    let interval = match condition {
+        None | Some(Condition::Null(NullCondition { is_null: false, .. })) => variable.domain(),
+        Some(Condition::Null(_)) => return None,
+        Some(Condition::Interval(cond)) => cond.interval.clone(),
+        Some(Condition::Set(set)) if set.is_member => {
+            let values = set
+                .values
+                .iter()
+                .filter(|value| modulos.iter().all(|modulo| modulo.holds_for(**value)))
+                .copied()
+                .collect::<Vec<_>>();
+
+            return (!values.is_empty()).then(|| {
+                Input::Set(convert_set_dto(
+                    variable,
+                    SetCondition {
+                        values,
+                        ..set.clone()
+                    },
+                ))
+            });
+        }
+        // The excluded values become holes in the domain
+        Some(Condition::Set(set)) => set.points().complement_within(&set.domain),
+        Some(cond) => panic!(
+            "Mismatched types in predicate! Variable {} has modulo conditions and {cond:#?}",
+            variable.var_name
+        ),
+    };
+    let precision = variable.var_type.get_precision().expect("Type error: when converting a periodic dto in convert_periodic_input, the variable type doesn't have a precision!");
+
+    Some(Input::Periodic(PeriodicDTO {
+        interval,
+        modulos: modulos
+            .iter()
+            .map(|modulo| ModuloDTO {
+                divisor: modulo.divisor,
+                remainder: modulo.remainder,
+                should_equal: modulo.should_equal,
+            })
+            .collect(),
+        precision,
+        is_constant: false,
+        domain: variable.domain(),
+    }))
+}
+
+/// Attaches the pattern conditions of a string variable to its length condition after they are intersected,
+/// or returns `None` if a class has to be both matched and violated
+fn convert_string_input(
+    variable: &ir::Variable,
+    condition: Option<&Condition>,
+    patterns: &[PatternCondition],
+) -> Option<Input> {
+    let lengths = match condition {
+        None | Some(Condition::Null(NullCondition { is_null: false, .. })) => {
+            LengthCondition::domain()
+        }
+        Some(Condition::Null(_)) => return None,
+        Some(Condition::Length(cond)) => cond.lengths.clone(),
+        Some(cond) => panic!(
+            "Mismatched types in predicate! Variable {} has pattern conditions and {cond:#?}",
+            variable.var_name
+        ),
+    };
+
+    let mut pattern_dtos: Vec<PatternDTO> = Vec::new();
+    for pattern in patterns {
+        match pattern_dtos
+            .iter()
+            .find(|other| other.class == pattern.class)
+        {
+            Some(other) if other.matches != pattern.matches => return None,
+            Some(_) => (),
+            None => pattern_dtos.push(PatternDTO {
+                class: pattern.class.clone(),
+                matches: pattern.matches,
+            }),
+        }
+    }
+
+    Some(Input::String(StringDTO {
+        lengths,
+        patterns: pattern_dtos,
+        is_constant: false,
+    }))
+}
+
+/// Any value of the variable, which only has to be there
+fn convert_not_null(variable: &ir::Variable) -> Input {
+    match &variable.var_type {
+        // Either value would do, the relations between bools pick their own
+        Type::Bool => Input::Bool(convert_bool_dto(&BoolCondition {
+            var_name: variable.var_name.clone(),
+            should_equal_to: true,
+        })),
+        Type::Integer { .. } | Type::Float { .. } => Input::Interval(convert_interval_dto(
+            variable,
+            &IntervalCondition {
+                var_name: variable.var_name.clone(),
+                interval: variable.domain(),
+                domain: variable.domain(),
+                constants: Vec::new(),
+            },
+        )),
+        Type::Enum(members) => Input::Enum(EnumDTO {
+            values: members.clone(),
+            members: members.clone(),
+            is_constant: false,
+        }),
+        Type::String => Input::String(StringDTO {
+            lengths: LengthCondition::domain(),
+            patterns: Vec::new(),
+            is_constant: false,
+        }),
+        Type::Record => unreachable!("Records are flattened into their fields"),
     }
 }
 
 fn convert_condition(variable: &ir::Variable, condition: ir::Condition) -> Input {
     match condition {
+        ir::Condition::Null(NullCondition { is_null: true, .. }) => {
+            Input::Null(Box::new(convert_not_null(variable)))
+        }
+        ir::Condition::Null(_) => convert_not_null(variable),
         ir::Condition::Bool(cond) => Input::Bool(convert_bool_dto(&cond)),
         ir::Condition::Interval(cond) => Input::Interval(convert_interval_dto(variable, &cond)),
+        ir::Condition::Enum(cond) => Input::Enum(convert_enum_dto(cond)),
+        ir::Condition::Set(cond) => Input::Set(convert_set_dto(variable, cond)),
+        ir::Condition::Length(cond) => Input::String(StringDTO {
+            lengths: cond.lengths,
+            patterns: Vec::new(),
+            is_constant: false,
+        }),
+        ir::Condition::Relation(_) | ir::Condition::Linear(_) => {
+            unreachable!(
+                "Relations are converted separately by convert_relation_dto and convert_linear_dto"
+            )
+        }
+        ir::Condition::Modulo(_) => {
+            unreachable!("Modulo conditions are converted separately by convert_periodic_input")
+        }
+        ir::Condition::Pattern(_) => {
+            unreachable!("Pattern conditions are converted separately by convert_string_input")
+        }
     }
 }
 
+fn convert_relation_dto(condition: &ir::RelationCondition) -> RelationDTO {
+    RelationDTO::Bool(BoolRelationDTO {
+        left: condition.left.clone(),
+        right: condition.right.clone(),
+        should_equal: match condition.binary_op {
+            BinaryOp::Equal => true,
+            BinaryOp::NotEqual => false,
+            _ => unreachable!("Bool variables can only be related with == and !="),
+        },
+    })
+}
+
+fn convert_linear_dto(variables: &[ir::Variable], condition: &ir::LinearCondition) -> RelationDTO {
+    let terms = condition
+        .terms
+        .iter()
+        .map(|term| {
+            let variable = variables
+                .iter()
+                .find(|variable| variable.var_name == term.var_name)
+                // Reported by `checker::check_root` before the conversion
+                .unwrap_or_else(|| panic!("Undefined variable: {}", term.var_name));
+            let precision = variable
+                .var_type
+                .get_precision()
+                .expect("Type error: when converting a linear dto in convert_linear_dto, the variable type doesn't have a precision!");
+
+            LinearTermDTO {
+                var_name: term.var_name.clone(),
+                coefficient: term.coefficient,
+                precision,
+                domain: variable.domain(),
+            }
+        })
+        .collect();
+
+    RelationDTO::Linear(LinearDTO {
+        terms,
+        interval: binary_op_to_interval(&condition.binary_op, condition.constant),
+    })
+}
+
 // fn sort_objects_into_tuple<TTupleElem, TObject, TCompare>(
 //     tuple_format: Vec<TTupleElem>,
 //     objects: &Vec<TObject>,
@@ -64,18 +365,66 @@ fn convert_condition(variable: &ir::Variable, condition: ir::Condition) -> Input
 
 fn convert_predicate_to_ntuple(
     variables: &[ir::Variable],
+    instance: Option<&String>,
     predicate: &ir::Predicate,
 ) -> Vec<NTupleInput> {
+    let expected = predicate.outcomes();
+    let requirements = predicate.requirements().uniques();
+    let bindings = predicate.bindings().uniques();
+
     predicate
         .conjunction_of_conditions()
         .into_iter()
         .filter_map(|conditions| {
+            let (relations, conditions): (Vec<_>, Vec<_>) = conditions
+                .into_iter()
+                .partition(|cond| matches!(cond, Condition::Relation(_) | Condition::Linear(_)));
+            // The relations give their variables a value, so they don't need one to not be null
+            let related = relations
+                .iter()
+                .flat_map(Condition::variables)
+                .map(str::to_owned)
+                .collect::<Vec<_>>();
+            let relations = relations
+                .iter()
+                .filter_map(|cond| match cond {
+                    Condition::Relation(cond) => Some(convert_relation_dto(cond)),
+                    Condition::Linear(cond) => Some(convert_linear_dto(variables, cond)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let (modulos, conditions): (Vec<_>, Vec<_>) = conditions
+                .into_iter()
+                .partition(|cond| matches!(cond, Condition::Modulo(_)));
+            let mut modulos_of_variables = HashMap::<String, Vec<ModuloCondition>>::new();
+            for cond in modulos {
+                if let Condition::Modulo(cond) = cond {
+                    modulos_of_variables
+                        .entry(cond.var_name.clone())
+                        .or_default()
+                        .push(cond);
+                }
+            }
+            let (patterns, conditions): (Vec<_>, Vec<_>) = conditions
+                .into_iter()
+                .partition(|cond| matches!(cond, Condition::Pattern(_)));
+            let mut patterns_of_variables = HashMap::<String, Vec<PatternCondition>>::new();
+            for cond in patterns {
+                if let Condition::Pattern(cond) = cond {
+                    patterns_of_variables
+                        .entry(cond.var_name.clone())
+                        .or_default()
+                        .push(cond);
+                }
+            }
+
             conditions
                 .into_iter()
                 .fold(
                     Some(HashMap::<String, Condition>::new()),
                     |ntuple, cond| {
-                        ntuple.map(|mut ntuple| {
+                        // A variable without values in common in its conditions makes the whole conjunction infeasible
+                        ntuple.and_then(|mut ntuple| {
                             let var_name = cond.get_variable();
                             let to_insert = match (&cond, ntuple.get(var_name)) {
                                 (x, None) => Some(x.clone()),
@@ -100,55 +449,279 @@ fn convert_predicate_to_ntuple(
                                 }
                                 (
                                     Condition::Interval(IntervalCondition {
-                                        interval: old, ..
+                                        interval: old,
+                                        domain,
+                                        constants: old_constants,
+                                        ..
                                     }),
                                     Some(Condition::Interval(IntervalCondition {
                                         interval: new,
+                                        constants: new_constants,
                                         ..
                                     })),
                                 ) => old.intersect(new).map(|intersection| {
                                     Condition::Interval(IntervalCondition {
                                         interval: intersection,
                                         var_name: var_name.to_owned(),
+                                        domain: domain.clone(),
+                                        constants: [old_constants.as_slice(), new_constants.as_slice()]
+                                            .concat()
+                                            .uniques(),
                                     })
                                 }),
-                                (x, y) => panic!("Mismatched types in predicate! Variable {var_name} has both a boolean and an interval condition! {x:#?} and {y:#?}")
+                                (
+                                    Condition::Enum(EnumCondition {
+                                        values: old, members, ..
+                                    }),
+                                    Some(Condition::Enum(EnumCondition {
+                                        values: new,
+                                        ..
+                                    })),
+                                ) => {
+                                    let values = old
+                                        .iter()
+                                        .filter(|value| new.contains(value))
+                                        .cloned()
+                                        .collect::<Vec<_>>();
+
+                                    if values.is_empty() {
+                                        None
+                                    } else {
+                                        Some(Condition::Enum(EnumCondition {
+                                            var_name: var_name.to_owned(),
+                                            values,
+                                            members: members.clone(),
+                                        }))
+                                    }
+                                }
+                                (
+                                    Condition::Length(LengthCondition { lengths: old, .. }),
+                                    Some(Condition::Length(LengthCondition { lengths: new, .. })),
+                                ) => old.intersect(new).map(|lengths| {
+                                    Condition::Length(LengthCondition {
+                                        var_name: var_name.to_owned(),
+                                        lengths,
+                                    })
+                                }),
+                                // A value can't be null, but it is never null if it has to have a value
+                                (
+                                    Condition::Null(NullCondition { is_null: old, .. }),
+                                    Some(Condition::Null(NullCondition { is_null: new, .. })),
+                                ) => (old == new).then(|| cond.clone()),
+                                (Condition::Null(NullCondition { is_null: true, .. }), Some(_))
+                                | (_, Some(Condition::Null(NullCondition { is_null: true, .. }))) => None,
+                                (Condition::Null(_), Some(other)) => Some(other.clone()),
+                                (other, Some(Condition::Null(_))) => Some(other.clone()),
+                                (Condition::Set(set), Some(other)) => {
+                                    intersect_set_condition(set, other)
+                                }
+                                (other, Some(Condition::Set(set))) => {
+                                    intersect_set_condition(set, other)
+                                }
+                                (x, y) => panic!("Mismatched types in predicate! Variable {var_name} has conditions of different kinds! {x:#?} and {y:#?}")
                             };
-                            if let Some(to_insert) = to_insert {
-                                ntuple.insert(var_name.to_owned(), to_insert);
-                            }
-                            ntuple
+                            ntuple.insert(var_name.to_owned(), to_insert?);
+                            Some(ntuple)
                         })
                     },
                 )
-                .map(|x| x.into_iter().map(|(var_name, condition)| {
-                    let variable = variables
+                .map(|mut ntuple| {
+                    ntuple.retain(|var_name, cond| {
+                        !matches!(cond, Condition::Null(NullCondition { is_null: false, .. }))
+                            || !related.contains(var_name)
+                    });
+                    ntuple
+                })
+                .and_then(|ntuple| {
+                    let constants = ntuple
+                        .values()
+                        .filter_map(|cond| match cond {
+                            Condition::Interval(cond) => Some(cond),
+                            _ => None,
+                        })
+                        .flat_map(|cond| {
+                            cond.constants.iter().map(|constant| ConstantDTO {
+                                var_name: cond.var_name.clone(),
+                                name: constant.name.clone(),
+                                value: constant.value,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+
+                    let inputs = ntuple
+                        .keys()
+                        .chain(modulos_of_variables.keys())
+                        .chain(patterns_of_variables.keys())
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .uniques()
+                        .into_iter()
+                        .map(|var_name| {
+                            let variable = variables
+                                .iter()
+                                .find(|variable| var_name.as_str() == variable.var_name)
+                                // Reported by `checker::check_root` before the conversion
+                                .unwrap_or_else(|| panic!("Undefined variable: {var_name}"));
+                            let input = match (
+                                modulos_of_variables.get(&var_name),
+                                patterns_of_variables.get(&var_name),
+                            ) {
+                                (Some(modulos), _) => {
+                                    convert_periodic_input(variable, ntuple.get(&var_name), modulos)?
+                                }
+                                (None, Some(patterns)) => {
+                                    convert_string_input(variable, ntuple.get(&var_name), patterns)?
+                                }
+                                (None, None) => {
+                                    convert_condition(variable, ntuple[&var_name].clone())
+                                }
+                            };
+
+                            Some((var_name, input))
+                        })
+                        .collect::<Option<HashMap<_, _>>>()?;
+
+                    Some((inputs, constants))
+                })
+                .map(|(inputs, constants)| NTupleInput {
+                    sized_variables: variables
                         .iter()
-                        .find(|variable| var_name.as_str() == variable.var_name)
-                        // TODO: This should be an actual error in a Result type
-                        .unwrap_or_else(|| {
-                            panic!("Undefined variable: {var_name}")
-                        });
-                    (
-                        var_name,
-                        convert_condition(variable, condition),
-                    )
-                }))
-                .map(|x| NTupleInput {inputs: x.collect() })
+                        .filter(|variable| {
+                            variable.var_type.range().is_some()
+                                && (inputs.contains_key(&variable.var_name)
+                                    || related.contains(&variable.var_name))
+                        })
+                        .map(|variable| variable.var_name.clone())
+                        .collect(),
+                    nullable_variables: variables
+                        .iter()
+                        .filter(|variable| {
+                            variable.nullable
+                                && (inputs.contains_key(&variable.var_name)
+                                    || related.contains(&variable.var_name))
+                        })
+                        .map(|variable| variable.var_name.clone())
+                        .collect(),
+                    inputs,
+                    relations,
+                    expected: expected.clone(),
+                    requirements: requirements.clone(),
+                    instances: instance.cloned().into_iter().collect(),
+                    bindings: bindings.clone(),
+                    assumptions: Vec::new(),
+                    constants,
+                })
         })
         .collect()
 }
 
-pub fn ir_to_ntuple(
+/// The ways to satisfy every assumption of the feature, each with its own conditions on the inputs
+fn convert_assumptions(
+    variables: &[ir::Variable],
+    assumptions: &[Predicate],
+) -> Vec<AssumptionDTO> {
+    assumptions
+        .iter()
+        .cloned()
+        .reduce(|left, right| Predicate::group(left, BoolOp::And, right))
+        .map_or_else(Vec::new, |predicate| {
+            convert_predicate_to_ntuple(variables, None, &predicate)
+                .into_iter()
+                .map(|ntuple| AssumptionDTO {
+                    inputs: ntuple.inputs,
+                    relations: ntuple.relations,
+                })
+                .collect()
+        })
+}
+
+/// Whether the inputs of the n-tuple leave some values for the inputs of the assumption.
+/// The relations of the assumption are only checked on the test cases.
+fn can_satisfy(ntuple: &NTupleInput, assumption: &AssumptionDTO) -> bool {
+    assumption.inputs.iter().all(|(var_name, assumed)| {
+        match (ntuple.inputs.get(var_name), assumed) {
+            (Some(Input::Interval(input)), Input::Interval(assumed)) => {
+                input.interval.intersects_with(&assumed.interval)
+            }
+            (Some(Input::Bool(input)), Input::Bool(assumed)) => input.bool_val == assumed.bool_val,
+            (Some(Input::Enum(input)), Input::Enum(assumed)) => input
+                .values
+                .iter()
+                .any(|value| assumed.values.contains(value)),
+            _ => true,
+        }
+    })
+}
+
+/// Narrows the number intervals of the n-tuple to the values allowed by the assumptions it can satisfy.
+/// A branch which can't satisfy any of them is kept as it is, because the test cases of not taking it can still satisfy them.
+///
+/// Every test case is narrowed to the assumptions when it's generated, and the ones which can't satisfy any are dropped.
+fn apply_assumptions(mut ntuple: NTupleInput, assumptions: &[AssumptionDTO]) -> NTupleInput {
+    let satisfiable = assumptions
+        .iter()
+        .filter(|assumption| can_satisfy(&ntuple, assumption))
+        .collect::<Vec<_>>();
+    ntuple.assumptions = assumptions.to_vec();
+    if satisfiable.is_empty() {
+        return ntuple;
+    }
+
+    for (var_name, input) in &mut ntuple.inputs {
+        let Input::Interval(IntervalDTO { interval, .. }) = input else {
+            continue;
+        };
+        let allowed = satisfiable
+            .iter()
+            .map(|assumption| match assumption.inputs.get(var_name) {
+                Some(Input::Interval(assumed)) => assumed.interval.clone(),
+                _ => MultiInterval::new_unbounded(),
+            })
+            .reduce(|left, right| left.union(&right))
+            .unwrap_or_else(MultiInterval::new_unbounded);
+        *interval = interval.restricted_to(&allowed);
+    }
+
+    ntuple
+}
+
+pub fn ir_to_ntuple(feature: &Feature) -> Vec<NTupleInput> {
+    let (ntuples, _) = ir_to_ntuple_and_count_infeasible(feature);
+    ntuples
+}
+
+/// Converts the predicates of the feature to n-tuples, and counts the branches which can't satisfy its assumptions
+pub fn ir_to_ntuple_and_count_infeasible(
     Feature {
         variables,
         predicates,
+        assumptions,
+        instance,
+        ..
     }: &Feature,
-) -> Vec<NTupleInput> {
-    predicates
+) -> (Vec<NTupleInput>, usize) {
+    let assumptions = convert_assumptions(variables, assumptions);
+
+    let ntuples = predicates
         .clone()
         .iter()
-        .flat_map(|predicate| convert_predicate_to_ntuple(variables, predicate))
+        .flat_map(|predicate| convert_predicate_to_ntuple(variables, instance.as_ref(), predicate))
         .collect::<Vec<_>>()
-        .uniques()
+        .uniques();
+    let infeasible = ntuples
+        .iter()
+        .filter(|ntuple| {
+            !assumptions.is_empty()
+                && !assumptions
+                    .iter()
+                    .any(|assumption| can_satisfy(ntuple, assumption))
+        })
+        .count();
+
+    let ntuples = ntuples
+        .into_iter()
+        .map(|ntuple| apply_assumptions(ntuple, &assumptions))
+        .collect::<Vec<_>>();
+
+    (ntuples.uniques(), infeasible)
 }
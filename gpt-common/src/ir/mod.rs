@@ -3,18 +3,39 @@ use std::fmt;
 use itertools::Itertools;
 
 use crate::{
-    interval::MultiInterval,
-    parser::ast::{BoolOp, Type},
+    interval::{Boundary, Interval, MultiInterval},
+    number::Num,
+    parser::ast::{BinaryOp, BoolOp, CharClass, Type},
     util::{ContinousSublistsFromFirst, UniquesVec},
 };
 
 pub mod ast_to_ir;
 pub mod ir_to_ntuple;
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Variable {
     pub var_name: String,
     pub var_type: Type,
+    /// Whether the variable can be null, like `coupon` in `var coupon: int?`
+    pub nullable: bool,
+    /// The declared values of a number variable, like `[0, 130]` in `var age: int in [0, 130]`
+    pub domain: Option<MultiInterval>,
+}
+
+impl Variable {
+    /// The declared domain, or `(-Inf, Inf)` if there is none
+    pub fn domain(&self) -> MultiInterval {
+        self.domain
+            .clone()
+            .unwrap_or_else(MultiInterval::new_unbounded)
+    }
+}
+
+/// A named number, like `const DISCOUNT_THRESHOLD = 200`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Constant {
+    pub name: String,
+    pub value: Num,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -23,16 +44,156 @@ pub struct BoolCondition {
     pub should_equal_to: bool,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IntervalCondition {
     pub var_name: String,
     pub interval: MultiInterval,
+    /// The domain of the variable, so the condition can be negated without the variable at hand
+    pub domain: MultiInterval,
+    /// The named constants the bounds come from, like `DISCOUNT_THRESHOLD` of `price >= DISCOUNT_THRESHOLD`
+    pub constants: Vec<Constant>,
+}
+
+/// The variable has to take one of the `values`. `members` are all the members
+/// of the enum type, so the condition can be negated without the type at hand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumCondition {
+    pub var_name: String,
+    pub values: Vec<String>,
+    pub members: Vec<String>,
+}
+
+/// The variable has to be one of the `values`, or none of them if `is_member` is false
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetCondition {
+    pub var_name: String,
+    /// Sorted and without duplicates
+    pub values: Vec<Num>,
+    pub is_member: bool,
+    pub domain: MultiInterval,
+}
+
+impl SetCondition {
+    /// The values of the set as single points
+    pub fn points(&self) -> MultiInterval {
+        MultiInterval::from_intervals(
+            self.values
+                .iter()
+                .map(|value| Interval::new_closed_point(*value))
+                .collect(),
+        )
+    }
+}
+
+/// `var_name % divisor == remainder`, or `!=` if `should_equal` is false.
+/// The remainder is never negative, like the one of `Num::rem_euclid`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuloCondition {
+    pub var_name: String,
+    pub divisor: Num,
+    pub remainder: Num,
+    pub should_equal: bool,
+}
+
+impl ModuloCondition {
+    pub fn holds_for(&self, value: Num) -> bool {
+        (value.rem_euclid(self.divisor) == self.remainder) == self.should_equal
+    }
+}
+
+/// `left binary_op right`, where both sides are bool variables.
+/// Numeric variables are compared with a `LinearCondition`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelationCondition {
+    pub left: String,
+    pub binary_op: BinaryOp,
+    pub right: String,
+}
+
+/// `var_name is null`, or `var_name is not null` if `is_null` is false
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NullCondition {
+    pub var_name: String,
+    pub is_null: bool,
+}
+
+/// The length of a string variable is one of the `lengths`, like `len(username) in [3, 16]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LengthCondition {
+    pub var_name: String,
+    pub lengths: MultiInterval,
+}
+
+impl LengthCondition {
+    /// The lengths a string can have, `[0, Inf)`
+    pub fn domain() -> MultiInterval {
+        MultiInterval::new(Boundary::Closed, Num::ZERO, Num::INFINITY, Boundary::Open)
+            .expect("[0, Inf) is a valid interval")
+    }
+}
+
+/// Every character of the string variable is in the class, or some character isn't if `matches` is false
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternCondition {
+    pub var_name: String,
+    pub class: CharClass,
+    pub matches: bool,
+}
+
+/// `coefficient * var_name`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearTerm {
+    pub var_name: String,
+    pub coefficient: Num,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// The sum of the terms compared to a constant, like `2 * x - y >= 10`.
+/// Every variable is in at most one of the terms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearCondition {
+    pub terms: Vec<LinearTerm>,
+    pub binary_op: BinaryOp,
+    pub constant: Num,
+}
+
+impl fmt::Display for LinearCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (
+            i,
+            LinearTerm {
+                var_name,
+                coefficient,
+            },
+        ) in self.terms.iter().enumerate()
+        {
+            let sign = match (i, coefficient.is_sign_negative()) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+            match coefficient.abs() {
+                coefficient if coefficient == Num::ONE => write!(f, "{sign}{var_name}")?,
+                coefficient => write!(f, "{sign}{coefficient} * {var_name}")?,
+            }
+        }
+
+        write!(f, " {} {}", self.binary_op, self.constant)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Condition {
     Bool(BoolCondition),
     Interval(IntervalCondition),
+    Enum(EnumCondition),
+    Set(SetCondition),
+    Modulo(ModuloCondition),
+    Relation(RelationCondition),
+    Linear(LinearCondition),
+    Null(NullCondition),
+    Length(LengthCondition),
+    Pattern(PatternCondition),
 }
 
 impl Condition {
@@ -40,6 +201,27 @@ impl Condition {
         match self {
             Self::Bool(cond) => cond.var_name.as_str(),
             Self::Interval(cond) => cond.var_name.as_str(),
+            Self::Enum(cond) => cond.var_name.as_str(),
+            Self::Set(cond) => cond.var_name.as_str(),
+            Self::Modulo(cond) => cond.var_name.as_str(),
+            Self::Relation(cond) => cond.left.as_str(),
+            Self::Linear(cond) => cond.terms.first().map_or("", |term| term.var_name.as_str()),
+            Self::Null(cond) => cond.var_name.as_str(),
+            Self::Length(cond) => cond.var_name.as_str(),
+            Self::Pattern(cond) => cond.var_name.as_str(),
+        }
+    }
+
+    /// Every variable of the condition, like both sides of a relation
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            Self::Relation(cond) => vec![cond.left.as_str(), cond.right.as_str()],
+            Self::Linear(cond) => cond
+                .terms
+                .iter()
+                .map(|term| term.var_name.as_str())
+                .collect(),
+            cond => vec![cond.get_variable()],
         }
     }
 
@@ -51,7 +233,49 @@ impl Condition {
             }),
             Self::Interval(cond) => Self::Interval(IntervalCondition {
                 var_name: cond.var_name.clone(),
-                interval: cond.interval.complement(),
+                interval: cond.interval.complement_within(&cond.domain),
+                domain: cond.domain.clone(),
+                constants: cond.constants.clone(),
+            }),
+            Self::Enum(cond) => Self::Enum(EnumCondition {
+                var_name: cond.var_name.clone(),
+                values: cond
+                    .members
+                    .iter()
+                    .filter(|member| !cond.values.contains(member))
+                    .cloned()
+                    .collect(),
+                members: cond.members.clone(),
+            }),
+            Self::Set(cond) => Self::Set(SetCondition {
+                is_member: !cond.is_member,
+                ..cond.clone()
+            }),
+            Self::Modulo(cond) => Self::Modulo(ModuloCondition {
+                should_equal: !cond.should_equal,
+                ..cond.clone()
+            }),
+            Self::Relation(cond) => Self::Relation(RelationCondition {
+                left: cond.left.clone(),
+                binary_op: cond.binary_op.negated(),
+                right: cond.right.clone(),
+            }),
+            Self::Linear(cond) => Self::Linear(LinearCondition {
+                terms: cond.terms.clone(),
+                binary_op: cond.binary_op.negated(),
+                constant: cond.constant,
+            }),
+            Self::Null(cond) => Self::Null(NullCondition {
+                var_name: cond.var_name.clone(),
+                is_null: !cond.is_null,
+            }),
+            Self::Length(cond) => Self::Length(LengthCondition {
+                var_name: cond.var_name.clone(),
+                lengths: cond.lengths.complement_within(&LengthCondition::domain()),
+            }),
+            Self::Pattern(cond) => Self::Pattern(PatternCondition {
+                matches: !cond.matches,
+                ..cond.clone()
             }),
         }
     }
@@ -64,9 +288,58 @@ impl fmt::Display for Condition {
                 var_name,
                 should_equal_to,
             }) => write!(f, "{var_name} == {should_equal_to}"),
-            Self::Interval(IntervalCondition { var_name, interval }) => {
+            Self::Interval(IntervalCondition {
+                var_name, interval, ..
+            }) => {
                 write!(f, "{var_name} in {interval}")
             }
+            Self::Enum(EnumCondition {
+                var_name, values, ..
+            }) => write!(f, "{var_name} in {{{}}}", values.join(", ")),
+            Self::Set(SetCondition {
+                var_name,
+                values,
+                is_member,
+                ..
+            }) => write!(
+                f,
+                "{var_name} {} {{{}}}",
+                if *is_member { "in" } else { "not in" },
+                values.iter().join(", ")
+            ),
+            Self::Modulo(ModuloCondition {
+                var_name,
+                divisor,
+                remainder,
+                should_equal,
+            }) => write!(
+                f,
+                "{var_name} % {divisor} {} {remainder}",
+                if *should_equal { "==" } else { "!=" }
+            ),
+            Self::Relation(RelationCondition {
+                left,
+                binary_op,
+                right,
+            }) => write!(f, "{left} {binary_op} {right}"),
+            Self::Linear(cond) => write!(f, "{cond}"),
+            Self::Null(NullCondition { var_name, is_null }) => write!(
+                f,
+                "{var_name} is {}null",
+                if *is_null { "" } else { "not " }
+            ),
+            Self::Length(LengthCondition { var_name, lengths }) => {
+                write!(f, "len({var_name}) in {lengths}")
+            }
+            Self::Pattern(PatternCondition {
+                var_name,
+                class,
+                matches,
+            }) => write!(
+                f,
+                "{var_name} {} \"{class}\"",
+                if *matches { "matches" } else { "doesn't match" }
+            ),
         }
     }
 }
@@ -80,32 +353,109 @@ pub enum Predicate {
         right: Box<Predicate>,
         operator: BoolOp,
     },
+    /// The predicate of a branch with an expected outcome, like `if(price >= 200) => discount = 10`
+    Annotated {
+        predicate: Box<Self>,
+        outcome: String,
+    },
+    /// The predicate of a branch or a feature tracing to requirements, like `@req("R2-1") if(age < 18)`
+    Traced {
+        predicate: Box<Self>,
+        requirements: Vec<String>,
+    },
+    /// The conditions of a `let` binding used by its name, like `working_age` in `if(working_age && service >= 30)`
+    Derived {
+        predicate: Box<Self>,
+        name: String,
+    },
 }
 
 impl Predicate {
+    /// The outcome and the requirements don't hold for the negated predicate, so they are dropped.
+    /// A negated binding still tests the binding, so it keeps its name.
     pub fn negated(&self) -> Self {
         match self {
             Self::Negated(pred) => pred.as_ref().clone(),
+            Self::Annotated { predicate, .. } | Self::Traced { predicate, .. } => {
+                predicate.as_ref().negated()
+            }
+            Self::Derived { predicate, name } => Self::Derived {
+                predicate: Box::new(predicate.negated()),
+                name: name.clone(),
+            },
             Self::Expression(cond) => Self::Expression(cond.negated()),
             Self::Group {
                 left,
                 right,
-                operator,
-            } => Self::Group {
-                left: Box::new(left.as_ref().negated()),
-                right: Box::new(right.as_ref().negated()),
-                operator: match operator {
-                    BoolOp::And => BoolOp::Or,
-                    BoolOp::Or => BoolOp::And,
-                },
-            },
+                operator: BoolOp::And,
+            } => Self::group(left.negated(), BoolOp::Or, right.negated()),
+            Self::Group {
+                left,
+                right,
+                operator: BoolOp::Or,
+            } => Self::group(left.negated(), BoolOp::And, right.negated()),
+            // `!(a => b)` is `a && !b`
+            Self::Group {
+                left,
+                right,
+                operator: BoolOp::Implies,
+            } => Self::group(left.as_ref().clone(), BoolOp::And, right.as_ref().negated()),
+            Self::Group {
+                left,
+                right,
+                operator: BoolOp::Xor,
+            } => Self::group(
+                left.as_ref().clone(),
+                BoolOp::Equivalent,
+                right.as_ref().clone(),
+            ),
+            Self::Group {
+                left,
+                right,
+                operator: BoolOp::Equivalent,
+            } => Self::group(left.as_ref().clone(), BoolOp::Xor, right.as_ref().clone()),
+        }
+    }
+
+    fn group(left: Self, operator: BoolOp, right: Self) -> Self {
+        Self::Group {
+            left: Box::new(left),
+            right: Box::new(right),
+            operator,
+        }
+    }
+
+    /// `a => b`, `a ^ b` and `a <=> b` with only `&&`, `||` and negations, so they can be reduced.
+    /// `^` and `<=>` are split into their rows where they hold, so every row gets its own test cases.
+    fn lowered(left: &Self, operator: BoolOp, right: &Self) -> Self {
+        match operator {
+            BoolOp::And | BoolOp::Or => Self::group(left.clone(), operator, right.clone()),
+            BoolOp::Implies => Self::group(left.negated(), BoolOp::Or, right.clone()),
+            BoolOp::Xor => Self::group(
+                Self::group(left.clone(), BoolOp::And, right.negated()),
+                BoolOp::Or,
+                Self::group(left.negated(), BoolOp::And, right.clone()),
+            ),
+            BoolOp::Equivalent => Self::group(
+                Self::group(left.clone(), BoolOp::And, right.clone()),
+                BoolOp::Or,
+                Self::group(left.negated(), BoolOp::And, right.negated()),
+            ),
         }
     }
 
     pub fn reduce(&self) -> ReducedPredicate {
         match self {
             Self::Negated(x) => x.as_ref().negated().reduce(),
+            Self::Annotated { predicate, .. }
+            | Self::Traced { predicate, .. }
+            | Self::Derived { predicate, .. } => predicate.as_ref().reduce(),
             Self::Expression(x) => ReducedPredicate::Expression(x.clone()),
+            Self::Group {
+                left,
+                right,
+                operator: operator @ (BoolOp::Implies | BoolOp::Xor | BoolOp::Equivalent),
+            } => Self::lowered(left, *operator, right).reduce(),
             Self::Group {
                 left,
                 right,
@@ -171,6 +521,69 @@ impl Predicate {
     pub fn conjunction_of_conditions(&self) -> Vec<Vec<Condition>> {
         self.reduce().to_ands()
     }
+
+    /// The expected outcomes of every branch, whose predicate has to hold for this one to hold
+    pub fn outcomes(&self) -> Vec<String> {
+        match self {
+            Self::Negated(_) | Self::Expression(_) => Vec::new(),
+            Self::Group { left, right, .. } => {
+                let mut outcomes = left.outcomes();
+                outcomes.append(&mut right.outcomes());
+                outcomes
+            }
+            Self::Annotated { predicate, outcome } => {
+                let mut outcomes = vec![outcome.clone()];
+                outcomes.append(&mut predicate.outcomes());
+                outcomes
+            }
+            Self::Traced { predicate, .. } | Self::Derived { predicate, .. } => {
+                predicate.outcomes()
+            }
+        }
+    }
+
+    /// The requirements of every branch and feature, whose predicate has to hold for this one to hold
+    pub fn requirements(&self) -> Vec<String> {
+        match self {
+            Self::Negated(_) | Self::Expression(_) => Vec::new(),
+            Self::Group { left, right, .. } => {
+                let mut requirements = left.requirements();
+                requirements.append(&mut right.requirements());
+                requirements
+            }
+            Self::Annotated { predicate, .. } | Self::Derived { predicate, .. } => {
+                predicate.requirements()
+            }
+            Self::Traced {
+                predicate,
+                requirements,
+            } => {
+                let mut requirements = requirements.clone();
+                requirements.append(&mut predicate.requirements());
+                requirements
+            }
+        }
+    }
+
+    /// The names of the `let` bindings the predicate uses, even negated, because their boundaries are tested either way
+    pub fn bindings(&self) -> Vec<String> {
+        match self {
+            Self::Expression(_) => Vec::new(),
+            Self::Negated(predicate)
+            | Self::Annotated { predicate, .. }
+            | Self::Traced { predicate, .. } => predicate.bindings(),
+            Self::Group { left, right, .. } => {
+                let mut bindings = left.bindings();
+                bindings.append(&mut right.bindings());
+                bindings
+            }
+            Self::Derived { predicate, name } => {
+                let mut bindings = vec![name.clone()];
+                bindings.append(&mut predicate.bindings());
+                bindings
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -202,6 +615,17 @@ impl ReducedPredicate {
             }) => {
                 if sub_ors.is_empty() {
                     vec![conjugated_conditions.clone()]
+                } else if let [ors] = &sub_ors[..] {
+                    // There's nothing to pair a single or with, like in the negated earlier branches of an else if
+                    Self::Or(ors.clone())
+                        .to_ands()
+                        .into_iter()
+                        .map(|mut and| {
+                            let mut base = conjugated_conditions.clone();
+                            base.append(&mut and);
+                            base
+                        })
+                        .collect()
                 } else {
                     let mut result = Vec::new();
 
@@ -265,40 +689,15 @@ impl ReducedPredicate {
                     .collect::<Vec<Vec<Condition>>>()
                     .uniques(); // TODO: Itertools::unique would be betetr, but that requires Eq and Hash
 
-                if sub_ands.is_empty() {
-                    all_possible_anded_form_of_the_or
-                } else {
-                    let ands_from_ands: Vec<Vec<Vec<Condition>>> = sub_ands
-                        .iter()
-                        .map(|and| Self::And(and.clone()).to_ands())
-                        .collect();
-
-                    all_possible_anded_form_of_the_or
-                        .iter()
-                        .flat_map(|conjugated_conditions| {
-                            let mut result = Vec::new();
-                            for (a, b) in ands_from_ands.iter().tuple_combinations() {
-                                let mut asd = a
-                                    .iter()
-                                    .cartesian_product(b.iter())
-                                    .map(|(x, y)| {
-                                        let mut z = x.clone();
-                                        z.append(&mut y.clone());
-                                        z
-                                    })
-                                    .collect::<Vec<Vec<Condition>>>();
-
-                                for x in &mut asd {
-                                    let mut base = conjugated_conditions.clone();
-                                    base.append(x);
-                                    result.push(base);
-                                }
-                            }
-
-                            result
-                        })
-                        .collect()
-                }
+                // Any disjunct or conjunction makes the or hold, like the rows of `a ^ b`, or `(!a && !b) || c` of `a || b => c`
+                all_possible_anded_form_of_the_or
+                    .into_iter()
+                    .chain(
+                        sub_ands
+                            .iter()
+                            .flat_map(|and| Self::And(and.clone()).to_ands()),
+                    )
+                    .collect()
             }
         }
     }
@@ -337,18 +736,21 @@ impl ReducedPredicate {
 }
 
 pub struct Feature {
+    /// Every constant which can be used in the feature, including the ones shared by every feature
+    pub constants: Vec<Constant>,
     pub variables: Vec<Variable>,
     pub predicates: Vec<Predicate>,
+    /// The conditions the inputs always satisfy, including the shared ones, which aren't tested themselves
+    pub assumptions: Vec<Predicate>,
+    /// The template instantiation the feature is expanded from, like `discount(200)`
+    pub instance: Option<String>,
 }
 
 impl fmt::Display for Predicate {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Negated(pred) => write!(f, "!({pred})"),
-            Self::Expression(cond) => match cond {
-                Condition::Bool(cond) => write!(f, "{} == {}", cond.var_name, cond.should_equal_to),
-                Condition::Interval(cond) => write!(f, "{} in {}", cond.var_name, cond.interval),
-            },
+            Self::Expression(cond) => write!(f, "{cond}"),
             Self::Group {
                 left,
                 right,
@@ -360,9 +762,22 @@ impl fmt::Display for Predicate {
                 match operator {
                     BoolOp::And => "&&",
                     BoolOp::Or => "||",
+                    BoolOp::Implies => "=>",
+                    BoolOp::Xor => "^",
+                    BoolOp::Equivalent => "<=>",
                 },
                 right.as_ref()
             ),
+            Self::Annotated { predicate, outcome } => write!(f, "{predicate} => {outcome}"),
+            Self::Traced {
+                predicate,
+                requirements,
+            } => write!(
+                f,
+                "@req({}) {predicate}",
+                requirements.iter().map(|id| format!("{id:?}")).join(", ")
+            ),
+            Self::Derived { predicate, name } => write!(f, "let {name} = {predicate}"),
         }
     }
 }
@@ -376,15 +791,24 @@ impl fmt::Debug for Predicate {
 #[allow(dead_code)]
 #[cfg(test)]
 mod test {
+    use itertools::Itertools;
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
-    use super::{Condition, IntervalCondition, Predicate};
-    use crate::{interval::test::multiint, parser::ast::BoolOp};
+    use super::{
+        Condition, IntervalCondition, LengthCondition, NullCondition, PatternCondition, Predicate,
+    };
+    use crate::{
+        interval::{test::multiint, MultiInterval},
+        parser::ast::{BoolOp, CharClass},
+    };
 
     fn cond<'a>(var_name: &'a str, interval: &'a str) -> Condition {
         Condition::Interval(IntervalCondition {
             var_name: var_name.to_owned(),
             interval: multiint(interval),
+            domain: MultiInterval::new_unbounded(),
+            constants: Vec::new(),
         })
     }
 
@@ -400,6 +824,76 @@ mod test {
         })
     }
 
+    #[rstest]
+    #[case::implies(
+        BoolOp::Implies,
+        vec![
+            vec![cond("x", "(-Inf, 0) (0, Inf)")],
+            vec![cond("x", "[0,0]"), cond("y", "[0,0]")],
+            vec![cond("y", "[0,0]")],
+            vec![cond("y", "(-Inf, 0) (0, Inf)"), cond("x", "(-Inf, 0) (0, Inf)")],
+        ]
+    )]
+    #[case::xor(
+        BoolOp::Xor,
+        vec![
+            vec![cond("x", "[0,0]"), cond("y", "(-Inf, 0) (0, Inf)")],
+            vec![cond("x", "(-Inf, 0) (0, Inf)"), cond("y", "[0,0]")],
+        ]
+    )]
+    #[case::equivalent(
+        BoolOp::Equivalent,
+        vec![
+            vec![cond("x", "[0,0]"), cond("y", "[0,0]")],
+            vec![cond("x", "(-Inf, 0) (0, Inf)"), cond("y", "(-Inf, 0) (0, Inf)")],
+        ]
+    )]
+    fn test_conjunction_of_conditions_of_operator(
+        #[case] operator: BoolOp,
+        #[case] expected: Vec<Vec<Condition>>,
+    ) {
+        let predicate = Predicate::Group {
+            left: expr("x", "[0,0]"),
+            right: expr("y", "[0,0]"),
+            operator,
+        };
+
+        assert_eq!(predicate.conjunction_of_conditions(), expected);
+    }
+
+    #[test]
+    fn test_conjunction_of_conditions_of_or_of_ands() {
+        // Before the rows of `^` and `<=>` were lowered, this had no conjunctions at all,
+        // since there's no disjunct to pair the conjunctions with
+        let predicate = Predicate::Group {
+            left: and(expr("x", "[0,0]"), expr("y", "[0,0]")),
+            right: and(expr("x", "[1,1]"), expr("y", "[1,1]")),
+            operator: BoolOp::Or,
+        };
+
+        assert_eq!(
+            predicate.conjunction_of_conditions(),
+            vec![
+                vec![cond("x", "[0,0]"), cond("y", "[0,0]")],
+                vec![cond("x", "[1,1]"), cond("y", "[1,1]")],
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::implies(BoolOp::Implies, "(x in [0, 0] && y in (-Inf, 0) (0, Inf))")]
+    #[case::xor(BoolOp::Xor, "(x in [0, 0] <=> y in [0, 0])")]
+    #[case::equivalent(BoolOp::Equivalent, "(x in [0, 0] ^ y in [0, 0])")]
+    fn test_negated_operator(#[case] operator: BoolOp, #[case] expected: &str) {
+        let predicate = Predicate::Group {
+            left: expr("x", "[0,0]"),
+            right: expr("y", "[0,0]"),
+            operator,
+        };
+
+        assert_eq!(predicate.negated().to_string(), expected);
+    }
+
     // #[test]
     // fn test_to_ands() {
     //     let predicate = Predicate::Group {
@@ -492,4 +986,149 @@ mod test {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_outcomes() {
+        let annotated = |predicate: Box<Predicate>, outcome: &str| {
+            Box::new(Predicate::Annotated {
+                predicate,
+                outcome: outcome.to_owned(),
+            })
+        };
+
+        // else if(x in [0, 10]) => a { if(y in [0, 0]) => b }, after if(z in [5, 5]) => c
+        let predicate = and(
+            Box::new(Predicate::Negated(annotated(expr("z", "[5, 5]"), "c"))),
+            and(
+                annotated(expr("x", "[0, 10]"), "a"),
+                annotated(expr("y", "[0, 0]"), "b"),
+            ),
+        );
+
+        assert_eq!(predicate.outcomes(), vec!["a".to_owned(), "b".to_owned()]);
+        assert_eq!(predicate.requirements(), Vec::<String>::new());
+        assert_eq!(
+            predicate.conjunction_of_conditions(),
+            vec![vec![
+                cond("z", "(-Inf, 5) (5, Inf)"),
+                cond("x", "[0, 10]"),
+                cond("y", "[0, 0]")
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_requirements() {
+        let traced = |predicate: Box<Predicate>, requirements: &[&str]| {
+            Box::new(Predicate::Traced {
+                predicate,
+                requirements: requirements.iter().map(|id| (*id).to_owned()).collect(),
+            })
+        };
+
+        // @req("R1") [ @req("R3") else if(x in [0, 10]) { @req("R3-1") if(y in [0, 0]) } ], after @req("R2") if(z in [5, 5])
+        let predicate = traced(
+            and(
+                Box::new(Predicate::Negated(traced(expr("z", "[5, 5]"), &["R2"]))),
+                and(
+                    traced(expr("x", "[0, 10]"), &["R3"]),
+                    traced(expr("y", "[0, 0]"), &["R3-1"]),
+                ),
+            ),
+            &["R1"],
+        );
+
+        assert_eq!(predicate.requirements(), vec!["R1", "R3", "R3-1"]);
+        assert_eq!(
+            predicate.conjunction_of_conditions(),
+            vec![vec![
+                cond("z", "(-Inf, 5) (5, Inf)"),
+                cond("x", "[0, 10]"),
+                cond("y", "[0, 0]")
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_bindings() {
+        let derived = |predicate: Box<Predicate>, name: &str| {
+            Box::new(Predicate::Derived {
+                predicate,
+                name: name.to_owned(),
+            })
+        };
+
+        // let adult = x in [18, 130], let senior = adult && y in [30, 50], if(!senior), after if(z in [5, 5])
+        let predicate = and(
+            Box::new(Predicate::Negated(expr("z", "[5, 5]"))),
+            Box::new(Predicate::Negated(derived(
+                and(
+                    derived(expr("x", "[18, 130]"), "adult"),
+                    expr("y", "[30, 50]"),
+                ),
+                "senior",
+            ))),
+        );
+
+        assert_eq!(predicate.bindings(), vec!["senior", "adult"]);
+        assert_eq!(predicate.negated().bindings(), vec!["senior", "adult"]);
+        assert_eq!(predicate.requirements(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_negated_null_guard() {
+        // coupon > 20 of an int?
+        let predicate = and(
+            Box::new(Predicate::Expression(Condition::Null(NullCondition {
+                var_name: "coupon".to_owned(),
+                is_null: false,
+            }))),
+            expr("coupon", "(20, Inf)"),
+        );
+
+        assert_eq!(
+            predicate.negated().to_string(),
+            "(coupon is null || coupon in (-Inf, 20])"
+        );
+        // The contradicting last one is dropped when the conditions of a variable are intersected
+        assert_eq!(
+            predicate
+                .negated()
+                .conjunction_of_conditions()
+                .iter()
+                .map(|conditions| conditions.iter().join(" && "))
+                .collect::<Vec<_>>(),
+            vec![
+                "coupon is null",
+                "coupon is not null && coupon in (-Inf, 20]",
+                "coupon in (-Inf, 20]",
+                "coupon in (20, Inf) && coupon is null",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_negated_string_conditions() {
+        // len(username) in [3, 16] && username matches "[a-z]"
+        let predicate = and(
+            Box::new(Predicate::Expression(Condition::Length(LengthCondition {
+                var_name: "username".to_owned(),
+                lengths: multiint("[3, 16]"),
+            }))),
+            Box::new(Predicate::Expression(Condition::Pattern(
+                PatternCondition {
+                    var_name: "username".to_owned(),
+                    class: CharClass {
+                        ranges: vec![('a', 'z')],
+                    },
+                    matches: true,
+                },
+            ))),
+        );
+
+        assert_eq!(
+            predicate.negated().to_string(),
+            "(len(username) in [0, 3) (16, Inf) || username doesn't match \"[a-z]\")"
+        );
+    }
 }
@@ -6,7 +6,6 @@
     // clippy::cargo
 )]
 #![allow(
-    illegal_floating_point_literal_pattern,
     clippy::let_and_return,
     clippy::suboptimal_flops,
     clippy::option_if_let_else
@@ -17,43 +16,236 @@
     clippy::missing_errors_doc
 )]
 
+use diagnostic::{Diagnostic, Severity};
 use dto::NTupleSingleInterval;
 use ir::Feature;
-use nom::{error::convert_error, Err};
-use parser::parse_gpt_to_features;
-use prelude::{GPTError, Result};
-use test_case_generator::generate_test_cases_for_multiple_features;
+use loader::{
+    check_source_files, load_ir, load_ir_recovering, load_source_files, parse_source_files,
+    FileProvider, InMemoryFileProvider,
+};
+use number::Num;
+use prelude::Result;
+use test_case_generator::{
+    generate_robustness_test_cases_for_multiple_features, generate_test_cases_for_multiple_features,
+};
+use util::UniquesVec;
 
-use crate::parser::parse_gpt_to_ir;
+use crate::prelude::GPTError;
 
 pub mod bva;
+mod checker;
+pub mod cst;
+pub mod diagnostic;
 pub mod dto;
+pub mod formatter;
 pub mod graph_reduction;
 pub mod interval;
 mod ir;
+pub mod loader;
+pub mod number;
 pub mod parser;
 pub mod prelude;
+mod relation;
 pub mod test_case_generator;
 pub mod test_value_generator;
 mod util;
 
+/// The path of the input, when it isn't read from a file
+pub(crate) const INPUT_PATH: &str = "input.gpt";
+
+/// What the assumptions of the features rule out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Infeasible {
+    /// The branches which can't be taken, only the test cases of not taking them are generated
+    pub branches: usize,
+    /// The test cases dropped, because they can't be narrowed to satisfy the assumptions
+    pub test_cases: usize,
+}
+
+/// A spec with its imports, loaded, parsed and checked once, so every step can use it without loading it again
+pub struct CheckedSpec {
+    features: Vec<Feature>,
+    warnings: Vec<Diagnostic>,
+}
+
+impl CheckedSpec {
+    /// The warnings of the checker, like overlapping cases of a switch, which don't stop generating the test cases
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    /// Generates the test cases, and counts the ones dropped because they can't satisfy the assumptions of their feature,
+    /// and the branches which can't satisfy them at all.
+    pub fn generate_tests_and_count_infeasible(
+        &self,
+    ) -> Result<(Vec<NTupleSingleInterval>, Infeasible)> {
+        let (features, infeasible_branches): (Vec<_>, Vec<_>) = self
+            .features
+            .iter()
+            .map(ir::ir_to_ntuple::ir_to_ntuple_and_count_infeasible)
+            .unzip();
+        log::warn!("Inputs: {:#?}", features);
+        let (test_cases, infeasible_test_cases) =
+            generate_test_cases_for_multiple_features(&features)
+                .map_err(|err| GPTError::IntervalError(format!("{err:?}")))?;
+
+        Ok((
+            test_cases,
+            Infeasible {
+                branches: infeasible_branches.iter().sum(),
+                test_cases: infeasible_test_cases,
+            },
+        ))
+    }
+
+    pub fn generate_robustness_tests(&self) -> Vec<NTupleSingleInterval> {
+        let features = self
+            .features
+            .iter()
+            .map(ir::ir_to_ntuple::ir_to_ntuple)
+            .collect::<Vec<_>>();
+
+        generate_robustness_test_cases_for_multiple_features(&features)
+    }
+
+    /// The name and the precision of every number variable, so test values can be shown with their declared decimals
+    pub fn precisions(&self) -> Vec<(String, Num)> {
+        self.features
+            .iter()
+            .flat_map(|feature| &feature.variables)
+            .filter_map(|variable| {
+                let precision = variable.var_type.get_precision()?;
+                Some((variable.var_name.clone(), precision))
+            })
+            .collect::<Vec<_>>()
+            .uniques()
+    }
+}
+
+/// Loads the file with its imports, failing with their syntax and semantic errors
+pub fn check_gpt_file(provider: &dyn FileProvider, path: &str) -> Result<CheckedSpec> {
+    let (features, warnings) = load_ir(provider, path)?;
+
+    Ok(CheckedSpec { features, warnings })
+}
+
+pub fn check_gpt_input(input: &str) -> Result<CheckedSpec> {
+    check_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
+
+pub fn and_reduce_gpt_file(provider: &dyn FileProvider, path: &str) -> Result<Vec<Feature>> {
+    load_ir(provider, path).map(|(features, _)| features)
+}
+
 pub fn and_reduce_gpt_input(input: &str) -> Result<Vec<Feature>> {
-    let (_, ir) = parse_gpt_to_ir(input).map_err(|error| match error {
-        Err::Error(err) | Err::Failure(err) => GPTError::ParseError(convert_error(input, err)),
-        Err::Incomplete(err) => GPTError::UnknownParseError(format!("{err:?}")),
-    })?;
+    and_reduce_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
 
-    Ok(ir)
+pub fn generate_tests_and_count_infeasible_for_gpt_file(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<(Vec<NTupleSingleInterval>, Infeasible)> {
+    check_gpt_file(provider, path)?.generate_tests_and_count_infeasible()
+}
+
+pub fn generate_tests_and_count_infeasible_for_gpt_input(
+    input: &str,
+) -> Result<(Vec<NTupleSingleInterval>, Infeasible)> {
+    generate_tests_and_count_infeasible_for_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
+
+pub fn generate_tests_for_gpt_file(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<Vec<NTupleSingleInterval>> {
+    generate_tests_and_count_infeasible_for_gpt_file(provider, path)
+        .map(|(test_cases, _)| test_cases)
 }
 
 pub fn generate_tests_for_gpt_input(input: &str) -> Result<Vec<NTupleSingleInterval>> {
-    let (_, features) = parse_gpt_to_features(input).map_err(|error| match error {
-        Err::Error(err) | Err::Failure(err) => GPTError::ParseError(convert_error(input, err)),
-        Err::Incomplete(err) => GPTError::UnknownParseError(format!("{err:?}")),
-    })?;
-    log::warn!("Inputs: {:#?}", features);
-    let test_cases = generate_test_cases_for_multiple_features(&features)
+    generate_tests_for_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
+
+/// Generates the test cases for the features without syntax errors, so they can be shown while the input is edited.
+/// The syntax errors are returned as well.
+pub fn generate_tests_for_gpt_file_recovering(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<(Vec<NTupleSingleInterval>, Vec<Diagnostic>)> {
+    let (features, diagnostics) = load_ir_recovering(provider, path)?;
+    let features = features
+        .iter()
+        .map(ir::ir_to_ntuple::ir_to_ntuple)
+        .collect::<Vec<_>>();
+    let (test_cases, _) = generate_test_cases_for_multiple_features(&features)
         .map_err(|err| GPTError::IntervalError(format!("{err:?}")))?;
 
-    Ok(test_cases)
+    Ok((test_cases, diagnostics))
+}
+
+pub fn generate_tests_for_gpt_input_recovering(
+    input: &str,
+) -> Result<(Vec<NTupleSingleInterval>, Vec<Diagnostic>)> {
+    generate_tests_for_gpt_file_recovering(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
+
+pub fn generate_robustness_tests_for_gpt_file(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<Vec<NTupleSingleInterval>> {
+    Ok(check_gpt_file(provider, path)?.generate_robustness_tests())
+}
+
+pub fn generate_robustness_tests_for_gpt_input(input: &str) -> Result<Vec<NTupleSingleInterval>> {
+    generate_robustness_tests_for_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
+
+/// The warnings of the checker, like overlapping cases of a switch, which don't stop generating the test cases
+pub fn warnings_for_gpt_file(provider: &dyn FileProvider, path: &str) -> Result<Vec<Diagnostic>> {
+    let sources = load_source_files(provider, path)?;
+    let mut root_node = parse_source_files(&sources)?;
+
+    Ok(check_source_files(&sources, &mut root_node)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+        .collect())
+}
+
+pub fn warnings_for_gpt_input(input: &str) -> Result<Vec<Diagnostic>> {
+    warnings_for_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
+}
+
+pub fn precisions_for_gpt_file(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<Vec<(String, Num)>> {
+    Ok(check_gpt_file(provider, path)?.precisions())
+}
+
+pub fn precisions_for_gpt_input(input: &str) -> Result<Vec<(String, Num)>> {
+    precisions_for_gpt_file(
+        &InMemoryFileProvider::from([(INPUT_PATH, input)]),
+        INPUT_PATH,
+    )
 }
@@ -0,0 +1,634 @@
+use std::collections::HashMap;
+
+use nom::Err;
+
+use crate::{
+    checker::{check_root, CheckResult, SemanticError},
+    diagnostic::{code, Diagnostic, Severity},
+    ir::{self, ast_to_ir::convert_ast_to_ir},
+    parser::{ast::RootNode, expand_templates, imports, root_recovering, IResult},
+    prelude::{GPTError, Result},
+    util::UniquesVec,
+};
+
+/// Reads the files of a spec, so they can come from the file system or from memory (e.g. in the browser)
+pub trait FileProvider {
+    fn read_file(&self, path: &str) -> std::io::Result<String>;
+}
+
+/// Reads the files from the file system
+pub struct FsFileProvider;
+
+impl FileProvider for FsFileProvider {
+    fn read_file(&self, path: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// Serves the files from memory by their path
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryFileProvider {
+    files: HashMap<String, String>,
+}
+
+impl InMemoryFileProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: &str, source: &str) {
+        self.files.insert(normalize_path(path), source.to_owned());
+    }
+}
+
+impl<const N: usize> From<[(&str, &str); N]> for InMemoryFileProvider {
+    fn from(files: [(&str, &str); N]) -> Self {
+        let mut provider = Self::new();
+        for (path, source) in files {
+            provider.insert(path, source);
+        }
+        provider
+    }
+}
+
+impl FileProvider for InMemoryFileProvider {
+    fn read_file(&self, path: &str) -> std::io::Result<String> {
+        self.files
+            .get(&normalize_path(path))
+            .cloned()
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: String,
+    pub source: String,
+}
+
+/// Removes the `.` segments and resolves the `..` segments of a `/` separated path
+fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+
+    for segment in path.split('/') {
+        match segment {
+            "." => {}
+            "" if !segments.is_empty() => {}
+            ".." if matches!(segments.last(), Some(last) if *last != ".." && !last.is_empty()) => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    segments.join("/")
+}
+
+/// Resolves the path of an import relative to the directory of the importing file
+fn resolve_import(importing_path: &str, import: &str) -> String {
+    if import.starts_with('/') {
+        return normalize_path(import);
+    }
+
+    match importing_path.rsplit_once('/') {
+        Some((directory, _)) => normalize_path(&format!("{directory}/{import}")),
+        None => normalize_path(import),
+    }
+}
+
+pub(crate) fn parse_error_in_file<T>(path: &str, source: &str, result: IResult<T>) -> Result<T> {
+    result.map(|(_, x)| x).map_err(|error| match error {
+        Err::Error(err) | Err::Failure(err) => {
+            GPTError::ParseError(vec![Diagnostic::from_parse_error(path, source, &err)])
+        }
+        Err::Incomplete(err) => GPTError::UnknownParseError(format!("in file {path}: {err:?}")),
+    })
+}
+
+/// The statement of an import, in the source of the importing file
+struct ImportStatement<'a> {
+    path: &'a str,
+    source: &'a str,
+    statement: &'a str,
+}
+
+impl ImportStatement<'_> {
+    /// The diagnostic of a problem with the import, or of the file itself if it isn't imported
+    fn error(
+        statement: Option<&Self>,
+        path: &str,
+        code: &'static str,
+        message: String,
+    ) -> GPTError {
+        let diagnostic = statement
+            .and_then(|statement| {
+                Diagnostic::for_fragment(
+                    statement.path,
+                    statement.source,
+                    statement.statement,
+                    Severity::Error,
+                    code,
+                    message.clone(),
+                )
+            })
+            .unwrap_or_else(|| Diagnostic::new(path, "", 0..0, Severity::Error, code, message));
+
+        GPTError::ImportError(vec![diagnostic])
+    }
+}
+
+fn load_source_file(
+    provider: &dyn FileProvider,
+    path: String,
+    imported_by: Option<&ImportStatement>,
+    importing_paths: &mut Vec<String>,
+    sources: &mut Vec<SourceFile>,
+) -> Result<()> {
+    if let Some(position) = importing_paths.iter().position(|x| *x == path) {
+        return Err(ImportStatement::error(
+            imported_by,
+            &path,
+            code::IMPORT_CYCLE,
+            format!(
+                "import cycle: {} -> {path}",
+                importing_paths[position..].join(" -> ")
+            ),
+        ));
+    }
+
+    // Files imported by more than one file are only loaded once
+    if sources.iter().any(|source_file| source_file.path == path) {
+        return Ok(());
+    }
+
+    let source = provider.read_file(&path).map_err(|err| {
+        ImportStatement::error(
+            imported_by,
+            &path,
+            code::UNREADABLE_FILE,
+            format!("can't read `{path}`: {err}"),
+        )
+    })?;
+    let imports = parse_error_in_file(&path, &source, imports(&source))?;
+
+    importing_paths.push(path.clone());
+    for (statement, import) in imports {
+        let statement = ImportStatement {
+            path: &path,
+            source: &source,
+            statement,
+        };
+        load_source_file(
+            provider,
+            resolve_import(&path, import),
+            Some(&statement),
+            importing_paths,
+            sources,
+        )?;
+    }
+    importing_paths.pop();
+
+    sources.push(SourceFile { path, source });
+
+    Ok(())
+}
+
+/// Reads the file and every file imported by it, the imported files come before the files importing them
+pub fn load_source_files(provider: &dyn FileProvider, path: &str) -> Result<Vec<SourceFile>> {
+    let mut sources = Vec::new();
+    load_source_file(
+        provider,
+        normalize_path(path),
+        None,
+        &mut Vec::new(),
+        &mut sources,
+    )?;
+
+    Ok(sources)
+}
+
+/// Parses every file with error recovery and merges them into one, expanding the uses of templates.
+///
+/// The features with syntax errors are left out, and if there is a syntax error outside of the features, every feature is left out.
+pub fn parse_source_files_recovering(sources: &[SourceFile]) -> (RootNode, Vec<Diagnostic>) {
+    let mut merged = RootNode {
+        imports: Vec::new(),
+        constants: Vec::new(),
+        variables: Vec::new(),
+        assumptions: Vec::new(),
+        templates: Vec::new(),
+        features: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut diagnostics = Vec::new();
+    let mut has_error_outside_features = false;
+
+    for SourceFile { path, source } in sources {
+        let mut root_node = root_recovering(source);
+
+        has_error_outside_features |= !root_node.errors.is_empty();
+        diagnostics.extend(
+            root_node
+                .errors
+                .iter()
+                .chain(
+                    root_node
+                        .features
+                        .iter()
+                        .flat_map(|feature| &feature.errors),
+                )
+                .map(|error_node| Diagnostic::from_parse_error(path, source, &error_node.error)),
+        );
+
+        merged.constants.append(&mut root_node.constants);
+        merged.variables.append(&mut root_node.variables);
+        merged.assumptions.append(&mut root_node.assumptions);
+        merged.templates.append(&mut root_node.templates);
+        merged.features.extend(
+            root_node
+                .features
+                .into_iter()
+                .filter(|feature| feature.errors.is_empty()),
+        );
+    }
+
+    if has_error_outside_features {
+        merged.features.clear();
+    }
+    // The templates can be used in any file after merging them
+    expand_templates(&mut merged);
+
+    (merged, diagnostics)
+}
+
+/// Parses every file and merges them into one, failing with every syntax error found in them
+pub fn parse_source_files(sources: &[SourceFile]) -> Result<RootNode> {
+    let (root_node, diagnostics) = parse_source_files_recovering(sources);
+
+    if diagnostics.is_empty() {
+        Ok(root_node)
+    } else {
+        Err(GPTError::ParseError(diagnostics))
+    }
+}
+
+/// Checks the meaning of the merged files, and leaves out the features with semantic errors.
+///
+/// If there is a semantic error outside of the features, every feature is left out.
+/// Warnings are returned with the errors, but they don't leave out anything.
+pub fn check_source_files(sources: &[SourceFile], root_node: &mut RootNode) -> Vec<Diagnostic> {
+    let CheckResult {
+        root_errors,
+        feature_errors,
+    } = check_root(root_node);
+
+    let diagnostics = root_errors
+        .iter()
+        .chain(feature_errors.iter().flatten())
+        .map(|error| {
+            sources
+                .iter()
+                .find_map(|SourceFile { path, source }| {
+                    Diagnostic::for_fragment(
+                        path,
+                        source,
+                        error.fragment,
+                        Severity::of_code(error.code),
+                        error.code,
+                        error.message.clone(),
+                    )
+                })
+                // Every name in the AST is a part of one of the sources, this is only a fallback
+                .unwrap_or_else(|| {
+                    Diagnostic::new(
+                        "",
+                        "",
+                        0..0,
+                        Severity::of_code(error.code),
+                        error.code,
+                        error.message.clone(),
+                    )
+                })
+        })
+        .collect::<Vec<_>>()
+        .uniques();
+
+    let is_error = |error: &SemanticError| Severity::of_code(error.code) == Severity::Error;
+    let mut feature_errors = feature_errors.iter();
+    root_node.features.retain(|_| {
+        let has_errors = feature_errors
+            .next()
+            .is_some_and(|errors| errors.iter().any(is_error));
+        !root_errors.iter().any(is_error) && !has_errors
+    });
+
+    diagnostics
+}
+
+/// Converts the features if there are no syntax or semantic errors, and returns the warnings as well
+pub(crate) fn load_ir(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<(Vec<ir::Feature>, Vec<Diagnostic>)> {
+    let sources = load_source_files(provider, path)?;
+    let mut root_node = parse_source_files(&sources)?;
+
+    let diagnostics = check_source_files(&sources, &mut root_node);
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        return Err(GPTError::SemanticError(diagnostics));
+    }
+
+    Ok((convert_ast_to_ir(&root_node), diagnostics))
+}
+
+/// Converts the features without syntax or semantic errors, and returns the errors as well
+pub(crate) fn load_ir_recovering(
+    provider: &dyn FileProvider,
+    path: &str,
+) -> Result<(Vec<ir::Feature>, Vec<Diagnostic>)> {
+    let sources = load_source_files(provider, path)?;
+    let (mut root_node, mut diagnostics) = parse_source_files_recovering(&sources);
+    diagnostics.append(&mut check_source_files(&sources, &mut root_node));
+
+    Ok((convert_ast_to_ir(&root_node), diagnostics))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::diagnostic::code;
+
+    #[rstest]
+    #[case("common.gpt", "common.gpt")]
+    #[case("./specs/../common.gpt", "common.gpt")]
+    #[case("specs//products/./shop.gpt", "specs/products/shop.gpt")]
+    #[case("../common.gpt", "../common.gpt")]
+    #[case("/specs/../common.gpt", "/common.gpt")]
+    fn test_normalize_path(#[case] path: &str, #[case] expected: &str) {
+        assert_eq!(normalize_path(path), expected);
+    }
+
+    #[rstest]
+    #[case("shop.gpt", "common.gpt", "common.gpt")]
+    #[case("specs/shop.gpt", "common.gpt", "specs/common.gpt")]
+    #[case("specs/shop.gpt", "../common.gpt", "common.gpt")]
+    #[case("specs/shop.gpt", "/shared/common.gpt", "/shared/common.gpt")]
+    fn test_resolve_import(
+        #[case] importing_path: &str,
+        #[case] import: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(resolve_import(importing_path, import), expected);
+    }
+
+    #[test]
+    fn test_load_source_files() {
+        let provider = InMemoryFileProvider::from([
+            (
+                "specs/shop.gpt",
+                "import \"common.gpt\"\nimport \"../vars.gpt\"\nif(price > LIMIT)",
+            ),
+            (
+                "specs/common.gpt",
+                "import \"../vars.gpt\"\nconst LIMIT = 10",
+            ),
+            ("vars.gpt", "var price: int"),
+        ]);
+
+        let paths = load_source_files(&provider, "specs/shop.gpt")
+            .unwrap()
+            .into_iter()
+            .map(|source_file| source_file.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec!["vars.gpt", "specs/common.gpt", "specs/shop.gpt"]
+        );
+    }
+
+    #[test]
+    fn test_load_source_files_cycle() {
+        let provider = InMemoryFileProvider::from([
+            ("a.gpt", "import \"b.gpt\""),
+            ("b.gpt", "import \"c.gpt\""),
+            ("c.gpt", "// Back to b\nimport \"b.gpt\""),
+        ]);
+
+        match load_source_files(&provider, "a.gpt") {
+            Err(GPTError::ImportError(diagnostics)) => assert_eq!(
+                diagnostics,
+                vec![Diagnostic::new(
+                    "c.gpt",
+                    "// Back to b\nimport \"b.gpt\"",
+                    13..27,
+                    Severity::Error,
+                    code::IMPORT_CYCLE,
+                    "import cycle: b.gpt -> c.gpt -> b.gpt".to_owned()
+                )]
+            ),
+            result => panic!("Expected an import cycle error, got {result:?}"),
+        }
+    }
+
+    #[rstest]
+    #[case::missing_import(
+        InMemoryFileProvider::from([("a.gpt", "// Shared\nimport \"b.gpt\"")]),
+        ("a.gpt", 2, 1, 14)
+    )]
+    #[case::missing_file(InMemoryFileProvider::new(), ("a.gpt", 1, 1, 0))]
+    fn test_load_source_files_missing_file(
+        #[case] provider: InMemoryFileProvider,
+        #[case] expected: (&str, usize, usize, usize),
+    ) {
+        match load_source_files(&provider, "a.gpt") {
+            Err(GPTError::ImportError(diagnostics)) => assert_eq!(
+                diagnostics
+                    .iter()
+                    .map(|diagnostic| (
+                        (
+                            diagnostic.file.as_str(),
+                            diagnostic.line,
+                            diagnostic.column,
+                            diagnostic.span.len()
+                        ),
+                        diagnostic.code
+                    ))
+                    .collect::<Vec<_>>(),
+                vec![(expected, code::UNREADABLE_FILE)]
+            ),
+            result => panic!("Expected a missing file error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_names_file() {
+        let provider = InMemoryFileProvider::from([
+            ("a.gpt", "import \"b.gpt\"\nvar x: int"),
+            ("b.gpt", "var y: int\nif(y >)"),
+        ]);
+
+        match load_ir(&provider, "a.gpt") {
+            Err(GPTError::ParseError(diagnostics)) => {
+                assert_eq!(
+                    diagnostics
+                        .iter()
+                        .map(|diagnostic| (diagnostic.file.as_str(), diagnostic.line))
+                        .collect::<Vec<_>>(),
+                    vec![("b.gpt", 2)]
+                );
+            }
+            Err(err) => panic!("Expected a parse error, got {err:?}"),
+            Ok(_) => panic!("Expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_load_ir_recovering() {
+        let provider = InMemoryFileProvider::from([(
+            "a.gpt",
+            "var x: int\n[\nif(x > 5)\n]\n[\nif(x >)\n]\n[\nif(x < 2)\nif(x in [1, 3)\n]",
+        )]);
+
+        let (features, diagnostics) = load_ir_recovering(&provider, "a.gpt").unwrap();
+
+        assert_eq!(features.len(), 1);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.line)
+                .collect::<Vec<_>>(),
+            vec![6, 11]
+        );
+        assert!(matches!(
+            load_ir(&provider, "a.gpt"),
+            Err(GPTError::ParseError(diagnostics)) if diagnostics.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_load_ir_semantic_errors() {
+        let provider = InMemoryFileProvider::from([
+            (
+                "a.gpt",
+                "import \"b.gpt\"\n[\nif(x > 5)\n]\n[\nif(vip > 5)\n]",
+            ),
+            ("b.gpt", "var x: int\nvar vip: bool"),
+        ]);
+
+        match load_ir(&provider, "a.gpt") {
+            Err(GPTError::SemanticError(diagnostics)) => assert_eq!(
+                diagnostics
+                    .iter()
+                    .map(|diagnostic| (diagnostic.file.as_str(), diagnostic.line, diagnostic.code))
+                    .collect::<Vec<_>>(),
+                vec![("a.gpt", 6, code::TYPE_MISMATCH)]
+            ),
+            Err(err) => panic!("Expected a semantic error, got {err:?}"),
+            Ok(_) => panic!("Expected a semantic error"),
+        }
+
+        let (features, diagnostics) = load_ir_recovering(&provider, "a.gpt").unwrap();
+        assert_eq!(features.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_load_ir_warnings() {
+        let provider = InMemoryFileProvider::from([(
+            "a.gpt",
+            "var x: int\nswitch(x) {\n case [0, 5] => a = 1:\n case [3, 8] => a = 2:\n default => a = 3:\n}",
+        )]);
+
+        let (features, warnings) = load_ir(&provider, "a.gpt").unwrap();
+
+        assert_eq!(features.len(), 1);
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|diagnostic| (diagnostic.line, diagnostic.code))
+                .collect::<Vec<_>>(),
+            vec![(4, code::OVERLAPPING_CASES)]
+        );
+    }
+
+    #[test]
+    fn test_load_ir_merges_files() {
+        let provider = InMemoryFileProvider::from([
+            (
+                "shop.gpt",
+                "import \"common.gpt\"\n[ var vip: bool\n if(price > LIMIT && vip == true) ]",
+            ),
+            (
+                "common.gpt",
+                "const LIMIT = 10\nvar price: int\n[ var weight: int\n if(weight < 5) ]",
+            ),
+        ]);
+
+        let (features, _) = load_ir(&provider, "shop.gpt").unwrap();
+        let variables = features
+            .iter()
+            .map(|feature| {
+                feature
+                    .variables
+                    .iter()
+                    .map(|variable| variable.var_name.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            variables,
+            vec![vec!["price", "weight"], vec!["price", "vip"]]
+        );
+    }
+
+    #[test]
+    fn test_load_ir_switch() {
+        let provider = InMemoryFileProvider::from([(
+            "a.gpt",
+            "var x: int in [0, 100]\nvar tier: enum { a, b, c }\n[ switch(x) { case [0, 10): case 5, [10, 50) => \"mid\": default: } ]\n[ switch(tier) { case c: case a, c: } ]",
+        )]);
+
+        let (features, diagnostics) = load_ir_recovering(&provider, "a.gpt").unwrap();
+        let predicates = features
+            .iter()
+            .map(|feature| {
+                feature
+                    .predicates
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            predicates,
+            vec![
+                vec![
+                    "x in [0, 10)".to_owned(),
+                    "x in [10, 50) => mid".to_owned(),
+                    "x in [50, 100]".to_owned(),
+                ],
+                vec!["tier in {c}".to_owned(), "tier in {a}".to_owned()],
+            ]
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.severity, diagnostic.code))
+                .collect::<Vec<_>>(),
+            vec![
+                (Severity::Warning, code::OVERLAPPING_CASES),
+                (Severity::Warning, code::OVERLAPPING_CASES),
+                (Severity::Warning, code::UNCOVERED_VALUES),
+            ]
+        );
+    }
+}
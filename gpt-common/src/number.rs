@@ -0,0 +1,422 @@
+//! The numbers of the language.
+//!
+//! They are exact decimals, so big integers like IDs, timestamps in milliseconds or amounts in cents,
+//! and the multiples of precisions like `0.1` are represented without rounding,
+//! and the boundaries of `num(precision)` variables are stepped exactly.
+
+use std::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use rust_decimal::Decimal;
+
+/// An exact number, or one of the infinities bounding the unbounded intervals.
+///
+/// Finite numbers have a 96 bit mantissa, so every `i64` and `u64` fits, and so do 28 decimals.
+/// Arithmetic overflowing that range results in the infinity of its sign.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Num {
+    NegInfinity,
+    Finite(Decimal),
+    Infinity,
+}
+
+impl Num {
+    pub const ZERO: Self = Self::Finite(Decimal::ZERO);
+    pub const ONE: Self = Self::Finite(Decimal::ONE);
+    pub const INFINITY: Self = Self::Infinity;
+    pub const NEG_INFINITY: Self = Self::NegInfinity;
+
+    /// `mantissa * 10^-scale`, e.g. `Num::new(1, 2)` is `0.01`
+    pub fn new(mantissa: i64, scale: u32) -> Self {
+        Self::Finite(Decimal::new(mantissa, scale))
+    }
+
+    pub const fn is_finite(self) -> bool {
+        matches!(self, Self::Finite(_))
+    }
+
+    pub const fn is_sign_negative(self) -> bool {
+        match self {
+            Self::NegInfinity => true,
+            Self::Finite(x) => x.is_sign_negative() && !x.is_zero(),
+            Self::Infinity => false,
+        }
+    }
+
+    #[must_use]
+    pub fn abs(self) -> Self {
+        if self.is_sign_negative() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// `self + steps * precision`, like the boundary values in boundary value analysis
+    #[must_use]
+    pub fn add_steps(self, steps: i64, precision: Self) -> Self {
+        self + Self::from(steps) * precision
+    }
+
+    /// The multiple of the precision chosen by `round` from the ones around the number
+    fn to_multiple(self, precision: Self, round: impl Fn(Decimal) -> Decimal) -> Self {
+        match (self, precision) {
+            (Self::Finite(x), Self::Finite(precision)) => x
+                .checked_div(precision)
+                .map(round)
+                .and_then(|steps| steps.checked_mul(precision))
+                .map_or(self, Self::Finite),
+            _ => self,
+        }
+    }
+
+    /// The smallest multiple of the precision, which is not less than the number
+    #[must_use]
+    pub fn ceil_to(self, precision: Self) -> Self {
+        self.to_multiple(precision, |x| x.ceil())
+    }
+
+    /// The largest multiple of the precision, which is not more than the number
+    #[must_use]
+    pub fn floor_to(self, precision: Self) -> Self {
+        self.to_multiple(precision, |x| x.floor())
+    }
+
+    /// The nearest multiple of the precision
+    #[must_use]
+    pub fn round_to(self, precision: Self) -> Self {
+        self.to_multiple(precision, |x| x.round())
+    }
+
+    /// Whether the number is a multiple of the precision, like an integer for a precision of 1
+    pub fn is_multiple_of(self, precision: Self) -> bool {
+        self.is_finite() && self.floor_to(precision) == self
+    }
+
+    /// The remainder of dividing by `divisor`, which is never negative, like the one of `i64::rem_euclid`
+    #[must_use]
+    pub fn rem_euclid(self, divisor: Self) -> Self {
+        match (self, divisor) {
+            (Self::Finite(x), Self::Finite(divisor)) if !divisor.is_zero() => {
+                let remainder = x % divisor;
+                Self::Finite(if remainder.is_sign_negative() && !remainder.is_zero() {
+                    remainder + divisor.abs()
+                } else {
+                    remainder
+                })
+            }
+            _ => panic!("The remainder of {self} divided by {divisor} is undefined"),
+        }
+    }
+
+    /// The greatest number both numbers are multiples of, like `0.5` for `1.5` and `2`
+    #[must_use]
+    pub fn gcd(self, other: Self) -> Self {
+        if other == Self::ZERO {
+            self.abs()
+        } else {
+            other.gcd(self.rem_euclid(other.abs()))
+        }
+    }
+
+    /// The number of decimals it is written with, like 2 for `0.25`
+    pub fn decimals(self) -> u32 {
+        match self {
+            Self::Finite(x) => x.normalize().scale(),
+            _ => 0,
+        }
+    }
+
+    /// The number written with the decimals of the precision, like `199.90` for `0.01`.
+    /// The conditions are rounded to the precision, but numbers with more decimals keep all of them, so they aren't shown rounded.
+    pub fn format_with_precision(self, precision: Self) -> String {
+        match self {
+            Self::Finite(x) => {
+                let mut x = x.normalize();
+                x.rescale(x.scale().max(precision.decimals()));
+                x.to_string()
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// The number as a float, for when an estimate is enough
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Self::NegInfinity => f64::NEG_INFINITY,
+            Self::Finite(x) => x.to_string().parse().unwrap_or_default(),
+            Self::Infinity => f64::INFINITY,
+        }
+    }
+
+    const fn infinity_with_sign(is_negative: bool) -> Self {
+        if is_negative {
+            Self::NegInfinity
+        } else {
+            Self::Infinity
+        }
+    }
+}
+
+impl From<Decimal> for Num {
+    fn from(value: Decimal) -> Self {
+        Self::Finite(value)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Num {
+                fn from(value: $integer) -> Self {
+                    Self::Finite(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl FromStr for Num {
+    type Err = rust_decimal::Error;
+
+    /// Parses decimals, like `-12.5`, and `Inf` or `-Inf`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Inf" => Ok(Self::Infinity),
+            "-Inf" => Ok(Self::NegInfinity),
+            s => Decimal::from_str_exact(s).map(Self::Finite),
+        }
+    }
+}
+
+impl Add for Num {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Finite(x), Self::Finite(y)) => x.checked_add(y).map_or_else(
+                || Self::infinity_with_sign(x.is_sign_negative()),
+                Self::Finite,
+            ),
+            (Self::Infinity, Self::NegInfinity) | (Self::NegInfinity, Self::Infinity) => {
+                panic!("The sum of opposite infinities is undefined")
+            }
+            (Self::Infinity, _) | (_, Self::Infinity) => Self::Infinity,
+            (Self::NegInfinity, _) | (_, Self::NegInfinity) => Self::NegInfinity,
+        }
+    }
+}
+
+impl AddAssign for Num {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Neg for Num {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self {
+            Self::NegInfinity => Self::Infinity,
+            Self::Finite(x) => Self::Finite(-x),
+            Self::Infinity => Self::NegInfinity,
+        }
+    }
+}
+
+impl Sub for Num {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl SubAssign for Num {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul for Num {
+    type Output = Self;
+
+    /// Infinity times 0 is 0, so scaling an unbounded interval by 0 results in a point
+    fn mul(self, other: Self) -> Self {
+        let is_negative = self.is_sign_negative() != other.is_sign_negative();
+
+        match (self, other) {
+            (Self::Finite(x), Self::Finite(y)) => x
+                .checked_mul(y)
+                .map_or_else(|| Self::infinity_with_sign(is_negative), Self::Finite),
+            (x, y) if x == Self::ZERO || y == Self::ZERO => Self::ZERO,
+            _ => Self::infinity_with_sign(is_negative),
+        }
+    }
+}
+
+impl Div for Num {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let is_negative = self.is_sign_negative() != other.is_sign_negative();
+
+        match (self, other) {
+            (_, y) if y == Self::ZERO => panic!("Division of {self} by 0"),
+            (Self::Finite(x), Self::Finite(y)) => x
+                .checked_div(y)
+                .map_or_else(|| Self::infinity_with_sign(is_negative), Self::Finite),
+            (Self::Finite(_), _) => Self::ZERO,
+            (_, Self::Finite(_)) => Self::infinity_with_sign(is_negative),
+            _ => panic!("The quotient of infinities is undefined"),
+        }
+    }
+}
+
+impl Sum for Num {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl Product for Num {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+/// Finite numbers are written without trailing zeros, the infinities as `Inf` and `-Inf`
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegInfinity => write!(f, "-Inf"),
+            Self::Finite(x) => write!(f, "{}", x.normalize()),
+            Self::Infinity => write!(f, "Inf"),
+        }
+    }
+}
+
+impl fmt::Debug for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+/// Writes a number literal as an exact `Num` in tests, e.g. `num!(0.1)` or `num!(-2)`
+#[cfg(test)]
+macro_rules! num {
+    ($($literal:tt)+) => {
+        stringify!($($literal)+)
+            .replace(' ', "")
+            .parse::<$crate::number::Num>()
+            .unwrap()
+    };
+}
+#[cfg(test)]
+pub(crate) use num;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::Num;
+
+    #[rstest]
+    #[case("200", -1, "0.1", "199.9")]
+    #[case("199.9", -1, "0.1", "199.8")]
+    #[case("1000.3", 2, "0.1", "1000.5")]
+    #[case("0.3", -3, "0.1", "0")]
+    #[case("1", 1, "0.42", "1.42")]
+    #[case("5.05", 1, "0.1", "5.15")]
+    #[case("9223372036854775806", 1, "1", "9223372036854775807")]
+    #[case("18446744073709551615", -1, "1", "18446744073709551614")]
+    #[case("Inf", 1, "0.1", "Inf")]
+    #[case("-Inf", -1, "0.1", "-Inf")]
+    fn test_add_steps(
+        #[case] value: Num,
+        #[case] steps: i64,
+        #[case] precision: Num,
+        #[case] expected: Num,
+    ) {
+        assert_eq!(value.add_steps(steps, precision), expected);
+    }
+
+    #[rstest]
+    #[case("3.5", "1", "4", "3")]
+    #[case("0.3", "0.1", "0.3", "0.3")]
+    #[case("0.25", "0.1", "0.3", "0.2")]
+    #[case("-0.25", "0.1", "-0.2", "-0.3")]
+    #[case("199.95", "0.1", "200", "199.9")]
+    #[case("Inf", "0.1", "Inf", "Inf")]
+    fn test_ceil_and_floor_to(
+        #[case] value: Num,
+        #[case] precision: Num,
+        #[case] ceil: Num,
+        #[case] floor: Num,
+    ) {
+        assert_eq!(value.ceil_to(precision), ceil);
+        assert_eq!(value.floor_to(precision), floor);
+    }
+
+    #[rstest]
+    #[case("7", "4", "3")]
+    #[case("-7", "4", "1")]
+    #[case("8", "4", "0")]
+    #[case("1700000000000123", "1000", "123")]
+    fn test_rem_euclid(#[case] value: Num, #[case] divisor: Num, #[case] expected: Num) {
+        assert_eq!(value.rem_euclid(divisor), expected);
+    }
+
+    #[rstest]
+    #[case("2", "3", "1")]
+    #[case("4", "6", "2")]
+    #[case("1.5", "2", "0.5")]
+    #[case("-2", "0.3", "0.1")]
+    #[case("0", "0.1", "0.1")]
+    fn test_gcd(#[case] a: Num, #[case] b: Num, #[case] expected: Num) {
+        assert_eq!(a.gcd(b), expected);
+        assert_eq!(b.gcd(a), expected);
+    }
+
+    #[rstest]
+    #[case("Inf", "1", "Inf")]
+    #[case("-Inf", "-2", "Inf")]
+    #[case("-Inf", "0", "0")]
+    #[case("79228162514264337593543950335", "2", "Inf")]
+    #[case("79228162514264337593543950335", "-2", "-Inf")]
+    fn test_mul(#[case] left: Num, #[case] right: Num, #[case] expected: Num) {
+        assert_eq!(left * right, expected);
+    }
+
+    #[rstest]
+    #[case("199.9", "0.1", "199.9")]
+    #[case("199.9", "0.01", "199.90")]
+    #[case("200", "0.1", "200.0")]
+    #[case("200", "1", "200")]
+    #[case("-0.5", "0.25", "-0.50")]
+    #[case("5.05", "0.1", "5.05")]
+    #[case("Inf", "0.1", "Inf")]
+    fn test_format_with_precision(
+        #[case] value: Num,
+        #[case] precision: Num,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(value.format_with_precision(precision), expected);
+    }
+
+    #[rstest]
+    #[case("2.50", "2.5")]
+    #[case("9223372036854775807", "9223372036854775807")]
+    #[case("-Inf", "-Inf")]
+    fn test_display(#[case] value: Num, #[case] expected: &str) {
+        assert_eq!(value.to_string(), expected);
+    }
+}
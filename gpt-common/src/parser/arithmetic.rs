@@ -0,0 +1,267 @@
+use nom::{
+    branch::alt,
+    character::complete::char,
+    combinator::{map, opt, value},
+    error::context,
+    multi::many0,
+    sequence::{pair, separated_pair},
+};
+
+use super::{
+    ast::{LinearExpression, LinearTerm, Number},
+    primitives::{number, number_or_constant, var_name},
+    utils::token,
+    IResult,
+};
+use crate::number::Num;
+
+#[derive(Debug, PartialEq)]
+enum Term<'a> {
+    Variable(LinearTerm<'a>),
+    Constant(Num),
+}
+
+fn sign(input: &str) -> IResult<Num> {
+    alt((value(Num::ONE, char('+')), value(-Num::ONE, char('-'))))(input)
+}
+
+/// Parses a variable with an optional coefficient, like `2 * x` or `RATE * x`, or a constant
+fn term(input: &str) -> IResult<Term> {
+    alt((
+        map(
+            separated_pair(token(number_or_constant), token(char('*')), var_name),
+            |(coefficient, var_name)| {
+                Term::Variable(match coefficient {
+                    Number::Literal(coefficient) => LinearTerm {
+                        coefficient,
+                        coefficient_constant: None,
+                        var_name,
+                    },
+                    Number::Constant(constant) => LinearTerm {
+                        coefficient: Num::ONE,
+                        coefficient_constant: Some(constant),
+                        var_name,
+                    },
+                })
+            },
+        ),
+        map(var_name, |var_name| {
+            Term::Variable(LinearTerm {
+                coefficient: Num::ONE,
+                coefficient_constant: None,
+                var_name,
+            })
+        }),
+        map(number, Term::Constant),
+    ))(input)
+}
+
+/// Parses a sum of terms, like `2 * age - service + 10`
+pub fn linear_expression(input: &str) -> IResult<LinearExpression> {
+    context(
+        "linear expression",
+        map(
+            pair(
+                pair(opt(token(sign)), token(term)),
+                many0(pair(token(sign), token(term))),
+            ),
+            |((first_sign, first_term), rest)| {
+                std::iter::once((first_sign.unwrap_or(Num::ONE), first_term))
+                    .chain(rest)
+                    .fold(
+                        LinearExpression {
+                            terms: Vec::new(),
+                            constant: Num::ZERO,
+                        },
+                        |mut expression, (sign, term)| {
+                            match term {
+                                Term::Variable(LinearTerm {
+                                    coefficient,
+                                    coefficient_constant,
+                                    var_name,
+                                }) => expression.terms.push(LinearTerm {
+                                    coefficient: sign * coefficient,
+                                    coefficient_constant,
+                                    var_name,
+                                }),
+                                Term::Constant(constant) => expression.constant += sign * constant,
+                            };
+                            expression
+                        },
+                    )
+            },
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::number::num;
+
+    #[test]
+    fn test_term() {
+        assert_eq!(
+            term("2 * x"),
+            Ok((
+                "",
+                Term::Variable(LinearTerm {
+                    coefficient: num!(2.0),
+                    coefficient_constant: None,
+                    var_name: "x"
+                })
+            ))
+        );
+        assert_eq!(
+            term("0.5*x y"),
+            Ok((
+                " y",
+                Term::Variable(LinearTerm {
+                    coefficient: num!(0.5),
+                    coefficient_constant: None,
+                    var_name: "x"
+                })
+            ))
+        );
+        assert_eq!(
+            term("x * 2"),
+            Ok((
+                " * 2",
+                Term::Variable(LinearTerm {
+                    coefficient: num!(1.0),
+                    coefficient_constant: None,
+                    var_name: "x"
+                })
+            ))
+        );
+        assert_eq!(
+            term("RATE * x"),
+            Ok((
+                "",
+                Term::Variable(LinearTerm {
+                    coefficient: num!(1.0),
+                    coefficient_constant: Some("RATE"),
+                    var_name: "x"
+                })
+            ))
+        );
+        assert_eq!(term("10 > x"), Ok((" > x", Term::Constant(num!(10.0)))));
+        assert!(term("* x").is_err());
+    }
+
+    #[test]
+    fn test_linear_expression() {
+        assert_eq!(
+            linear_expression("x"),
+            Ok((
+                "",
+                LinearExpression {
+                    terms: vec![LinearTerm {
+                        coefficient: num!(1.0),
+                        coefficient_constant: None,
+                        var_name: "x"
+                    }],
+                    constant: num!(0.0)
+                }
+            ))
+        );
+        assert_eq!(
+            linear_expression("-2 * age - service + 10 - 2.5 >= 0"),
+            Ok((
+                ">= 0",
+                LinearExpression {
+                    terms: vec![
+                        LinearTerm {
+                            coefficient: num!(-2.0),
+                            coefficient_constant: None,
+                            var_name: "age"
+                        },
+                        LinearTerm {
+                            coefficient: num!(-1.0),
+                            coefficient_constant: None,
+                            var_name: "service"
+                        }
+                    ],
+                    constant: num!(7.5)
+                }
+            ))
+        );
+        assert_eq!(
+            linear_expression("price+shipping-x&&"),
+            Ok((
+                "&&",
+                LinearExpression {
+                    terms: vec![
+                        LinearTerm {
+                            coefficient: num!(1.0),
+                            coefficient_constant: None,
+                            var_name: "price"
+                        },
+                        LinearTerm {
+                            coefficient: num!(1.0),
+                            coefficient_constant: None,
+                            var_name: "shipping"
+                        },
+                        LinearTerm {
+                            coefficient: num!(-1.0),
+                            coefficient_constant: None,
+                            var_name: "x"
+                        }
+                    ],
+                    constant: num!(0.0)
+                }
+            ))
+        );
+        assert_eq!(
+            linear_expression("x - y)"),
+            Ok((
+                ")",
+                LinearExpression {
+                    terms: vec![
+                        LinearTerm {
+                            coefficient: num!(1.0),
+                            coefficient_constant: None,
+                            var_name: "x"
+                        },
+                        LinearTerm {
+                            coefficient: num!(-1.0),
+                            coefficient_constant: None,
+                            var_name: "y"
+                        }
+                    ],
+                    constant: num!(0.0)
+                }
+            ))
+        );
+        assert_eq!(
+            linear_expression("price - RATE * price + FEE"),
+            Ok((
+                "",
+                LinearExpression {
+                    terms: vec![
+                        LinearTerm {
+                            coefficient: num!(1.0),
+                            coefficient_constant: None,
+                            var_name: "price"
+                        },
+                        LinearTerm {
+                            coefficient: num!(-1.0),
+                            coefficient_constant: Some("RATE"),
+                            var_name: "price"
+                        },
+                        LinearTerm {
+                            coefficient: num!(1.0),
+                            coefficient_constant: None,
+                            var_name: "FEE"
+                        }
+                    ],
+                    constant: num!(0.0)
+                }
+            ))
+        );
+        assert!(linear_expression("+").is_err());
+        assert!(linear_expression("&& x").is_err());
+    }
+}
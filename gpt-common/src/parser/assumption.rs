@@ -0,0 +1,38 @@
+use nom::{combinator::cut, error::context};
+
+use super::{
+    ast::ConditionsNode,
+    condition::conditions,
+    utils::{parenthesized, token_lit},
+    IResult,
+};
+
+/// Parses a condition the inputs always satisfy, like `assume(service <= age - 16)`
+pub fn assumption(input: &str) -> IResult<ConditionsNode> {
+    context("assumption", |input| {
+        let (input, _) = token_lit("assume")(input)?;
+        cut(parenthesized(conditions))(input)
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_assumption() {
+        assert_eq!(
+            assumption("assume(service <= age - 16) if"),
+            Ok(("if", conditions("service <= age - 16").unwrap().1))
+        );
+        assert_eq!(
+            assumption("assume (x > 5 && y == true)"),
+            Ok(("", conditions("x > 5 && y == true").unwrap().1))
+        );
+        assert!(assumption("assume x > 5").is_err());
+        assert!(assumption("assume()").is_err());
+        assert!(assumption("if(x > 5)").is_err());
+    }
+}
@@ -1,20 +1,110 @@
-use crate::interval::MultiInterval;
+use std::fmt;
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+use itertools::Itertools;
+use nom::error::VerboseError;
+
+use crate::{
+    interval::{Boundary, MultiInterval},
+    number::Num,
+};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Type {
     Bool,
-    Integer,
-    Float { precision: f32 },
+    /// `int`, or a sized integer type like `u8` if `size` is set
+    Integer {
+        size: Option<IntegerSize>,
+    },
+    Float {
+        precision: Num,
+    },
+    /// A categorical type, which can take one of the listed members
+    Enum(Vec<String>),
+    /// A record like `{ price: num, prepaid: bool }`, whose fields are in `VarNode::fields`
+    Record,
+    /// A text, whose length and characters can be constrained by `len(x)` and `matches` conditions
+    String,
 }
 
 impl Type {
-    pub const fn get_precision(&self) -> Option<f32> {
+    pub const fn get_precision(&self) -> Option<Num> {
         match self {
             Self::Bool => None,
-            Self::Integer => Some(1.0),
+            Self::Integer { .. } => Some(Num::ONE),
             Self::Float { precision } => Some(*precision),
+            Self::Enum(_) | Self::Record | Self::String => None,
         }
     }
+
+    /// The values of a sized integer type, like `[0, 255]` for `u8`
+    pub fn range(&self) -> Option<MultiInterval> {
+        match self {
+            Self::Integer { size: Some(size) } => {
+                let (min, max) = size.limits();
+                MultiInterval::new_closed(min, max).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The fixed width integer types of the code under test, like `u8` or `i64`
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum IntegerSize {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+}
+
+impl IntegerSize {
+    pub const ALL: [Self; 8] = [
+        Self::U8,
+        Self::I8,
+        Self::U16,
+        Self::I16,
+        Self::U32,
+        Self::I32,
+        Self::U64,
+        Self::I64,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::I8 => "i8",
+            Self::U16 => "u16",
+            Self::I16 => "i16",
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::U64 => "u64",
+            Self::I64 => "i64",
+        }
+    }
+
+    /// The smallest and the largest value of the type
+    pub fn limits(self) -> (Num, Num) {
+        match self {
+            Self::U8 => (Num::from(u8::MIN), Num::from(u8::MAX)),
+            Self::I8 => (Num::from(i8::MIN), Num::from(i8::MAX)),
+            Self::U16 => (Num::from(u16::MIN), Num::from(u16::MAX)),
+            Self::I16 => (Num::from(i16::MIN), Num::from(i16::MAX)),
+            Self::U32 => (Num::from(u32::MIN), Num::from(u32::MAX)),
+            Self::I32 => (Num::from(i32::MIN), Num::from(i32::MAX)),
+            Self::U64 => (Num::from(u64::MIN), Num::from(u64::MAX)),
+            Self::I64 => (Num::from(i64::MIN), Num::from(i64::MAX)),
+        }
+    }
+}
+
+impl fmt::Display for IntegerSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -47,6 +137,53 @@ impl BinaryOp {
             Self::NotEqual => Self::NotEqual,
         }
     }
+
+    /// The `BinaryOp` which holds exactly when this one doesn't.
+    ///
+    /// Example: !(x < y) == x >= y
+    pub const fn negated(&self) -> Self {
+        match self {
+            Self::LessThan => Self::GreaterThanEqualTo,
+            Self::GreaterThan => Self::LessThanEqualTo,
+            Self::LessThanEqualTo => Self::GreaterThan,
+            Self::GreaterThanEqualTo => Self::LessThan,
+            Self::Equal => Self::NotEqual,
+            Self::NotEqual => Self::Equal,
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::LessThan => "<",
+                Self::GreaterThan => ">",
+                Self::LessThanEqualTo => "<=",
+                Self::GreaterThanEqualTo => ">=",
+                Self::Equal => "==",
+                Self::NotEqual => "!=",
+            }
+        )
+    }
+}
+
+/// A number literal, or the name of a constant standing for one, like `DISCOUNT_THRESHOLD`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Number<'a> {
+    Literal(Num),
+    Constant(&'a str),
+}
+
+/// An interval whose bounds can be constants, like `[MIN_AGE, 130]`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntervalNode<'a> {
+    pub lo_boundary: Boundary,
+    pub lo: Number<'a>,
+    pub hi: Number<'a>,
+    pub hi_boundary: Boundary,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -59,6 +196,12 @@ pub enum IntervalOp {
 pub enum BoolOp {
     And,
     Or,
+    /// `a => b`, which only fails if `a` holds and `b` doesn't
+    Implies,
+    /// `a ^ b`, exactly one of them holds
+    Xor,
+    /// `a <=> b`, both or neither of them hold
+    Equivalent,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -74,59 +217,331 @@ pub struct BoolCondition<'a> {
     pub eq_op: EqOp,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct BinaryCondition<'a> {
     pub var_name: &'a str,
     pub constant_position: ConstantPosition,
-    pub constant: f32,
+    pub constant: Num,
     pub binary_op: BinaryOp,
 }
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct IntervalCondition<'a> {
     pub var_name: &'a str,
     pub interval_op: IntervalOp,
-    pub interval: MultiInterval,
+    pub interval: IntervalNode<'a>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumCondition<'a> {
+    pub var_name: &'a str,
+    pub interval_op: IntervalOp,
+    pub members: Vec<&'a str>,
+}
+
+/// `code in {3, 7, 12}`, where the values can be constants
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SetCondition<'a> {
+    pub var_name: &'a str,
+    pub interval_op: IntervalOp,
+    pub values: Vec<Number<'a>>,
+}
+
+/// `year % 4 == 0`, where the remainder is never negative, so `-1 % 4 == 3`.
+/// The divisor and the remainder can be constants.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ModuloCondition<'a> {
+    pub var_name: &'a str,
+    pub divisor: Number<'a>,
+    pub eq_op: EqOp,
+    pub remainder: Number<'a>,
+}
+
+/// Compares two variables, like `x < y`.
+///
+/// `x == y` can also mean that the enum variable `x` is the member `y`,
+/// which is resolved in `ast_to_ir`, where the types of the variables are known.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RelationCondition<'a> {
+    pub left: &'a str,
+    pub binary_op: BinaryOp,
+    pub right: &'a str,
+}
+
+/// `coefficient * var_name`, where `var_name` can also be a constant.
+///
+/// If the coefficient is a constant, like in `-RATE * x`, it is multiplied by `coefficient`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinearTerm<'a> {
+    pub coefficient: Num,
+    pub coefficient_constant: Option<&'a str>,
+    pub var_name: &'a str,
+}
+
+/// The sum of the terms and the constant, like `2 * age - service + 10`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinearExpression<'a> {
+    pub terms: Vec<LinearTerm<'a>>,
+    pub constant: Num,
+}
+
+/// Compares two linear expressions, like `price + shipping >= 200`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinearCondition<'a> {
+    pub lhs: LinearExpression<'a>,
+    pub binary_op: BinaryOp,
+    pub rhs: LinearExpression<'a>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// `coupon is null`, or `coupon is not null` if `is_null` is false
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NullCondition<'a> {
+    pub var_name: &'a str,
+    pub is_null: bool,
+}
+
+/// The lengths a `len(x)` condition allows, like `>= 3` or `in [3, 16]`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LengthComparison<'a> {
+    Binary(BinaryOp, Number<'a>),
+    Interval(IntervalOp, IntervalNode<'a>),
+}
+
+/// `len(username) in [3, 16]`, a condition on the number of characters of a string variable
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LengthCondition<'a> {
+    pub var_name: &'a str,
+    pub comparison: LengthComparison<'a>,
+}
+
+/// A set of characters like `[a-z0-9_]`, made of single characters and inclusive ranges
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CharClass {
+    pub ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c))
+    }
+
+    /// The characters in both classes
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .cartesian_product(&other.ranges)
+                .map(|((lo, hi), (other_lo, other_hi))| (*lo.max(other_lo), *hi.min(other_hi)))
+                .filter(|(lo, hi)| lo <= hi)
+                .collect(),
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (lo, hi) in &self.ranges {
+            if lo == hi {
+                write!(f, "{lo}")?;
+            } else {
+                write!(f, "{lo}-{hi}")?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+/// `postcode matches "[0-9]"`, every character of the string variable is in the class
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PatternCondition<'a> {
+    pub var_name: &'a str,
+    pub class: CharClass,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Condition<'a> {
     Bool(BoolCondition<'a>),
     Binary(BinaryCondition<'a>),
     Interval(IntervalCondition<'a>),
+    Enum(EnumCondition<'a>),
+    Set(SetCondition<'a>),
+    Modulo(ModuloCondition<'a>),
+    Relation(RelationCondition<'a>),
+    Linear(LinearCondition<'a>),
+    Null(NullCondition<'a>),
+    Length(LengthCondition<'a>),
+    Pattern(PatternCondition<'a>),
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct FeatureNode<'a> {
+    /// The IDs of the requirements every branch of the feature traces to, like `R2` in `@req("R2") [ ... ]`
+    pub requirements: Vec<&'a str>,
+    pub constants: Vec<ConstNode<'a>>,
     pub variables: Vec<VarNode<'a>>,
     pub if_statements: Vec<IfNode<'a>>,
+    pub switch_statements: Vec<SwitchNode<'a>>,
+    /// The conditions the inputs of the feature always satisfy, like `assume(service <= age - 16)`
+    pub assumptions: Vec<ConditionsNode<'a>>,
+    /// The named conditions, which can be used in the conditions of the feature by their names
+    pub bindings: Vec<LetNode<'a>>,
+    /// The syntax errors in the feature, when it is parsed with error recovery
+    pub errors: Vec<ErrorNode<'a>>,
+    /// The instantiation the feature is expanded from, like `use discount(200)`
+    pub instance: Option<UseNode<'a>>,
 }
 
+/// A feature whose parameters are bound by the features using it,
+/// like `template discount(threshold) [ ... ]`
 #[derive(PartialEq, Debug)]
+pub struct TemplateNode<'a> {
+    pub name: &'a str,
+    pub params: Vec<&'a str>,
+    pub feature: FeatureNode<'a>,
+}
+
+/// `use discount(200)`, which instantiates a template with its parameters bound to the arguments
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct UseNode<'a> {
+    pub template: &'a str,
+    pub args: Vec<Number<'a>>,
+}
+
+impl fmt::Display for UseNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                Number::Literal(value) => value.to_string(),
+                Number::Constant(name) => (*name).to_string(),
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}({})", self.template, args.join(", "))
+    }
+}
+
+/// A part of the source with a syntax error, which is skipped until the next statement
+#[derive(PartialEq, Debug, Clone)]
+pub struct ErrorNode<'a> {
+    pub skipped: &'a str,
+    pub error: VerboseError<&'a str>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct VarNode<'a> {
     pub var_name: &'a str,
     pub var_type: Type,
+    /// Whether the variable can be missing, like `coupon` in `var coupon: int?`
+    pub nullable: bool,
+    /// The values the variable can take, like `[0, 130]` in `var age: int in [0, 130]`
+    pub domain: Option<IntervalNode<'a>>,
+    /// The fields of a record variable, like `price` in `var order: { price: num, prepaid: bool }`
+    pub fields: Vec<Self>,
 }
 
-#[derive(PartialEq, Debug)]
+/// `let working_age = age >= 18 && age < 60`, whose name can be used as a condition
+#[derive(PartialEq, Debug, Clone)]
+pub struct LetNode<'a> {
+    pub name: &'a str,
+    pub conditions: ConditionsNode<'a>,
+}
+
+/// `const DISCOUNT_THRESHOLD = 200`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ConstNode<'a> {
+    pub name: &'a str,
+    pub value: Number<'a>,
+}
+
+/// The expected result of a branch, like `=> discount = 10` or `=> "free delivery"`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Outcome<'a> {
+    Assignment { var_name: &'a str, value: &'a str },
+    Description(&'a str),
+}
+
+impl fmt::Display for Outcome<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assignment { var_name, value } => write!(f, "{var_name} = {value}"),
+            Self::Description(description) => write!(f, "{description}"),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub struct IfNode<'a> {
+    /// The IDs of the requirements the branch traces to, like `R2-1` in `@req("R2-1") if(age < 18)`
+    pub requirements: Vec<&'a str>,
     pub conditions: ConditionsNode<'a>,
+    pub outcome: Option<Outcome<'a>>,
     pub body: Option<Vec<IfNode<'a>>>,
     pub else_if: Vec<ElseIfNode<'a>>,
     pub else_node: Option<ElseNode<'a>>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct ElseIfNode<'a> {
+    pub requirements: Vec<&'a str>,
     pub conditions: ConditionsNode<'a>,
+    pub outcome: Option<Outcome<'a>>,
     pub body: Vec<IfNode<'a>>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct ElseNode<'a> {
+    pub requirements: Vec<&'a str>,
+    pub outcome: Option<Outcome<'a>>,
     pub body: Vec<IfNode<'a>>,
 }
 
-#[derive(PartialEq, Debug)]
+/// A value a `case` of a switch matches
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum CaseValue<'a> {
+    /// `case [0, 10000):`
+    Interval(IntervalNode<'a>),
+    /// `case 5:`
+    Number(Num),
+    /// `case true:`
+    Bool(bool),
+    /// A constant or a member of an enum, like `case LIMIT:` or `case gold:`,
+    /// which is resolved by the type of the variable
+    Name(&'a str),
+}
+
+/// `case [0, 10000), 20000 => tax = 0: ...`, which matches any of its values
+#[derive(Debug, Clone)]
+pub struct CaseNode<'a> {
+    /// The values in the source, like `[0, 10000), 20000`, where the problems of the case are reported
+    pub label: &'a str,
+    pub values: Vec<CaseValue<'a>>,
+    pub outcome: Option<Outcome<'a>>,
+    pub body: Vec<IfNode<'a>>,
+}
+
+/// The label is only compared by its values, so the layout of the source doesn't matter
+impl PartialEq for CaseNode<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values && self.outcome == other.outcome && self.body == other.body
+    }
+}
+
+/// Branches on the value of one variable. The first case matching the value is taken,
+/// and the `default` is taken when none of them match.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SwitchNode<'a> {
+    pub var_name: &'a str,
+    pub cases: Vec<CaseNode<'a>>,
+    pub default: Option<ElseNode<'a>>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum ConditionsNode<'a> {
     Negated(Box<ConditionsNode<'a>>),
     Expression(Condition<'a>),
@@ -135,9 +550,23 @@ pub enum ConditionsNode<'a> {
         left: Box<ConditionsNode<'a>>,
         right: Box<ConditionsNode<'a>>,
     },
+    /// The name of a `let` binding, like `working_age` in `if(working_age && service >= 30)`,
+    /// which stands for its conditions
+    Binding(&'a str),
 }
 
 #[derive(PartialEq, Debug)]
 pub struct RootNode<'a> {
+    /// Paths of the imported files, relative to the file importing them
+    pub imports: Vec<&'a str>,
+    /// Constants shared by every feature
+    pub constants: Vec<ConstNode<'a>>,
+    /// Variables shared by every feature
+    pub variables: Vec<VarNode<'a>>,
+    /// Assumptions shared by every feature
+    pub assumptions: Vec<ConditionsNode<'a>>,
+    pub templates: Vec<TemplateNode<'a>>,
     pub features: Vec<FeatureNode<'a>>,
+    /// The syntax errors outside of the features, when it is parsed with error recovery
+    pub errors: Vec<ErrorNode<'a>>,
 }
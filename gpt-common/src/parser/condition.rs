@@ -1,20 +1,29 @@
 use nom::{
     branch::alt,
-    combinator::{cut, map, value},
+    bytes::complete::tag,
+    character::complete::one_of,
+    combinator::{cut, map, not, peek, recognize, value, verify},
     error::context,
+    multi::separated_list1,
     sequence::tuple,
 };
 
 use super::{
+    arithmetic::linear_expression,
     ast::{
         BinaryCondition, BoolCondition, BoolOp, Condition, ConditionsNode, ConstantPosition,
-        IntervalCondition,
+        EnumCondition, IntervalCondition, LengthComparison, LengthCondition, LinearCondition,
+        LinearExpression, LinearTerm, ModuloCondition, NullCondition, PatternCondition,
+        RelationCondition, SetCondition,
+    },
+    primitives::{
+        binary_op, boolean, char_class, eq_op, interval_node, interval_op, member_set, null_op,
+        number, number_or_constant, value_set, var_name,
     },
-    interval,
-    primitives::{binary_op, boolean, eq_op, interval_op, number, var_name},
     utils::{parenthesized, token, token_lit},
     IResult,
 };
+use crate::number::Num;
 
 fn condition_bool_lhs(input: &str) -> IResult<Condition> {
     let (input, constant) = token(boolean)(input)?;
@@ -76,7 +85,7 @@ fn condition_binary_rhs(input: &str) -> IResult<Condition> {
 
 fn condition_interval(input: &str) -> IResult<Condition> {
     map(
-        tuple((token(var_name), token(interval_op), token(interval))),
+        tuple((token(var_name), token(interval_op), token(interval_node))),
         |(var_name, interval_op, interval)| {
             Condition::Interval(IntervalCondition {
                 var_name,
@@ -87,15 +96,160 @@ fn condition_interval(input: &str) -> IResult<Condition> {
     )(input)
 }
 
+fn condition_relation(input: &str) -> IResult<Condition> {
+    map(
+        tuple((token(var_name), token(binary_op), token(var_name))),
+        |(left, binary_op, right)| {
+            Condition::Relation(RelationCondition {
+                left,
+                binary_op,
+                right,
+            })
+        },
+    )(input)
+}
+
+fn condition_enum_set(input: &str) -> IResult<Condition> {
+    map(
+        tuple((token(var_name), token(interval_op), token(member_set))),
+        |(var_name, interval_op, members)| {
+            Condition::Enum(EnumCondition {
+                var_name,
+                interval_op,
+                members,
+            })
+        },
+    )(input)
+}
+
+fn condition_value_set(input: &str) -> IResult<Condition> {
+    map(
+        tuple((token(var_name), token(interval_op), token(value_set))),
+        |(var_name, interval_op, values)| {
+            Condition::Set(SetCondition {
+                var_name,
+                interval_op,
+                values,
+            })
+        },
+    )(input)
+}
+
+fn condition_modulo(input: &str) -> IResult<Condition> {
+    map(
+        tuple((
+            token(var_name),
+            token_lit("%"),
+            token(number_or_constant),
+            token(eq_op),
+            token(number_or_constant),
+        )),
+        |(var_name, _, divisor, eq_op, remainder)| {
+            Condition::Modulo(ModuloCondition {
+                var_name,
+                divisor,
+                eq_op,
+                remainder,
+            })
+        },
+    )(input)
+}
+
+fn condition_null(input: &str) -> IResult<Condition> {
+    map(
+        tuple((token(var_name), token(null_op))),
+        |(var_name, is_null)| Condition::Null(NullCondition { var_name, is_null }),
+    )(input)
+}
+
+/// `len(username) in [3, 16]` or `len(username) >= MIN_LENGTH`
+fn condition_length(input: &str) -> IResult<Condition> {
+    map(
+        tuple((
+            token_lit("len"),
+            parenthesized(token(var_name)),
+            alt((
+                map(
+                    tuple((token(interval_op), token(interval_node))),
+                    |(interval_op, interval)| LengthComparison::Interval(interval_op, interval),
+                ),
+                map(
+                    tuple((token(binary_op), token(number_or_constant))),
+                    |(binary_op, length)| LengthComparison::Binary(binary_op, length),
+                ),
+            )),
+        )),
+        |(_, var_name, comparison)| {
+            Condition::Length(LengthCondition {
+                var_name,
+                comparison,
+            })
+        },
+    )(input)
+}
+
+fn condition_pattern(input: &str) -> IResult<Condition> {
+    map(
+        tuple((token(var_name), token_lit("matches"), token(char_class))),
+        |(var_name, _, class)| Condition::Pattern(PatternCondition { var_name, class }),
+    )(input)
+}
+
+/// Whether the expression is more than a single variable or a single constant
+fn has_arithmetic(expression: &LinearExpression) -> bool {
+    match &expression.terms[..] {
+        [] => false,
+        [LinearTerm {
+            coefficient,
+            coefficient_constant,
+            ..
+        }] => {
+            *coefficient != Num::ONE
+                || coefficient_constant.is_some()
+                || expression.constant != Num::ZERO
+        }
+        _ => true,
+    }
+}
+
+/// `x < 10`, `10 < x` and `x < y` are left to the simpler condition parsers
+fn condition_linear(input: &str) -> IResult<Condition> {
+    map(
+        verify(
+            tuple((
+                token(linear_expression),
+                token(binary_op),
+                token(linear_expression),
+            )),
+            |(lhs, _, rhs)| has_arithmetic(lhs) || has_arithmetic(rhs),
+        ),
+        |(lhs, binary_op, rhs)| {
+            Condition::Linear(LinearCondition {
+                lhs,
+                binary_op,
+                rhs,
+            })
+        },
+    )(input)
+}
+
 fn condition(input: &str) -> IResult<Condition> {
     context(
         "condition",
         alt((
+            condition_length,
+            condition_modulo,
+            condition_linear,
             condition_binary_lhs,
             condition_binary_rhs,
             condition_bool_lhs,
             condition_bool_rhs,
             condition_interval,
+            condition_relation,
+            condition_value_set,
+            condition_enum_set,
+            condition_null,
+            condition_pattern,
         )),
     )(input)
 }
@@ -106,9 +260,26 @@ fn raw_expression(input: &str) -> IResult<ConditionsNode> {
     Ok((input, ConditionsNode::Expression(condition)))
 }
 
+/// The name of a `let` binding, which isn't the start of a comparison like `x > 5`.
+/// `<=>` and `=>` are boolean operators, which can follow a binding.
+fn binding(input: &str) -> IResult<ConditionsNode> {
+    let (input, name) = token(var_name)(input)?;
+    let (input, _) = alt((
+        value((), peek(alt((tag("<=>"), tag("=>"))))),
+        not(peek(alt((
+            recognize(interval_op),
+            recognize(null_op),
+            tag("matches"),
+            recognize(one_of("<>=!%+-*/(")),
+        )))),
+    ))(input)?;
+
+    Ok((input, ConditionsNode::Binding(name)))
+}
+
 fn negated(input: &str) -> IResult<ConditionsNode> {
     let (input, _) = token_lit("!")(input)?;
-    let (input, node) = parenthesized(conditions)(input)?;
+    let (input, node) = alt((parenthesized(conditions), binding))(input)?;
 
     Ok((input, ConditionsNode::Negated(Box::new(node))))
 }
@@ -116,63 +287,73 @@ fn negated(input: &str) -> IResult<ConditionsNode> {
 fn expression(input: &str) -> IResult<ConditionsNode> {
     context(
         "expression",
-        alt((negated, parenthesized(conditions), cut(raw_expression))),
+        alt((
+            negated,
+            parenthesized(conditions),
+            binding,
+            cut(raw_expression),
+        )),
     )(input)
 }
 
-fn and_condition(input: &str) -> IResult<ConditionsNode> {
-    alt((
-        |input| {
-            let (input, left) = expression(input)?;
-            let (input, op) = value(BoolOp::And, token_lit("&&"))(input)?;
-            let (input, right) = and_condition(input)?;
-
-            Ok((
-                input,
-                ConditionsNode::Group {
+/// Parses the operands separated by the operator, grouped from the right like `a && (b && c)`
+fn operator_chain<'a>(
+    operand: fn(&'a str) -> IResult<ConditionsNode<'a>>,
+    symbol: &'static str,
+    operator: BoolOp,
+) -> impl FnMut(&'a str) -> IResult<ConditionsNode<'a>> {
+    map(
+        separated_list1(token_lit(symbol), operand),
+        move |operands| {
+            operands
+                .into_iter()
+                .rev()
+                .reduce(|right, left| ConditionsNode::Group {
                     left: Box::new(left),
                     right: Box::new(right),
-                    operator: op,
-                },
-            ))
+                    operator,
+                })
+                .expect("There is at least one operand")
         },
-        expression,
-    ))(input)
+    )
+}
+
+fn and_condition(input: &str) -> IResult<ConditionsNode> {
+    operator_chain(expression, "&&", BoolOp::And)(input)
+}
+
+fn xor_condition(input: &str) -> IResult<ConditionsNode> {
+    operator_chain(and_condition, "^", BoolOp::Xor)(input)
 }
 
 fn or_condition(input: &str) -> IResult<ConditionsNode> {
-    alt((
-        |input| {
-            let (input, left) = and_condition(input)?;
-            let (input, op) = value(BoolOp::Or, token_lit("||"))(input)?;
-            let (input, right) = or_condition(input)?;
+    operator_chain(xor_condition, "||", BoolOp::Or)(input)
+}
 
-            Ok((
-                input,
-                ConditionsNode::Group {
-                    left: Box::new(left),
-                    right: Box::new(right),
-                    operator: op,
-                },
-            ))
-        },
-        and_condition,
-        expression,
-    ))(input)
+fn implication(input: &str) -> IResult<ConditionsNode> {
+    operator_chain(or_condition, "=>", BoolOp::Implies)(input)
+}
+
+fn equivalence(input: &str) -> IResult<ConditionsNode> {
+    operator_chain(implication, "<=>", BoolOp::Equivalent)(input)
 }
 
+/// The operators from the strongest to the weakest are `!`, `&&`, `^`, `||`, `=>` and `<=>`,
+/// so `a && b => c || d` means `(a && b) => (c || d)`
 pub fn conditions(input: &str) -> IResult<ConditionsNode> {
-    context("conditions", alt((or_condition, and_condition, expression)))(input)
+    context("conditions", equivalence)(input)
 }
 
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
     use super::*;
     use crate::{
-        interval::{Boundary, MultiInterval},
-        parser::ast::{BinaryOp, EqOp, IntervalOp},
+        interval::Boundary,
+        number::num,
+        parser::ast::{BinaryOp, CharClass, EqOp, IntervalNode, IntervalOp, Number},
     };
 
     #[test]
@@ -292,7 +473,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "x",
                     constant_position: ConstantPosition::LeftHandSide,
-                    constant: 10.32,
+                    constant: num!(10.32),
                     binary_op: BinaryOp::Equal
                 })
             ))
@@ -304,7 +485,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "foo",
                     constant_position: ConstantPosition::LeftHandSide,
-                    constant: 3.0,
+                    constant: num!(3.0),
                     binary_op: BinaryOp::LessThanEqualTo
                 })
             ))
@@ -316,7 +497,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "qwe",
                     constant_position: ConstantPosition::LeftHandSide,
-                    constant: 0.1,
+                    constant: num!(0.1),
                     binary_op: BinaryOp::GreaterThan
                 })
             ))
@@ -338,7 +519,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "x",
                     constant_position: ConstantPosition::RightHandSide,
-                    constant: 10.32,
+                    constant: num!(10.32),
                     binary_op: BinaryOp::Equal
                 })
             ))
@@ -350,7 +531,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "foo",
                     constant_position: ConstantPosition::RightHandSide,
-                    constant: 3.0,
+                    constant: num!(3.0),
                     binary_op: BinaryOp::LessThanEqualTo
                 })
             ))
@@ -362,7 +543,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "qwe",
                     constant_position: ConstantPosition::RightHandSide,
-                    constant: 0.1,
+                    constant: num!(0.1),
                     binary_op: BinaryOp::LessThanEqualTo
                 })
             ))
@@ -384,8 +565,12 @@ mod tests {
                 Condition::Interval(IntervalCondition {
                     var_name: "x",
                     interval_op: IntervalOp::In,
-                    interval: MultiInterval::new(Boundary::Closed, 0.0, 10.0, Boundary::Closed)
-                        .unwrap()
+                    interval: IntervalNode {
+                        lo_boundary: Boundary::Closed,
+                        lo: Number::Literal(num!(0.0)),
+                        hi: Number::Literal(num!(10.0)),
+                        hi_boundary: Boundary::Closed
+                    }
                 })
             ))
         );
@@ -396,8 +581,12 @@ mod tests {
                 Condition::Interval(IntervalCondition {
                     var_name: "y",
                     interval_op: IntervalOp::In,
-                    interval: MultiInterval::new(Boundary::Closed, 0.0, 10.0, Boundary::Closed)
-                        .unwrap()
+                    interval: IntervalNode {
+                        lo_boundary: Boundary::Closed,
+                        lo: Number::Literal(num!(0.0)),
+                        hi: Number::Literal(num!(10.0)),
+                        hi_boundary: Boundary::Closed
+                    }
                 })
             ))
         );
@@ -408,7 +597,28 @@ mod tests {
                 Condition::Interval(IntervalCondition {
                     var_name: "foo",
                     interval_op: IntervalOp::NotIn,
-                    interval: MultiInterval::new(Boundary::Open, 0.0, 0.0, Boundary::Open).unwrap()
+                    interval: IntervalNode {
+                        lo_boundary: Boundary::Open,
+                        lo: Number::Literal(num!(0.0)),
+                        hi: Number::Literal(num!(0.0)),
+                        hi_boundary: Boundary::Open
+                    }
+                })
+            ))
+        );
+        assert_eq!(
+            condition_interval("age in [MIN_AGE, MAX_AGE)"),
+            Ok((
+                "",
+                Condition::Interval(IntervalCondition {
+                    var_name: "age",
+                    interval_op: IntervalOp::In,
+                    interval: IntervalNode {
+                        lo_boundary: Boundary::Closed,
+                        lo: Number::Constant("MIN_AGE"),
+                        hi: Number::Constant("MAX_AGE"),
+                        hi_boundary: Boundary::Open
+                    }
                 })
             ))
         );
@@ -420,6 +630,286 @@ mod tests {
         assert!(condition_interval(" in [0, 10]").is_err());
     }
 
+    #[test]
+    fn test_condition_relation() {
+        assert_eq!(
+            condition_relation("withdrawal <= balance"),
+            Ok((
+                "",
+                Condition::Relation(RelationCondition {
+                    left: "withdrawal",
+                    binary_op: BinaryOp::LessThanEqualTo,
+                    right: "balance"
+                })
+            ))
+        );
+        assert_eq!(
+            condition_relation("tier!=gold) asd"),
+            Ok((
+                ") asd",
+                Condition::Relation(RelationCondition {
+                    left: "tier",
+                    binary_op: BinaryOp::NotEqual,
+                    right: "gold"
+                })
+            ))
+        );
+        assert!(condition_relation("x == true").is_err());
+        assert!(condition_relation("x < 10").is_err());
+        assert!(condition_relation("10 < x").is_err());
+        assert!(condition_relation("x ==").is_err());
+    }
+
+    #[test]
+    fn test_condition_enum_set() {
+        assert_eq!(
+            condition_enum_set("tier in {silver, gold}"),
+            Ok((
+                "",
+                Condition::Enum(EnumCondition {
+                    var_name: "tier",
+                    interval_op: IntervalOp::In,
+                    members: vec!["silver", "gold"]
+                })
+            ))
+        );
+        assert_eq!(
+            condition_enum_set("tier not in{bronze}  asd"),
+            Ok((
+                "asd",
+                Condition::Enum(EnumCondition {
+                    var_name: "tier",
+                    interval_op: IntervalOp::NotIn,
+                    members: vec!["bronze"]
+                })
+            ))
+        );
+        assert!(condition_enum_set("tier in [0, 10]").is_err());
+        assert!(condition_enum_set("tier in {}").is_err());
+        assert!(condition_enum_set("in {gold}").is_err());
+    }
+
+    #[test]
+    fn test_condition_value_set() {
+        assert_eq!(
+            condition("code not in {3, 7, MAX_CODE}"),
+            Ok((
+                "",
+                Condition::Set(SetCondition {
+                    var_name: "code",
+                    interval_op: IntervalOp::NotIn,
+                    values: vec![
+                        Number::Literal(num!(3.0)),
+                        Number::Literal(num!(7.0)),
+                        Number::Constant("MAX_CODE")
+                    ]
+                })
+            ))
+        );
+        assert!(matches!(
+            condition("code in {LOW, HIGH}"),
+            Ok(("", Condition::Enum(_)))
+        ));
+    }
+
+    #[test]
+    fn test_condition_modulo() {
+        assert_eq!(
+            condition("year % 4 == 0"),
+            Ok((
+                "",
+                Condition::Modulo(ModuloCondition {
+                    var_name: "year",
+                    divisor: Number::Literal(num!(4.0)),
+                    eq_op: EqOp::Equal,
+                    remainder: Number::Literal(num!(0.0))
+                })
+            ))
+        );
+        assert_eq!(
+            condition("order%BATCH!=BATCH_REST)"),
+            Ok((
+                ")",
+                Condition::Modulo(ModuloCondition {
+                    var_name: "order",
+                    divisor: Number::Constant("BATCH"),
+                    eq_op: EqOp::NotEqual,
+                    remainder: Number::Constant("BATCH_REST")
+                })
+            ))
+        );
+        assert!(condition_modulo("year % 4 < 2").is_err());
+        assert!(condition_modulo("2 % year == 0").is_err());
+    }
+
+    #[test]
+    fn test_condition_null() {
+        assert_eq!(
+            condition("coupon is null)"),
+            Ok((
+                ")",
+                Condition::Null(NullCondition {
+                    var_name: "coupon",
+                    is_null: true
+                })
+            ))
+        );
+        assert_eq!(
+            condition("order.coupon is not null"),
+            Ok((
+                "",
+                Condition::Null(NullCondition {
+                    var_name: "order.coupon",
+                    is_null: false
+                })
+            ))
+        );
+        assert!(condition_null("coupon is 5").is_err());
+        assert!(condition_null("null is coupon").is_err());
+    }
+
+    #[test]
+    fn test_condition_length() {
+        assert_eq!(
+            condition("len(username) in [3, 16])"),
+            Ok((
+                ")",
+                Condition::Length(LengthCondition {
+                    var_name: "username",
+                    comparison: LengthComparison::Interval(
+                        IntervalOp::In,
+                        IntervalNode {
+                            lo_boundary: Boundary::Closed,
+                            lo: Number::Literal(num!(3)),
+                            hi: Number::Literal(num!(16)),
+                            hi_boundary: Boundary::Closed,
+                        }
+                    )
+                })
+            ))
+        );
+        assert_eq!(
+            condition("len( user.name ) >= MIN_LENGTH"),
+            Ok((
+                "",
+                Condition::Length(LengthCondition {
+                    var_name: "user.name",
+                    comparison: LengthComparison::Binary(
+                        BinaryOp::GreaterThanEqualTo,
+                        Number::Constant("MIN_LENGTH")
+                    )
+                })
+            ))
+        );
+        assert!(condition_length("len username >= 3").is_err());
+        assert!(condition_length("len(username) matches \"[a-z]\"").is_err());
+    }
+
+    #[test]
+    fn test_condition_pattern() {
+        assert_eq!(
+            condition("postcode matches \"[0-9]\")"),
+            Ok((
+                ")",
+                Condition::Pattern(PatternCondition {
+                    var_name: "postcode",
+                    class: CharClass {
+                        ranges: vec![('0', '9')]
+                    }
+                })
+            ))
+        );
+        assert!(condition_pattern("postcode matches [0-9]").is_err());
+        assert!(conditions("!(postcode matches \"[0-9]\")").is_ok());
+    }
+
+    #[test]
+    fn test_condition_linear() {
+        let term = |coefficient, var_name| LinearTerm {
+            coefficient,
+            coefficient_constant: None,
+            var_name,
+        };
+
+        assert_eq!(
+            condition_linear("price + shipping >= 200"),
+            Ok((
+                "",
+                Condition::Linear(LinearCondition {
+                    lhs: LinearExpression {
+                        terms: vec![term(num!(1.0), "price"), term(num!(1.0), "shipping")],
+                        constant: num!(0.0)
+                    },
+                    binary_op: BinaryOp::GreaterThanEqualTo,
+                    rhs: LinearExpression {
+                        terms: vec![],
+                        constant: num!(200.0)
+                    }
+                })
+            ))
+        );
+        assert_eq!(
+            condition_linear("2*age - service > 10 && x"),
+            Ok((
+                "&& x",
+                Condition::Linear(LinearCondition {
+                    lhs: LinearExpression {
+                        terms: vec![term(num!(2.0), "age"), term(num!(-1.0), "service")],
+                        constant: num!(0.0)
+                    },
+                    binary_op: BinaryOp::GreaterThan,
+                    rhs: LinearExpression {
+                        terms: vec![],
+                        constant: num!(10.0)
+                    }
+                })
+            ))
+        );
+        assert_eq!(
+            condition_linear("x < y + 1"),
+            Ok((
+                "",
+                Condition::Linear(LinearCondition {
+                    lhs: LinearExpression {
+                        terms: vec![term(num!(1.0), "x")],
+                        constant: num!(0.0)
+                    },
+                    binary_op: BinaryOp::LessThan,
+                    rhs: LinearExpression {
+                        terms: vec![term(num!(1.0), "y")],
+                        constant: num!(1.0)
+                    }
+                })
+            ))
+        );
+        assert_eq!(
+            condition_linear("RATE * price < LIMIT"),
+            Ok((
+                "",
+                Condition::Linear(LinearCondition {
+                    lhs: LinearExpression {
+                        terms: vec![LinearTerm {
+                            coefficient: num!(1.0),
+                            coefficient_constant: Some("RATE"),
+                            var_name: "price"
+                        }],
+                        constant: num!(0.0)
+                    },
+                    binary_op: BinaryOp::LessThan,
+                    rhs: LinearExpression {
+                        terms: vec![term(num!(1.0), "LIMIT")],
+                        constant: num!(0.0)
+                    }
+                })
+            ))
+        );
+        assert!(condition_linear("x < 10").is_err());
+        assert!(condition_linear("10 < x").is_err());
+        assert!(condition_linear("x < y").is_err());
+        assert!(condition_linear("x == true").is_err());
+        assert!(condition_linear("x in [0, 10]").is_err());
+    }
+
     #[test]
     fn test_condition() {
         assert_eq!(
@@ -451,7 +941,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "x",
                     constant_position: ConstantPosition::LeftHandSide,
-                    constant: 10.32,
+                    constant: num!(10.32),
                     binary_op: BinaryOp::Equal
                 })
             ))
@@ -463,7 +953,7 @@ mod tests {
                 Condition::Binary(BinaryCondition {
                     var_name: "x",
                     constant_position: ConstantPosition::RightHandSide,
-                    constant: 10.32,
+                    constant: num!(10.32),
                     binary_op: BinaryOp::Equal
                 })
             ))
@@ -475,8 +965,74 @@ mod tests {
                 Condition::Interval(IntervalCondition {
                     var_name: "x",
                     interval_op: IntervalOp::In,
-                    interval: MultiInterval::new(Boundary::Closed, 0.0, 10.0, Boundary::Closed)
-                        .unwrap()
+                    interval: IntervalNode {
+                        lo_boundary: Boundary::Closed,
+                        lo: Number::Literal(num!(0.0)),
+                        hi: Number::Literal(num!(10.0)),
+                        hi_boundary: Boundary::Closed
+                    }
+                })
+            ))
+        );
+        assert_eq!(
+            condition("x > y"),
+            Ok((
+                "",
+                Condition::Relation(RelationCondition {
+                    left: "x",
+                    binary_op: BinaryOp::GreaterThan,
+                    right: "y"
+                })
+            ))
+        );
+        assert_eq!(
+            condition("x - y < 0"),
+            Ok((
+                "",
+                Condition::Linear(LinearCondition {
+                    lhs: LinearExpression {
+                        terms: vec![
+                            LinearTerm {
+                                coefficient: num!(1.0),
+                                coefficient_constant: None,
+                                var_name: "x"
+                            },
+                            LinearTerm {
+                                coefficient: num!(-1.0),
+                                coefficient_constant: None,
+                                var_name: "y"
+                            }
+                        ],
+                        constant: num!(0.0)
+                    },
+                    binary_op: BinaryOp::LessThan,
+                    rhs: LinearExpression {
+                        terms: vec![],
+                        constant: num!(0.0)
+                    }
+                })
+            ))
+        );
+        assert_eq!(
+            condition("x == 10"),
+            Ok((
+                "",
+                Condition::Binary(BinaryCondition {
+                    var_name: "x",
+                    constant_position: ConstantPosition::RightHandSide,
+                    constant: num!(10.0),
+                    binary_op: BinaryOp::Equal
+                })
+            ))
+        );
+        assert_eq!(
+            condition("tier not in {bronze, silver}"),
+            Ok((
+                "",
+                Condition::Enum(EnumCondition {
+                    var_name: "tier",
+                    interval_op: IntervalOp::NotIn,
+                    members: vec!["bronze", "silver"]
                 })
             ))
         );
@@ -493,7 +1049,7 @@ mod tests {
         let y_greater_0 = Condition::Binary(BinaryCondition {
             var_name: "y",
             constant_position: ConstantPosition::LeftHandSide,
-            constant: 0.0,
+            constant: num!(0.0),
             binary_op: BinaryOp::GreaterThan,
         });
 
@@ -557,8 +1113,63 @@ mod tests {
                 }
             ))
         );
-        // TODO: Add a bunch more tests for testing good precedence detection and stuff
         assert!(conditions("").is_err());
         assert!(conditions("true == x &&").is_err());
     }
+
+    #[test]
+    fn test_binding() {
+        assert_eq!(
+            binding("working_age && x > 5"),
+            Ok(("&& x > 5", ConditionsNode::Binding("working_age")))
+        );
+        assert_eq!(
+            binding("adult<=>senior"),
+            Ok(("<=>senior", ConditionsNode::Binding("adult")))
+        );
+        assert_eq!(
+            binding("adult)"),
+            Ok((")", ConditionsNode::Binding("adult")))
+        );
+        assert!(binding("x > 5").is_err());
+        assert!(binding("x<=5").is_err());
+        assert!(binding("x == true").is_err());
+        assert!(binding("x in [0, 5]").is_err());
+        assert!(binding("x % 2 == 0").is_err());
+        assert!(binding("x + y > 5").is_err());
+        assert!(binding("x is null").is_err());
+    }
+
+    /// The grouping of the operators, with every condition written as `c`
+    fn grouping(node: &ConditionsNode) -> String {
+        match node {
+            ConditionsNode::Negated(node) => format!("!{}", grouping(node)),
+            ConditionsNode::Expression(_) => "c".to_owned(),
+            ConditionsNode::Group {
+                operator,
+                left,
+                right,
+            } => format!("({} {operator:?} {})", grouping(left), grouping(right)),
+            ConditionsNode::Binding(name) => (*name).to_owned(),
+        }
+    }
+
+    #[rstest]
+    #[case("x > 1 && x > 2 ^ x > 3", "((c And c) Xor c)")]
+    #[case("x > 1 ^ x > 2 || x > 3", "((c Xor c) Or c)")]
+    #[case("x > 1 || x > 2 => x > 3", "((c Or c) Implies c)")]
+    #[case("x > 1 => x > 2 => x > 3", "(c Implies (c Implies c))")]
+    #[case(
+        "x > 1 => x > 2 <=> x > 3 => x > 4",
+        "((c Implies c) Equivalent (c Implies c))"
+    )]
+    #[case("x>1<=>!(x<=2^x>=3)", "(c Equivalent !(c Xor c))")]
+    #[case("(x > 1 <=> x > 2) && x > 3", "((c Equivalent c) And c)")]
+    #[case("adult && x > 1 <=> senior", "((adult And c) Equivalent senior)")]
+    #[case("!adult=>x in [0, 5] || (senior)", "(!adult Implies (c Or senior))")]
+    fn test_conditions_precedence(#[case] input: &str, #[case] expected: &str) {
+        let (rest, node) = conditions(input).unwrap();
+
+        assert_eq!((rest, grouping(&node).as_str()), ("", expected));
+    }
 }
@@ -0,0 +1,67 @@
+use nom::{combinator::cut, error::context};
+
+use super::{
+    ast::ConstNode,
+    primitives::{identifier, number_or_constant},
+    utils::{token, token_lit},
+    IResult,
+};
+
+pub fn const_declaration(input: &str) -> IResult<ConstNode> {
+    context("const declaration", |input| {
+        let (input, _) = token_lit("const")(input)?;
+        cut(|input| {
+            let (input, name) = token(identifier)(input)?;
+            let (input, _) = token_lit("=")(input)?;
+            let (input, value) = token(number_or_constant)(input)?;
+
+            Ok((input, ConstNode { name, value }))
+        })(input)
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{number::num, parser::ast::Number};
+
+    #[test]
+    fn test_const_declaration() {
+        assert_eq!(
+            const_declaration("const DISCOUNT_THRESHOLD = 200"),
+            Ok((
+                "",
+                ConstNode {
+                    name: "DISCOUNT_THRESHOLD",
+                    value: Number::Literal(num!(200.0))
+                }
+            ))
+        );
+        assert_eq!(
+            const_declaration("const max_price=-0.5 var"),
+            Ok((
+                "var",
+                ConstNode {
+                    name: "max_price",
+                    value: Number::Literal(num!(-0.5))
+                }
+            ))
+        );
+        assert_eq!(
+            const_declaration("const LIMIT = DISCOUNT_THRESHOLD"),
+            Ok((
+                "",
+                ConstNode {
+                    name: "LIMIT",
+                    value: Number::Constant("DISCOUNT_THRESHOLD")
+                }
+            ))
+        );
+        assert!(const_declaration("const LIMIT").is_err());
+        assert!(const_declaration("const LIMIT = ").is_err());
+        assert!(const_declaration("const 10 = 10").is_err());
+        assert!(const_declaration("var LIMIT = 10").is_err());
+    }
+}
@@ -5,54 +5,78 @@ use nom::{
 };
 
 use super::{
-    ast::{FeatureNode, IfNode, VarNode},
-    if_statement::if_statement,
+    assumption::assumption,
+    ast::{ConditionsNode, ConstNode, FeatureNode, IfNode, LetNode, SwitchNode, VarNode},
+    const_declaration::const_declaration,
+    if_statement::{if_statement, requirements},
+    let_binding::let_binding,
+    switch_statement::switch_statement,
     utils::token_lit,
     var_declaration::var_declaration,
     IResult,
 };
 
 pub fn feature_body(input: &str) -> IResult<FeatureNode> {
-    enum VarOrIf<'a> {
+    enum Statement<'a> {
+        Const(ConstNode<'a>),
         Var(VarNode<'a>),
         If(IfNode<'a>),
+        Switch(SwitchNode<'a>),
+        Assumption(ConditionsNode<'a>),
+        Let(LetNode<'a>),
     }
 
-    fn var_or_if(input: &str) -> IResult<VarOrIf> {
+    fn statement(input: &str) -> IResult<Statement> {
         alt((
-            map(var_declaration, VarOrIf::Var),
-            map(if_statement, VarOrIf::If),
+            map(const_declaration, Statement::Const),
+            map(var_declaration, Statement::Var),
+            map(if_statement, Statement::If),
+            map(switch_statement, Statement::Switch),
+            map(assumption, Statement::Assumption),
+            map(let_binding, Statement::Let),
         ))(input)
     }
 
-    let (input, nodes) = many1(var_or_if)(input)?;
+    let (input, nodes) = many1(statement)(input)?;
 
-    let (variables, if_statements) = nodes.into_iter().fold(
-        (Vec::new(), Vec::new()),
-        |(mut variables, mut if_statements), x| {
-            match x {
-                VarOrIf::Var(var_node) => variables.push(var_node),
-                VarOrIf::If(if_node) => if_statements.push(if_node),
-            }
-            (variables, if_statements)
-        },
-    );
+    let mut feature_node = FeatureNode {
+        requirements: Vec::new(),
+        constants: Vec::new(),
+        variables: Vec::new(),
+        if_statements: Vec::new(),
+        switch_statements: Vec::new(),
+        assumptions: Vec::new(),
+        bindings: Vec::new(),
+        errors: Vec::new(),
+        instance: None,
+    };
+    for node in nodes {
+        match node {
+            Statement::Const(const_node) => feature_node.constants.push(const_node),
+            Statement::Var(var_node) => feature_node.variables.push(var_node),
+            Statement::If(if_node) => feature_node.if_statements.push(if_node),
+            Statement::Switch(switch_node) => feature_node.switch_statements.push(switch_node),
+            Statement::Assumption(conditions) => feature_node.assumptions.push(conditions),
+            Statement::Let(let_node) => feature_node.bindings.push(let_node),
+        }
+    }
 
-    Ok((
-        input,
-        FeatureNode {
-            variables,
-            if_statements,
-        },
-    ))
+    Ok((input, feature_node))
 }
 
 pub fn feature(input: &str) -> IResult<FeatureNode> {
+    let (input, requirements) = requirements(input)?;
     let (input, _) = token_lit("[")(input)?;
     let (input, feature_node) = cut(feature_body)(input)?;
     let (input, _) = cut(token_lit("]"))(input)?;
 
-    Ok((input, feature_node))
+    Ok((
+        input,
+        FeatureNode {
+            requirements,
+            ..feature_node
+        },
+    ))
 }
 
 #[cfg(test)]
@@ -120,8 +144,15 @@ mod tests {
             Ok((
                 "",
                 FeatureNode {
+                    requirements: vec![],
+                    constants: Vec::new(),
                     variables: Vec::new(),
-                    if_statements: Vec::new()
+                    if_statements: Vec::new(),
+                    switch_statements: Vec::new(),
+                    assumptions: Vec::new(),
+                    bindings: Vec::new(),
+                    errors: Vec::new(),
+                    instance: None,
                 }
             ))
         );
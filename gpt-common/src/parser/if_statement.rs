@@ -1,31 +1,81 @@
 use nom::{
-    combinator::{cut, map, opt},
+    branch::alt,
+    combinator::{cut, map, opt, recognize},
     error::context,
-    multi::{many0, many1},
-    sequence::tuple,
+    multi::{many0, many1, separated_list1},
+    sequence::{delimited, preceded, separated_pair, tuple},
 };
 
 use super::{
-    ast::{ElseIfNode, ElseNode, IfNode},
+    ast::{ElseIfNode, ElseNode, IfNode, Outcome},
     condition::conditions,
-    utils::token_lit,
+    primitives::{boolean, number, string_literal, var_name},
+    utils::{token, token_lit},
     IResult,
 };
 
+fn outcome_value(input: &str) -> IResult<&str> {
+    alt((
+        recognize(number),
+        recognize(boolean),
+        recognize(string_literal),
+        var_name,
+    ))(input)
+}
+
+/// Parses the expected outcome of a branch, like `=> discount = 10` or `=> "free delivery"`
+pub(super) fn outcome(input: &str) -> IResult<Outcome> {
+    context(
+        "outcome",
+        preceded(
+            token_lit("=>"),
+            cut(alt((
+                map(token(string_literal), Outcome::Description),
+                map(
+                    separated_pair(token(var_name), token_lit("="), token(outcome_value)),
+                    |(var_name, value)| Outcome::Assignment { var_name, value },
+                ),
+            ))),
+        ),
+    )(input)
+}
+
+fn requirement(input: &str) -> IResult<Vec<&str>> {
+    preceded(
+        token_lit("@req"),
+        cut(delimited(
+            token_lit("("),
+            separated_list1(token_lit(","), token(string_literal)),
+            token_lit(")"),
+        )),
+    )(input)
+}
+
+/// Parses the IDs of the requirements a branch or a feature traces to, like `@req("R1", "R2-1") @req("R3")`
+pub(super) fn requirements(input: &str) -> IResult<Vec<&str>> {
+    map(many0(token(requirement)), |requirements| {
+        requirements.into_iter().flatten().collect()
+    })(input)
+}
+
 fn else_if_statement(input: &str) -> IResult<ElseIfNode> {
     context("else if statement", |input| {
+        let (input, requirements) = requirements(input)?;
         let (input, _) = token_lit("else")(input)?;
         let (input, _) = token_lit("if")(input)?;
         let (input, _) = token_lit("(")(input)?;
         let (input, conditions) = conditions(input)?;
         let (input, _) = token_lit(")")(input)?;
+        let (input, outcome) = opt(outcome)(input)?;
         let (input, body) = opt(map(
             tuple((token_lit("{"), many0(if_statement), token_lit("}"))),
             |(_, body, _)| body,
         ))(input)?;
 
         let else_if_node = ElseIfNode {
+            requirements,
             conditions,
+            outcome,
             body: body.unwrap_or_default(),
         };
 
@@ -35,7 +85,9 @@ fn else_if_statement(input: &str) -> IResult<ElseIfNode> {
 
 fn else_statement(input: &str) -> IResult<ElseNode> {
     context("else statement", |input| {
+        let (input, requirements) = requirements(input)?;
         let (input, _) = token_lit("else")(input)?;
+        let (input, outcome) = opt(outcome)(input)?;
         let (input, if_statements) = opt(|input| {
             let (input, _) = token_lit("{")(input)?;
             let (input, if_statements) = many0(if_statement)(input)?;
@@ -45,6 +97,8 @@ fn else_statement(input: &str) -> IResult<ElseNode> {
         })(input)?;
 
         let else_node = ElseNode {
+            requirements,
+            outcome,
             body: if_statements.unwrap_or_default(),
         };
 
@@ -54,11 +108,13 @@ fn else_statement(input: &str) -> IResult<ElseNode> {
 
 pub fn if_statement(input: &str) -> IResult<IfNode> {
     context("if statement", |input| {
+        let (input, requirements) = requirements(input)?;
         let (input, _) = token_lit("if")(input)?;
-        cut(|input| {
+        cut(move |input| {
             let (input, _) = token_lit("(")(input)?;
             let (input, conditions) = conditions(input)?;
             let (input, _) = token_lit(")")(input)?;
+            let (input, outcome) = opt(outcome)(input)?;
             let (input, body) = opt(map(
                 tuple((token_lit("{"), many0(if_statement), token_lit("}"))),
                 |(_, body, _)| body,
@@ -68,8 +124,10 @@ pub fn if_statement(input: &str) -> IResult<IfNode> {
             let (input, else_statement) = opt(else_statement)(input)?;
 
             let if_node = IfNode {
+                requirements: requirements.clone(),
                 body,
                 conditions,
+                outcome,
                 else_if: else_if_statements.unwrap_or_default(),
                 else_node: else_statement,
             };
@@ -102,20 +160,30 @@ mod tests {
             Ok((
                 "qwe",
                 IfNode {
+                    requirements: vec![],
                     conditions: conditions("x >= 5 && y in (0, 10)").unwrap().1,
+                    outcome: None,
                     body: Some(vec![IfNode {
+                        requirements: vec![],
                         conditions: conditions("x == true").unwrap().1,
+                        outcome: None,
                         body: None,
                         else_if: vec![],
                         else_node: None
                     }]),
                     else_if: vec![ElseIfNode {
+                        requirements: vec![],
                         conditions: conditions("x < 4 && y > 6").unwrap().1,
+                        outcome: None,
                         body: vec![]
                     }],
                     else_node: Some(ElseNode {
+                        requirements: vec![],
+                        outcome: None,
                         body: vec![IfNode {
+                            requirements: vec![],
                             conditions: conditions("x != false").unwrap().1,
+                            outcome: None,
                             body: None,
                             else_if: vec![],
                             else_node: None
@@ -124,9 +192,116 @@ mod tests {
                 }
             ))
         );
+        assert_eq!(
+            if_statement(
+                "
+            if (price >= 200) => discount = 10 {
+                if (vip == true) => discount = DISCOUNT
+            } else => \"no discount\"
+        "
+                .trim()
+            ),
+            Ok((
+                "",
+                IfNode {
+                    requirements: vec![],
+                    conditions: conditions("price >= 200").unwrap().1,
+                    outcome: Some(Outcome::Assignment {
+                        var_name: "discount",
+                        value: "10"
+                    }),
+                    body: Some(vec![IfNode {
+                        requirements: vec![],
+                        conditions: conditions("vip == true").unwrap().1,
+                        outcome: Some(Outcome::Assignment {
+                            var_name: "discount",
+                            value: "DISCOUNT"
+                        }),
+                        body: None,
+                        else_if: vec![],
+                        else_node: None
+                    }]),
+                    else_if: vec![],
+                    else_node: Some(ElseNode {
+                        requirements: vec![],
+                        outcome: Some(Outcome::Description("no discount")),
+                        body: vec![]
+                    })
+                }
+            ))
+        );
+        assert_eq!(
+            if_statement(
+                "
+            @req(\"R2\") @req(\"R2-1\", \"R2-2\")
+            if (age < 18)
+            @req(\"R3\") else
+        "
+                .trim()
+            ),
+            Ok((
+                "",
+                IfNode {
+                    requirements: vec!["R2", "R2-1", "R2-2"],
+                    conditions: conditions("age < 18").unwrap().1,
+                    outcome: None,
+                    body: None,
+                    else_if: vec![],
+                    else_node: Some(ElseNode {
+                        requirements: vec!["R3"],
+                        outcome: None,
+                        body: vec![]
+                    })
+                }
+            ))
+        );
+        assert!(if_statement("if (x == true) =>").is_err());
+        assert!(if_statement("@req(R1) if (x == true)").is_err());
+        assert!(if_statement("@req() if (x == true)").is_err());
+        assert!(if_statement("if (x == true) => discount").is_err());
         // TODO
     }
 
+    #[test]
+    fn test_outcome() {
+        assert_eq!(
+            outcome("=> \"free delivery\" if"),
+            Ok(("if", Outcome::Description("free delivery")))
+        );
+        assert_eq!(
+            outcome("=>discount=-2.5"),
+            Ok((
+                "",
+                Outcome::Assignment {
+                    var_name: "discount",
+                    value: "-2.5"
+                }
+            ))
+        );
+        assert_eq!(
+            outcome("=> approved = false }"),
+            Ok((
+                "}",
+                Outcome::Assignment {
+                    var_name: "approved",
+                    value: "false"
+                }
+            ))
+        );
+        assert_eq!(
+            outcome("=> status = \"rejected\""),
+            Ok((
+                "",
+                Outcome::Assignment {
+                    var_name: "status",
+                    value: "\"rejected\""
+                }
+            ))
+        );
+        assert!(outcome("discount = 10").is_err());
+        assert!(outcome("=> 10").is_err());
+    }
+
     #[test]
     #[ignore = "todo"]
     fn test_else_if_statement() {
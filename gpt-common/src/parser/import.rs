@@ -0,0 +1,34 @@
+use nom::{combinator::cut, error::context};
+
+use super::{
+    primitives::string_literal,
+    utils::{token, token_lit},
+    IResult,
+};
+
+/// Parses an import of another file, like `import "common.gpt"`
+pub fn import(input: &str) -> IResult<&str> {
+    context("import", |input| {
+        let (input, _) = token_lit("import")(input)?;
+        cut(token(string_literal))(input)
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        assert_eq!(import("import \"common.gpt\""), Ok(("", "common.gpt")));
+        assert_eq!(
+            import("import\"../shared/vars.gpt\"  var"),
+            Ok(("var", "../shared/vars.gpt"))
+        );
+        assert!(import("import common.gpt").is_err());
+        assert!(import("import \"common.gpt").is_err());
+        assert!(import("const X = 1").is_err());
+    }
+}
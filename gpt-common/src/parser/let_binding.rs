@@ -0,0 +1,70 @@
+use nom::{combinator::cut, error::context};
+
+use super::{
+    ast::LetNode,
+    condition::conditions,
+    primitives::identifier,
+    utils::{token, token_lit},
+    IResult,
+};
+
+/// Parses a named condition, like `let working_age = age >= 18 && age < 60`
+pub fn let_binding(input: &str) -> IResult<LetNode> {
+    context("let binding", |input| {
+        let (input, _) = token_lit("let")(input)?;
+        cut(|input| {
+            let (input, name) = token(identifier)(input)?;
+            let (input, _) = token_lit("=")(input)?;
+            let (input, conditions) = conditions(input)?;
+
+            Ok((input, LetNode { name, conditions }))
+        })(input)
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parser::ast::ConditionsNode;
+
+    #[test]
+    fn test_let_binding() {
+        assert_eq!(
+            let_binding("let working_age = age >= 18 && age < 60 if"),
+            Ok((
+                "if",
+                LetNode {
+                    name: "working_age",
+                    conditions: conditions("age >= 18 && age < 60").unwrap().1
+                }
+            ))
+        );
+        assert_eq!(
+            let_binding("let senior=working_age && service >= 30\nlet"),
+            Ok((
+                "let",
+                LetNode {
+                    name: "senior",
+                    conditions: conditions("working_age && service >= 30").unwrap().1
+                }
+            ))
+        );
+        assert_eq!(
+            let_binding("let retired = !working_age"),
+            Ok((
+                "",
+                LetNode {
+                    name: "retired",
+                    conditions: ConditionsNode::Negated(Box::new(ConditionsNode::Binding(
+                        "working_age"
+                    )))
+                }
+            ))
+        );
+        assert!(let_binding("let working_age").is_err());
+        assert!(let_binding("let = age >= 18").is_err());
+        assert!(let_binding("var age: int").is_err());
+    }
+}
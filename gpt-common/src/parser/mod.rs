@@ -1,66 +1,184 @@
+mod arithmetic;
+mod assumption;
 pub mod ast;
 mod condition;
+mod const_declaration;
 mod feature;
 mod if_statement;
+mod import;
+mod let_binding;
 mod primitives;
+mod recovery;
+mod switch_statement;
+mod template;
 mod utils;
 mod var_declaration;
 
-use ast::RootNode;
+use ast::{ConditionsNode, ConstNode, FeatureNode, RootNode, TemplateNode, VarNode};
 use nom::{
     branch::alt,
-    combinator::{eof, map},
+    combinator::{consumed, eof, map},
     error::VerboseError,
-    multi::many1,
+    multi::{many0, many1},
+    sequence::preceded,
 };
 
 pub use self::primitives::interval;
 use self::{
+    assumption::assumption,
+    const_declaration::const_declaration,
     feature::{feature, feature_body},
+    import::import,
+    template::{template, use_template},
     utils::{token, whitespace},
+    var_declaration::var_declaration,
 };
+pub(crate) use self::{recovery::root_recovering, template::expand_templates};
 use super::dto::NTupleInput;
-use crate::ir;
+use crate::{
+    diagnostic::Severity,
+    ir,
+    loader::{check_source_files, parse_error_in_file, SourceFile},
+    prelude::{GPTError, Result},
+    INPUT_PATH,
+};
+
+pub(crate) type IResult<'a, O> = nom::IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// Parses the imports at the start of a file, without parsing the rest of it.
+/// The path of every import comes with its whole statement, so the problems of the import can point at it.
+pub(crate) fn imports(input: &str) -> IResult<Vec<(&str, &str)>> {
+    preceded(
+        whitespace,
+        many0(token(map(consumed(import), |(statement, path)| {
+            (statement.trim_end(), path)
+        }))),
+    )(input)
+}
 
-type IResult<'a, O> = nom::IResult<&'a str, O, VerboseError<&'a str>>;
+pub(crate) fn root(input: &str) -> IResult<RootNode> {
+    enum Declaration<'a> {
+        Const(ConstNode<'a>),
+        Var(VarNode<'a>),
+        Assumption(ConditionsNode<'a>),
+    }
+
+    let (input, imports) = imports(input)?;
+    let imports = imports
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect::<Vec<_>>();
 
-fn root(input: &str) -> IResult<RootNode> {
-    let (input, _) = whitespace(input)?;
+    // Declarations before the features are shared by every feature
+    let (input, declarations) = many0(token(alt((
+        map(const_declaration, Declaration::Const),
+        map(var_declaration, Declaration::Var),
+        map(assumption, Declaration::Assumption),
+    ))))(input)?;
+    let (constants, variables, assumptions) = declarations.into_iter().fold(
+        (Vec::new(), Vec::new(), Vec::new()),
+        |(mut constants, mut variables, mut assumptions), declaration| {
+            match declaration {
+                Declaration::Const(const_node) => constants.push(const_node),
+                Declaration::Var(var_node) => variables.push(var_node),
+                Declaration::Assumption(conditions) => assumptions.push(conditions),
+            }
+            (constants, variables, assumptions)
+        },
+    );
 
     // Accept empty files as input
     if eof::<&str, VerboseError<&str>>(input).is_ok() {
-        return Ok((input, RootNode { features: vec![] }));
+        return Ok((
+            input,
+            RootNode {
+                imports,
+                constants,
+                variables,
+                assumptions,
+                templates: vec![],
+                features: vec![],
+                errors: vec![],
+            },
+        ));
+    }
+
+    enum Item<'a> {
+        Template(TemplateNode<'a>),
+        Feature(FeatureNode<'a>),
     }
 
-    let (input, features) = alt((
-        many1(token(feature)),                 // Either a list of HGPT features
-        map(token(feature_body), |x| vec![x]), // Or a sungle feature without the brackets
+    let (input, items) = alt((
+        // Either a list of HGPT features, templates and their uses
+        many1(token(alt((
+            map(feature, Item::Feature),
+            map(template, Item::Template),
+            map(use_template, Item::Feature),
+        )))),
+        map(token(feature_body), |x| vec![Item::Feature(x)]), // Or a sungle feature without the brackets
     ))(input)?;
     let (input, _) = eof(input)?;
+    let (templates, features) = items.into_iter().fold(
+        (Vec::new(), Vec::new()),
+        |(mut templates, mut features), item| {
+            match item {
+                Item::Template(template_node) => templates.push(template_node),
+                Item::Feature(feature_node) => features.push(feature_node),
+            }
+            (templates, features)
+        },
+    );
 
-    Ok((input, RootNode { features }))
+    Ok((
+        input,
+        RootNode {
+            imports,
+            constants,
+            variables,
+            assumptions,
+            templates,
+            features,
+            errors: vec![],
+        },
+    ))
 }
 
-pub fn parse_gpt_to_ir(input: &str) -> IResult<Vec<ir::Feature>> {
-    let (input, ast) = root(input)?;
-    let ir_features = ir::ast_to_ir::convert_ast_to_ir(&ast);
+/// The input is checked before the conversion, its errors are returned as `GPTError::SemanticError`
+pub fn parse_gpt_to_ir(input: &str) -> Result<Vec<ir::Feature>> {
+    let mut ast = parse_error_in_file(INPUT_PATH, input, root(input))?;
+    expand_templates(&mut ast);
 
-    Ok((input, ir_features))
+    let sources = [SourceFile {
+        path: INPUT_PATH.to_owned(),
+        source: input.to_owned(),
+    }];
+    let diagnostics = check_source_files(&sources, &mut ast);
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        return Err(GPTError::SemanticError(diagnostics));
+    }
+
+    Ok(ir::ast_to_ir::convert_ast_to_ir(&ast))
 }
 
-pub fn parse_gpt_to_features(input: &str) -> IResult<Vec<Vec<NTupleInput>>> {
-    let (input, ast) = root(input)?;
-    let ir_features = ir::ast_to_ir::convert_ast_to_ir(&ast);
-    let ntuples_for_features = ir_features
+pub fn parse_gpt_to_features(input: &str) -> Result<Vec<Vec<NTupleInput>>> {
+    let ntuples_for_features = parse_gpt_to_ir(input)?
         .iter()
         .map(ir::ir_to_ntuple::ir_to_ntuple)
         .collect();
 
-    Ok((input, ntuples_for_features))
+    Ok(ntuples_for_features)
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::diagnostic::code;
+
     #[test]
     #[ignore = "todo"]
     fn test_root() {
@@ -72,4 +190,27 @@ mod tests {
     fn test_feature() {
         todo!("Write tests for feature parser")
     }
+
+    #[rstest]
+    #[case::undefined_constant("var x: int\nif(x in [0, FOO])", code::UNDEFINED_CONSTANT)]
+    #[case::invalid_interval(
+        "const A = 5\nconst B = 1\nvar x: int\nif(x in [A, B])",
+        code::INVALID_INTERVAL
+    )]
+    #[case::duplicate_variable("var x: int\nvar x: int\nif(x > 1)", code::DUPLICATE_DECLARATION)]
+    #[case::cancelled_variables("var x: int\nif(2 * x > x + x)", code::CONSTANT_CONDITION)]
+    #[case::enum_comparison("var t: enum { gold, silver }\nif(t < gold)", code::TYPE_MISMATCH)]
+    fn test_parse_gpt_to_ir_with_semantic_error(#[case] input: &str, #[case] expected: &str) {
+        match parse_gpt_to_ir(input) {
+            Err(GPTError::SemanticError(diagnostics)) => assert_eq!(
+                diagnostics
+                    .iter()
+                    .map(|diagnostic| diagnostic.code)
+                    .collect::<Vec<_>>(),
+                vec![expected]
+            ),
+            Err(err) => panic!("Expected a semantic error, got {err:?}"),
+            Ok(_) => panic!("Expected a semantic error"),
+        }
+    }
 }
@@ -1,60 +1,84 @@
-use std::{collections::HashSet, num::ParseIntError};
+use std::collections::HashSet;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_till},
     character::{
-        complete::{anychar, digit1},
+        complete::{anychar, char, digit1},
         is_alphabetic, is_alphanumeric,
-        streaming::char,
     },
-    combinator::{complete, cut, fail, map, map_res, opt, recognize, value},
-    error::context,
-    multi::many0,
-    sequence::tuple,
+    combinator::{complete, cut, fail, map, map_opt, map_res, opt, recognize, value, verify},
+    error::{context, ErrorKind, ParseError, VerboseError},
+    multi::{many0, separated_list1},
+    sequence::{delimited, tuple},
 };
 
 use super::{
-    ast::{BinaryOp, EqOp, IntervalOp},
+    ast::{BinaryOp, CharClass, EqOp, IntervalNode, IntervalOp, Number},
     utils::token,
     IResult,
 };
-use crate::interval::{Boundary, MultiInterval};
+use crate::{
+    interval::{Boundary, IntervalError, MultiInterval},
+    number::Num,
+};
 
-pub fn float(input: &str) -> IResult<f32> {
+/// Parses the digits matched by `digits` as a number.
+/// If they are out of the range of numbers, the parsing fails at them, instead of trying to parse something else there.
+fn parse_digits<'a>(
+    mut digits: impl FnMut(&'a str) -> IResult<'a, &'a str>,
+) -> impl FnMut(&'a str) -> IResult<'a, Num> {
+    move |input| {
+        let (rest, literal) = digits(input)?;
+        let num = literal.parse().map_err(|_| {
+            nom::Err::Failure(VerboseError::from_error_kind(input, ErrorKind::TooLarge))
+        })?;
+
+        Ok((rest, num))
+    }
+}
+
+pub fn float(input: &str) -> IResult<Num> {
     context(
         "float",
-        map_res(
-            recognize(tuple((opt(char('-')), digit1, char('.'), cut(digit1)))),
-            str::parse,
-        ),
+        parse_digits(recognize(tuple((
+            opt(char('-')),
+            digit1,
+            char('.'),
+            cut(digit1),
+        )))),
     )(input)
 }
 
-pub fn int(input: &str) -> IResult<f32> {
-    map_res(
-        recognize(tuple((opt(char('-')), digit1))),
-        |x: &str| -> Result<f32, ParseIntError> {
-            let int = x.parse::<i32>()?;
-            Ok(int as f32)
-        },
-    )(input)
+pub fn int(input: &str) -> IResult<Num> {
+    parse_digits(recognize(tuple((opt(char('-')), digit1))))(input)
 }
 
-pub fn infinity(input: &str) -> IResult<f32> {
+pub fn infinity(input: &str) -> IResult<Num> {
     map(tuple((opt(char('-')), tag("Inf"))), |(minus, _)| {
         if minus.is_none() {
-            f32::INFINITY
+            Num::INFINITY
         } else {
-            f32::NEG_INFINITY
+            Num::NEG_INFINITY
         }
     })(input)
 }
 
-pub fn number(input: &str) -> IResult<f32> {
+pub fn number(input: &str) -> IResult<Num> {
     context("number", alt((complete(float), int, infinity)))(input)
 }
 
+/// Parses a number, or the name of a constant, which is resolved in `ast_to_ir`
+pub fn number_or_constant(input: &str) -> IResult<Number> {
+    context(
+        "number or constant",
+        alt((
+            map(number, Number::Literal),
+            map(var_name, Number::Constant),
+        )),
+    )(input)
+}
+
 pub fn boolean(input: &str) -> IResult<bool> {
     context(
         "boolean",
@@ -82,6 +106,17 @@ pub fn interval_op(input: &str) -> IResult<IntervalOp> {
     )(input)
 }
 
+/// `is null`, or `is not null`, which parses as `false`
+pub fn null_op(input: &str) -> IResult<bool> {
+    context(
+        "Null check",
+        alt((
+            value(true, tag("is null")),
+            value(false, tag("is not null")),
+        )),
+    )(input)
+}
+
 pub fn binary_op(input: &str) -> IResult<BinaryOp> {
     context(
         "Binary Operator",
@@ -128,6 +163,113 @@ pub fn interval(input: &str) -> IResult<MultiInterval> {
     )(input)
 }
 
+/// Parses an interval whose bounds can be constants, like `[MIN_AGE, 130]`
+pub fn interval_node(input: &str) -> IResult<IntervalNode> {
+    context(
+        "interval",
+        map_res(
+            tuple((
+                token(parse_lo_openness),
+                token(number_or_constant),
+                token(char(',')),
+                token(number_or_constant),
+                token(parse_hi_openness),
+            )),
+            |(lo_boundary, lo, _comma, hi, hi_boundary)| {
+                // The bounds can only be checked here if they aren't constants
+                if let (Number::Literal(lo), Number::Literal(hi)) = (lo, hi) {
+                    MultiInterval::new(lo_boundary, lo, hi, hi_boundary)?;
+                }
+
+                Ok::<_, IntervalError>(IntervalNode {
+                    lo_boundary,
+                    lo,
+                    hi,
+                    hi_boundary,
+                })
+            },
+        ),
+    )(input)
+}
+
+/// Parses a text between double quotes, like `"free delivery"`, without the quotes
+pub fn string_literal(input: &str) -> IResult<&str> {
+    context(
+        "string",
+        complete(delimited(char('"'), take_till(|c| c == '"'), char('"'))),
+    )(input)
+}
+
+/// The ranges of a character class like `[a-z_]`.
+/// A `-` between two characters is a range, anywhere else it's the character itself.
+fn char_class_ranges(text: &str) -> Option<CharClass> {
+    let chars = text
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .chars()
+        .collect::<Vec<_>>();
+    let mut ranges = Vec::new();
+    let mut rest = &chars[..];
+    while !rest.is_empty() {
+        match rest {
+            [lo, '-', hi, tail @ ..] => {
+                if lo > hi {
+                    return None;
+                }
+                ranges.push((*lo, *hi));
+                rest = tail;
+            }
+            [c, tail @ ..] => {
+                ranges.push((*c, *c));
+                rest = tail;
+            }
+            [] => unreachable!(),
+        }
+    }
+
+    (!ranges.is_empty()).then_some(CharClass { ranges })
+}
+
+/// Parses a character class between double quotes, like `"[a-z0-9_]"`
+pub fn char_class(input: &str) -> IResult<CharClass> {
+    context(
+        "character class",
+        map_opt(string_literal, char_class_ranges),
+    )(input)
+}
+
+/// Parses a set of enum members, like `{ bronze, silver, gold }`
+pub fn member_set(input: &str) -> IResult<Vec<&str>> {
+    context(
+        "member set",
+        delimited(
+            token(char('{')),
+            separated_list1(token(char(',')), token(identifier)),
+            char('}'),
+        ),
+    )(input)
+}
+
+/// Parses a set of numbers, like `{3, 7, MAX_CODE}`.
+/// A set of only names is a `member_set`, since the names can't be told apart from enum members.
+pub fn value_set(input: &str) -> IResult<Vec<Number>> {
+    context(
+        "value set",
+        verify(
+            delimited(
+                token(char('{')),
+                separated_list1(token(char(',')), token(number_or_constant)),
+                char('}'),
+            ),
+            |values: &Vec<Number>| {
+                values
+                    .iter()
+                    .any(|value| matches!(value, Number::Literal(_)))
+            },
+        ),
+    )(input)
+}
+
 fn parse_alphabetic(input: &str) -> IResult<char> {
     let (i, c) = anychar(input)?;
     if is_alphabetic(c as u8) {
@@ -147,10 +289,13 @@ fn parse_alphanumberic_or_underscore(input: &str) -> IResult<char> {
 }
 
 fn keywords() -> HashSet<&'static str> {
-    HashSet::from(["if", "else", "true", "false"])
+    HashSet::from([
+        "if", "else", "true", "false", "null", "switch", "case", "default",
+    ])
 }
 
-pub fn var_name(input: &str) -> IResult<&str> {
+/// A name of a declaration, like a variable, a constant or a field of a record
+pub fn identifier(input: &str) -> IResult<&str> {
     context(
         "Variable name",
         map_res(
@@ -171,65 +316,105 @@ pub fn var_name(input: &str) -> IResult<&str> {
     )(input)
 }
 
+/// A variable, or a field of a record variable by its path, like `order.price`
+pub fn var_name(input: &str) -> IResult<&str> {
+    recognize(separated_list1(tag("."), identifier))(input)
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::number::num;
 
     #[test]
     fn test_int() {
-        assert_eq!(int("1"), Ok(("", 1.0)));
-        assert_eq!(int("-1"), Ok(("", -1.0)));
-        assert_eq!(int("1.0"), Ok((".0", 1.0)));
-        assert_eq!(int("123"), Ok(("", 123.0)));
-        assert_eq!(int("-123"), Ok(("", -123.0)));
-        assert_eq!(int("123.0"), Ok((".0", 123.0)));
-        assert_eq!(int("123.123"), Ok((".123", 123.0)));
-        assert_eq!(int("123.123000000"), Ok((".123000000", 123.0)));
-        assert_eq!(int("123."), Ok((".", 123.0)));
+        assert_eq!(int("1"), Ok(("", num!(1.0))));
+        assert_eq!(int("-1"), Ok(("", num!(-1.0))));
+        assert_eq!(int("1.0"), Ok((".0", num!(1.0))));
+        assert_eq!(int("123"), Ok(("", num!(123.0))));
+        assert_eq!(int("-123"), Ok(("", num!(-123.0))));
+        assert_eq!(int("123.0"), Ok((".0", num!(123.0))));
+        assert_eq!(int("123.123"), Ok((".123", num!(123.0))));
+        assert_eq!(int("123.123000000"), Ok((".123000000", num!(123.0))));
+        assert_eq!(int("123."), Ok((".", num!(123.0))));
+        assert_eq!(
+            int("-9223372036854775808"),
+            Ok(("", num!(-9223372036854775808)))
+        );
+        assert_eq!(
+            int("18446744073709551615"),
+            Ok(("", num!(18446744073709551615)))
+        );
         assert!(int("other").is_err());
+        assert!(matches!(
+            int("99999999999999999999999999999999"),
+            Err(nom::Err::Failure(_))
+        ));
     }
 
     #[test]
     fn test_float() {
         assert!(float("1").is_err());
-        assert_eq!(float("1.0"), Ok(("", 1.0)));
-        assert_eq!(float("-1.0"), Ok(("", -1.0)));
+        assert_eq!(float("1.0"), Ok(("", num!(1.0))));
+        assert_eq!(float("-1.0"), Ok(("", num!(-1.0))));
         assert!(float("123").is_err());
-        assert_eq!(float("123.0"), Ok(("", 123.0)));
-        assert_eq!(float("123.123"), Ok(("", 123.123)));
-        assert_eq!(float("-123.123"), Ok(("", -123.123)));
-        assert_eq!(float("123.123000000"), Ok(("", 123.123)));
+        assert_eq!(float("123.0"), Ok(("", num!(123.0))));
+        assert_eq!(float("123.123"), Ok(("", num!(123.123))));
+        assert_eq!(float("-123.123"), Ok(("", num!(-123.123))));
+        assert_eq!(float("123.123000000"), Ok(("", num!(123.123))));
         assert!(float("123.").is_err());
         assert!(float("other").is_err());
+        assert!(matches!(
+            float("99999999999999999999999999999999.0"),
+            Err(nom::Err::Failure(_))
+        ));
     }
 
     #[test]
     fn test_infinity() {
-        assert_eq!(infinity("-Inf"), Ok(("", f32::NEG_INFINITY)));
-        assert_eq!(infinity("Inf"), Ok(("", f32::INFINITY)));
+        assert_eq!(infinity("-Inf"), Ok(("", Num::NEG_INFINITY)));
+        assert_eq!(infinity("Inf"), Ok(("", Num::INFINITY)));
         assert!(infinity("other").is_err());
     }
 
     #[test]
     fn test_number() {
-        assert_eq!(number("1"), Ok(("", 1.0)));
-        assert_eq!(number("-1"), Ok(("", -1.0)));
-        assert_eq!(number("1.0"), Ok(("", 1.0)));
-        assert_eq!(number("-1.0"), Ok(("", -1.0)));
-        assert_eq!(number("123"), Ok(("", 123.0)));
-        assert_eq!(number("-123"), Ok(("", -123.0)));
-        assert_eq!(number("123.0"), Ok(("", 123.0)));
-        assert_eq!(number("-123.0"), Ok(("", -123.0)));
-        assert_eq!(number("123.123"), Ok(("", 123.123)));
-        assert_eq!(number("123.123000000"), Ok(("", 123.123)));
-        assert_eq!(number("-Inf"), Ok(("", f32::NEG_INFINITY)));
-        assert_eq!(number("Inf"), Ok(("", f32::INFINITY)));
+        assert_eq!(number("1"), Ok(("", num!(1.0))));
+        assert_eq!(number("-1"), Ok(("", num!(-1.0))));
+        assert_eq!(number("1.0"), Ok(("", num!(1.0))));
+        assert_eq!(number("-1.0"), Ok(("", num!(-1.0))));
+        assert_eq!(number("123"), Ok(("", num!(123.0))));
+        assert_eq!(number("-123"), Ok(("", num!(-123.0))));
+        assert_eq!(number("123.0"), Ok(("", num!(123.0))));
+        assert_eq!(number("-123.0"), Ok(("", num!(-123.0))));
+        assert_eq!(number("123.123"), Ok(("", num!(123.123))));
+        assert_eq!(number("123.123000000"), Ok(("", num!(123.123))));
+        assert_eq!(number("-Inf"), Ok(("", Num::NEG_INFINITY)));
+        assert_eq!(number("Inf"), Ok(("", Num::INFINITY)));
         assert!(number("123.").is_err());
         assert!(number("other").is_err());
     }
 
+    #[test]
+    fn test_number_or_constant() {
+        assert_eq!(
+            number_or_constant("12.5"),
+            Ok(("", Number::Literal(num!(12.5))))
+        );
+        assert_eq!(
+            number_or_constant("-Inf"),
+            Ok(("", Number::Literal(Num::NEG_INFINITY)))
+        );
+        assert_eq!(
+            number_or_constant("DISCOUNT_THRESHOLD)"),
+            Ok((")", Number::Constant("DISCOUNT_THRESHOLD")))
+        );
+        assert!(number_or_constant("true").is_err());
+        assert!(number_or_constant("-LIMIT").is_err());
+    }
+
     #[test]
     fn test_boolean() {
         assert_eq!(boolean("true"), Ok(("", true)));
@@ -251,6 +436,38 @@ mod tests {
         assert!(interval_op("other").is_err());
     }
 
+    #[test]
+    fn test_null_op() {
+        assert_eq!(null_op("is null"), Ok(("", true)));
+        assert_eq!(null_op("is not null"), Ok(("", false)));
+        assert!(null_op("is").is_err());
+    }
+
+    #[test]
+    fn test_char_class() {
+        assert_eq!(
+            char_class("\"[a-z0-9_]\" rest"),
+            Ok((
+                " rest",
+                CharClass {
+                    ranges: vec![('a', 'z'), ('0', '9'), ('_', '_')]
+                }
+            ))
+        );
+        assert_eq!(
+            char_class("\"[-.a-]\""),
+            Ok((
+                "",
+                CharClass {
+                    ranges: vec![('-', '-'), ('.', '.'), ('a', 'a'), ('-', '-')]
+                }
+            ))
+        );
+        assert!(char_class("\"[]\"").is_err());
+        assert!(char_class("\"[z-a]\"").is_err());
+        assert!(char_class("\"a-z\"").is_err());
+    }
+
     #[test]
     fn test_binary_op() {
         assert_eq!(binary_op("<="), Ok(("", BinaryOp::LessThanEqualTo)));
@@ -282,19 +499,94 @@ mod tests {
             interval("(12.0,Inf]"),
             Ok((
                 "",
-                MultiInterval::new(Boundary::Open, 12.0, f32::INFINITY, Boundary::Closed).unwrap()
+                MultiInterval::new(Boundary::Open, num!(12.0), Num::INFINITY, Boundary::Closed)
+                    .unwrap()
             ))
         );
         assert_eq!(
             interval("[   -43   ,    54   )   "),
             Ok((
                 "",
-                MultiInterval::new(Boundary::Closed, -43.0, 54.0, Boundary::Open).unwrap()
+                MultiInterval::new(Boundary::Closed, num!(-43.0), num!(54.0), Boundary::Open)
+                    .unwrap()
             ))
         );
         assert!(interval("other").is_err());
     }
 
+    #[test]
+    fn test_interval_node() {
+        assert_eq!(
+            interval_node("[MIN_AGE, 130)"),
+            Ok((
+                "",
+                IntervalNode {
+                    lo_boundary: Boundary::Closed,
+                    lo: Number::Constant("MIN_AGE"),
+                    hi: Number::Literal(num!(130.0)),
+                    hi_boundary: Boundary::Open,
+                }
+            ))
+        );
+        assert_eq!(
+            interval_node("( -1 ,MAX ]  asd"),
+            Ok((
+                "asd",
+                IntervalNode {
+                    lo_boundary: Boundary::Open,
+                    lo: Number::Literal(num!(-1.0)),
+                    hi: Number::Constant("MAX"),
+                    hi_boundary: Boundary::Closed,
+                }
+            ))
+        );
+        assert!(interval_node("[10, 0]").is_err());
+        assert!(interval_node("[MIN, ]").is_err());
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(
+            string_literal("\"free delivery\" {"),
+            Ok((" {", "free delivery"))
+        );
+        assert_eq!(string_literal("\"\""), Ok(("", "")));
+        assert!(string_literal("\"free delivery").is_err());
+        assert!(string_literal("free delivery").is_err());
+    }
+
+    #[test]
+    fn test_member_set() {
+        assert_eq!(member_set("{gold}"), Ok(("", vec!["gold"])));
+        assert_eq!(
+            member_set("{ bronze,silver ,  gold }  asd"),
+            Ok(("  asd", vec!["bronze", "silver", "gold"]))
+        );
+        assert!(member_set("{}").is_err());
+        assert!(member_set("{gold,}").is_err());
+        assert!(member_set("{true}").is_err());
+        assert!(member_set("gold").is_err());
+    }
+
+    #[test]
+    fn test_value_set() {
+        assert_eq!(
+            value_set("{3, -7,MAX, 1.5}  asd"),
+            Ok((
+                "  asd",
+                vec![
+                    Number::Literal(num!(3.0)),
+                    Number::Literal(num!(-7.0)),
+                    Number::Constant("MAX"),
+                    Number::Literal(num!(1.5))
+                ]
+            ))
+        );
+        assert!(value_set("{}").is_err());
+        assert!(value_set("{3,}").is_err());
+        assert!(value_set("{MIN, MAX}").is_err());
+    }
+
     #[test]
     fn test_parse_alphabetic() {
         assert_eq!(parse_alphabetic("abc"), Ok(("bc", 'a')));
@@ -317,4 +609,17 @@ mod tests {
         assert!(var_name("true").is_err());
         assert!(var_name("false").is_err());
     }
+
+    #[test]
+    fn test_var_name_path() {
+        assert_eq!(var_name("order.price"), Ok(("", "order.price")));
+        assert_eq!(
+            var_name("order.customer.age >"),
+            Ok((" >", "order.customer.age"))
+        );
+        assert_eq!(var_name("order.5"), Ok((".5", "order")));
+        assert_eq!(var_name("order.if"), Ok((".if", "order")));
+        assert!(var_name(".price").is_err());
+        assert_eq!(identifier("order.price"), Ok((".price", "order")));
+    }
 }
@@ -0,0 +1,399 @@
+use nom::{
+    branch::alt,
+    combinator::map,
+    error::{ErrorKind, VerboseError, VerboseErrorKind},
+    sequence::terminated,
+    Err,
+};
+
+use super::{
+    assumption::assumption,
+    ast::{
+        ConditionsNode, ConstNode, ErrorNode, FeatureNode, IfNode, LetNode, RootNode, SwitchNode,
+        TemplateNode, VarNode,
+    },
+    const_declaration::const_declaration,
+    if_statement::{if_statement, requirements},
+    import::import,
+    let_binding::let_binding,
+    switch_statement::switch_statement,
+    template::{template, use_template},
+    utils::{token, token_lit, whitespace},
+    var_declaration::var_declaration,
+    IResult,
+};
+
+enum Item<'a> {
+    Import(&'a str),
+    Const(ConstNode<'a>),
+    Var(VarNode<'a>),
+    If(IfNode<'a>),
+    Switch(SwitchNode<'a>),
+    Assumption(ConditionsNode<'a>),
+    Let(LetNode<'a>),
+    /// `[`, with the requirements of the feature before it
+    FeatureStart(Vec<&'a str>),
+    FeatureEnd,
+    Template(TemplateNode<'a>),
+    /// A feature instantiating a template
+    Use(FeatureNode<'a>),
+}
+
+fn item(input: &str) -> IResult<Item> {
+    alt((
+        map(token(import), Item::Import),
+        map(token(const_declaration), Item::Const),
+        map(token(var_declaration), Item::Var),
+        map(token(if_statement), Item::If),
+        map(token(switch_statement), Item::Switch),
+        map(token(assumption), Item::Assumption),
+        map(token(let_binding), Item::Let),
+        map(token(template), Item::Template),
+        map(token(use_template), Item::Use),
+        map(terminated(requirements, token_lit("[")), Item::FeatureStart),
+        map(token_lit("]"), |_| Item::FeatureEnd),
+    ))(input)
+}
+
+/// Where the parsing is in the file
+enum State<'a> {
+    /// Before the first feature, where the shared declarations are
+    Declarations,
+    /// In a feature between brackets
+    Feature(FeatureNode<'a>),
+    /// Between two features in brackets
+    BetweenFeatures,
+    /// In a single feature without the brackets, which lasts until the end of the file
+    FeatureWithoutBrackets(FeatureNode<'a>),
+}
+
+const fn new_feature(requirements: Vec<&str>) -> FeatureNode {
+    FeatureNode {
+        requirements,
+        constants: Vec::new(),
+        variables: Vec::new(),
+        if_statements: Vec::new(),
+        switch_statements: Vec::new(),
+        assumptions: Vec::new(),
+        bindings: Vec::new(),
+        errors: Vec::new(),
+        instance: None,
+    }
+}
+
+fn unexpected(input: &str) -> VerboseError<&str> {
+    VerboseError {
+        errors: vec![(input, VerboseErrorKind::Nom(ErrorKind::Tag))],
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether a statement or a feature can start or end at the position.
+/// Brackets only count at the start of a line, so the brackets of intervals are skipped.
+fn is_statement_start(input: &str, position: usize) -> bool {
+    let (before, after) = input.split_at(position);
+
+    let is_keyword = [
+        "import", "const", "var", "let", "if", "switch", "assume", "template", "use", "@req",
+    ]
+    .iter()
+    .any(|keyword| {
+        after.starts_with(keyword)
+            && !before.ends_with(is_word_char)
+            && !after[keyword.len()..].starts_with(is_word_char)
+    });
+    let is_bracket = (after.starts_with('[') || after.starts_with(']'))
+        && before
+            .rsplit_once('\n')
+            .map_or(before, |(_, line)| line)
+            .trim()
+            .is_empty();
+
+    is_keyword || is_bracket
+}
+
+/// Skips at least one character, until the next statement or the end of the input
+fn skip_to_next_statement(input: &str, from: usize) -> usize {
+    input[from..]
+        .char_indices()
+        .skip(1)
+        .map(|(i, _)| from + i)
+        .find(|position| is_statement_start(input, *position))
+        .unwrap_or(input.len())
+}
+
+/// Parses the file like `root`, but after a syntax error it skips to the next statement and carries on.
+/// The syntax errors are collected as error nodes in the feature they are in or in the root.
+pub fn root_recovering(input: &str) -> RootNode {
+    let mut root = RootNode {
+        imports: Vec::new(),
+        constants: Vec::new(),
+        variables: Vec::new(),
+        assumptions: Vec::new(),
+        templates: Vec::new(),
+        features: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut state = State::Declarations;
+
+    let mut input = match whitespace(input) {
+        Ok((input, _)) => input,
+        Err(Err::Error(error) | Err::Failure(error)) => {
+            root.errors.push(ErrorNode {
+                skipped: input,
+                error,
+            });
+            ""
+        }
+        Err(Err::Incomplete(_)) => unreachable!("The parsers only parse complete inputs"),
+    };
+
+    while !input.is_empty() {
+        let error = match item(input) {
+            Ok((rest, item)) => {
+                let start = input;
+                input = rest;
+
+                match (item, &mut state) {
+                    (Item::Import(import), State::Declarations)
+                        if root.constants.is_empty() && root.variables.is_empty() =>
+                    {
+                        root.imports.push(import);
+                        None
+                    }
+                    (Item::Const(const_node), State::Declarations) => {
+                        root.constants.push(const_node);
+                        None
+                    }
+                    (Item::Var(var_node), State::Declarations) => {
+                        root.variables.push(var_node);
+                        None
+                    }
+                    (Item::Assumption(conditions), State::Declarations) => {
+                        root.assumptions.push(conditions);
+                        None
+                    }
+                    (Item::Assumption(conditions), State::Feature(feature))
+                    | (Item::Assumption(conditions), State::FeatureWithoutBrackets(feature)) => {
+                        feature.assumptions.push(conditions);
+                        None
+                    }
+                    (Item::Let(let_node), State::Feature(feature))
+                    | (Item::Let(let_node), State::FeatureWithoutBrackets(feature)) => {
+                        feature.bindings.push(let_node);
+                        None
+                    }
+                    (Item::Let(let_node), State::Declarations) => {
+                        let mut feature = new_feature(Vec::new());
+                        feature.bindings.push(let_node);
+                        state = State::FeatureWithoutBrackets(feature);
+                        None
+                    }
+                    (Item::Const(const_node), State::Feature(feature))
+                    | (Item::Const(const_node), State::FeatureWithoutBrackets(feature)) => {
+                        feature.constants.push(const_node);
+                        None
+                    }
+                    (Item::Var(var_node), State::Feature(feature))
+                    | (Item::Var(var_node), State::FeatureWithoutBrackets(feature)) => {
+                        feature.variables.push(var_node);
+                        None
+                    }
+                    (Item::If(if_node), State::Feature(feature))
+                    | (Item::If(if_node), State::FeatureWithoutBrackets(feature)) => {
+                        feature.if_statements.push(if_node);
+                        None
+                    }
+                    (Item::If(if_node), State::Declarations) => {
+                        let mut feature = new_feature(Vec::new());
+                        feature.if_statements.push(if_node);
+                        state = State::FeatureWithoutBrackets(feature);
+                        None
+                    }
+                    (Item::Switch(switch_node), State::Feature(feature))
+                    | (Item::Switch(switch_node), State::FeatureWithoutBrackets(feature)) => {
+                        feature.switch_statements.push(switch_node);
+                        None
+                    }
+                    (Item::Switch(switch_node), State::Declarations) => {
+                        let mut feature = new_feature(Vec::new());
+                        feature.switch_statements.push(switch_node);
+                        state = State::FeatureWithoutBrackets(feature);
+                        None
+                    }
+                    (
+                        Item::FeatureStart(requirements),
+                        State::Declarations | State::BetweenFeatures,
+                    ) => {
+                        state = State::Feature(new_feature(requirements));
+                        None
+                    }
+                    (
+                        Item::Template(template_node),
+                        State::Declarations | State::BetweenFeatures,
+                    ) => {
+                        root.templates.push(template_node);
+                        state = State::BetweenFeatures;
+                        None
+                    }
+                    (Item::Use(feature), State::Declarations | State::BetweenFeatures) => {
+                        root.features.push(feature);
+                        state = State::BetweenFeatures;
+                        None
+                    }
+                    (Item::FeatureEnd, State::Feature(feature))
+                        if !(feature.constants.is_empty()
+                            && feature.variables.is_empty()
+                            && feature.if_statements.is_empty()
+                            && feature.switch_statements.is_empty()
+                            && feature.assumptions.is_empty()
+                            && feature.bindings.is_empty()
+                            && feature.errors.is_empty()) =>
+                    {
+                        if let State::Feature(feature) =
+                            std::mem::replace(&mut state, State::BetweenFeatures)
+                        {
+                            root.features.push(feature);
+                        }
+                        None
+                    }
+                    _ => Some((start, unexpected(start))),
+                }
+            }
+            Err(Err::Error(error) | Err::Failure(error)) => Some((input, error)),
+            Err(Err::Incomplete(_)) => unreachable!("The parsers only parse complete inputs"),
+        };
+
+        if let Some((start, error)) = error {
+            let error_position = error
+                .errors
+                .iter()
+                .find(|(_, kind)| !matches!(kind, VerboseErrorKind::Context(_)))
+                .map_or(0, |(remaining, _)| start.len() - remaining.len());
+            // The statement with the error can end right before the next one, like a feature without its `]`
+            let end = if error_position > 0 && is_statement_start(start, error_position) {
+                error_position
+            } else {
+                skip_to_next_statement(start, error_position)
+            };
+            let error_node = ErrorNode {
+                skipped: &start[..end],
+                error,
+            };
+
+            match &mut state {
+                State::Feature(feature) | State::FeatureWithoutBrackets(feature) => {
+                    feature.errors.push(error_node);
+                }
+                State::Declarations | State::BetweenFeatures => root.errors.push(error_node),
+            }
+
+            input = whitespace(&start[end..]).map_or("", |(rest, _)| rest);
+        }
+    }
+
+    match state {
+        State::Feature(mut feature) => {
+            feature.errors.push(ErrorNode {
+                skipped: input,
+                error: VerboseError {
+                    errors: vec![(input, VerboseErrorKind::Char(']'))],
+                },
+            });
+            root.features.push(feature);
+        }
+        State::FeatureWithoutBrackets(feature) => root.features.push(feature),
+        State::Declarations | State::BetweenFeatures => {}
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{diagnostic::Diagnostic, parser::root};
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::declarations_only("import \"common.gpt\"\nconst LIMIT = 10\nvar x: int")]
+    #[case::without_brackets("var x: int\nif(x > 5)\nvar y: bool\nif(y == true) { if(x < 2) }")]
+    #[case::with_brackets(
+        "const LIMIT = 10\nvar x: int\n[\nvar y: bool\nif(y == true && x in [0, 5])\n]\n[ if(x > LIMIT) ]"
+    )]
+    #[case::templates(
+        "var x: int\n@req(\"R1\") template a(limit) [ if(x > limit) ]\nuse a(5)\n[ if(x < 2) ]\n@req(\"R2\") use a(LIMIT)"
+    )]
+    #[case::assumptions("var x: int\nassume(x >= 0)\n[ var y: int\nassume(y <= x)\nif(y > 5) ]")]
+    #[case::bindings(
+        "var x: int\n[ let small = x < 5\nif(small) ]\n[ let big = x > 10\nlet medium = !big && x > 5\nif(medium || big) ]"
+    )]
+    #[case::bindings_without_brackets("var x: int\nlet small = x < 5\nif(small)")]
+    fn test_root_recovering_same_as_root(#[case] input: &str) {
+        assert_eq!(root_recovering(input), root(input).unwrap().1);
+    }
+
+    fn error_lines(input: &str, root_node: &RootNode) -> Vec<usize> {
+        root_node
+            .errors
+            .iter()
+            .chain(
+                root_node
+                    .features
+                    .iter()
+                    .flat_map(|feature| &feature.errors),
+            )
+            .map(|error_node| Diagnostic::from_parse_error("", input, &error_node.error).line)
+            .collect()
+    }
+
+    #[test]
+    fn test_root_recovering_without_brackets() {
+        let input =
+            "var x: int\nvar y: in\nif(x > 5)\nif(x >)\nif(x < 2) {\n  if(y ==)\n}\nif(x == 3)";
+        let root_node = root_recovering(input);
+
+        assert_eq!(
+            root_node
+                .variables
+                .iter()
+                .map(|var_node| var_node.var_name)
+                .collect::<Vec<_>>(),
+            vec!["x"]
+        );
+        assert_eq!(root_node.features.len(), 1);
+        assert_eq!(root_node.features[0].if_statements.len(), 2);
+        assert_eq!(error_lines(input, &root_node), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn test_root_recovering_with_brackets() {
+        let input = "var x: int\n[\nif(x in [0, 5)\n]\n[\nif(x > 5)\n]\nif(x < 2)\n[\nif(x > 2)";
+        let root_node = root_recovering(input);
+
+        assert_eq!(
+            root_node
+                .features
+                .iter()
+                .map(|feature| (feature.if_statements.len(), feature.errors.len()))
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+        assert_eq!(error_lines(input, &root_node), vec![8, 4, 10]);
+    }
+
+    #[rstest]
+    #[case::enum_members("var t: enum {a", vec![1])]
+    #[case::interval("var x: int\nif(x in [1", vec![2])]
+    #[case::member_set("var x: int\nif(x in {1", vec![2])]
+    #[case::outcome("var p: int\nif(p >= 200) =>", vec![2])]
+    fn test_root_recovering_truncated_input(#[case] input: &str, #[case] expected: Vec<usize>) {
+        assert_eq!(error_lines(input, &root_recovering(input)), expected);
+    }
+}
@@ -0,0 +1,170 @@
+use nom::{
+    branch::alt,
+    combinator::{consumed, cut, map, opt},
+    error::context,
+    multi::{many0, separated_list1},
+};
+
+use super::{
+    ast::{CaseNode, CaseValue, ElseNode, SwitchNode},
+    if_statement::{if_statement, outcome},
+    primitives::{boolean, interval_node, number, var_name},
+    utils::{token, token_lit},
+    IResult,
+};
+
+fn case_value(input: &str) -> IResult<CaseValue> {
+    context(
+        "case value",
+        alt((
+            map(interval_node, CaseValue::Interval),
+            map(number, CaseValue::Number),
+            map(boolean, CaseValue::Bool),
+            map(var_name, CaseValue::Name),
+        )),
+    )(input)
+}
+
+/// Parses a case with its values, like `case [0, 10000), 20000 => tax = 0:` and the if statements after it
+fn case(input: &str) -> IResult<CaseNode> {
+    context("case", |input| {
+        let (input, _) = token_lit("case")(input)?;
+        cut(|input| {
+            let (input, (label, values)) =
+                consumed(separated_list1(token_lit(","), token(case_value)))(input)?;
+            let (input, outcome) = opt(outcome)(input)?;
+            let (input, _) = token_lit(":")(input)?;
+            let (input, body) = many0(if_statement)(input)?;
+
+            Ok((
+                input,
+                CaseNode {
+                    label: label.trim_end(),
+                    values,
+                    outcome,
+                    body,
+                },
+            ))
+        })(input)
+    })(input)
+}
+
+fn default(input: &str) -> IResult<ElseNode> {
+    context("default", |input| {
+        let (input, _) = token_lit("default")(input)?;
+        cut(|input| {
+            let (input, outcome) = opt(outcome)(input)?;
+            let (input, _) = token_lit(":")(input)?;
+            let (input, body) = many0(if_statement)(input)?;
+
+            Ok((
+                input,
+                ElseNode {
+                    requirements: Vec::new(),
+                    outcome,
+                    body,
+                },
+            ))
+        })(input)
+    })(input)
+}
+
+/// Parses a switch on one variable, like `switch(income) { case [0, 10000): ... default: ... }`
+pub fn switch_statement(input: &str) -> IResult<SwitchNode> {
+    context("switch statement", |input| {
+        let (input, _) = token_lit("switch")(input)?;
+        cut(|input| {
+            let (input, _) = token_lit("(")(input)?;
+            let (input, var_name) = token(var_name)(input)?;
+            let (input, _) = token_lit(")")(input)?;
+            let (input, _) = token_lit("{")(input)?;
+            let (input, cases) = many0(case)(input)?;
+            let (input, default) = opt(default)(input)?;
+            let (input, _) = token_lit("}")(input)?;
+
+            Ok((
+                input,
+                SwitchNode {
+                    var_name,
+                    cases,
+                    default,
+                },
+            ))
+        })(input)
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        interval::Boundary,
+        number::num,
+        parser::ast::{IntervalNode, Number, Outcome},
+    };
+
+    #[test]
+    fn test_switch_statement() {
+        assert_eq!(
+            switch_statement(
+                "switch (income) {
+                    case [0, LIMIT) => tax = 0:
+                    case 50000, -1:
+                        if (age > 65)
+                    default:
+                } rest"
+            ),
+            Ok((
+                "rest",
+                SwitchNode {
+                    var_name: "income",
+                    cases: vec![
+                        CaseNode {
+                            label: "[0, LIMIT)",
+                            values: vec![CaseValue::Interval(IntervalNode {
+                                lo_boundary: Boundary::Closed,
+                                lo: Number::Literal(num!(0.0)),
+                                hi: Number::Constant("LIMIT"),
+                                hi_boundary: Boundary::Open,
+                            })],
+                            outcome: Some(Outcome::Assignment {
+                                var_name: "tax",
+                                value: "0"
+                            }),
+                            body: vec![],
+                        },
+                        CaseNode {
+                            label: "50000, -1",
+                            values: vec![
+                                CaseValue::Number(num!(50000.0)),
+                                CaseValue::Number(num!(-1.0))
+                            ],
+                            outcome: None,
+                            body: vec![if_statement("if (age > 65)").unwrap().1],
+                        },
+                    ],
+                    default: Some(ElseNode {
+                        requirements: vec![],
+                        outcome: None,
+                        body: vec![]
+                    }),
+                }
+            ))
+        );
+        assert_eq!(
+            switch_statement("switch(x){case true:case gold:}")
+                .unwrap()
+                .1
+                .cases
+                .iter()
+                .map(|case| case.values.clone())
+                .collect::<Vec<_>>(),
+            vec![vec![CaseValue::Bool(true)], vec![CaseValue::Name("gold")]]
+        );
+        assert!(switch_statement("switch(x) { case: }").is_err());
+        assert!(switch_statement("switch(x) { case 5 }").is_err());
+        assert!(switch_statement("switch(x) { default: case 5: }").is_err());
+    }
+}
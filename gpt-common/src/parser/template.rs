@@ -0,0 +1,204 @@
+use nom::{combinator::cut, error::context, multi::separated_list0};
+
+use super::{
+    ast::{ConstNode, FeatureNode, RootNode, TemplateNode, UseNode},
+    feature::feature_body,
+    if_statement::requirements,
+    primitives::{identifier, number_or_constant},
+    utils::{parenthesized, token, token_lit},
+    IResult,
+};
+
+/// Parses a feature with parameters, like `template discount(threshold) [ ... ]`
+pub fn template(input: &str) -> IResult<TemplateNode> {
+    context("template", |input| {
+        let (input, requirements) = requirements(input)?;
+        let (input, _) = token_lit("template")(input)?;
+        cut(move |input| {
+            let (input, name) = token(identifier)(input)?;
+            let (input, params) =
+                parenthesized(separated_list0(token_lit(","), token(identifier)))(input)?;
+            let (input, _) = token_lit("[")(input)?;
+            let (input, feature_node) = feature_body(input)?;
+            let (input, _) = token_lit("]")(input)?;
+
+            Ok((
+                input,
+                TemplateNode {
+                    name,
+                    params,
+                    feature: FeatureNode {
+                        requirements: requirements.clone(),
+                        ..feature_node
+                    },
+                },
+            ))
+        })(input)
+    })(input)
+}
+
+/// Parses an instantiation of a template, like `@req("R1") use discount(200)`,
+/// as a feature whose body is filled in by `expand_templates`
+pub fn use_template(input: &str) -> IResult<FeatureNode> {
+    context("use", |input| {
+        let (input, requirements) = requirements(input)?;
+        let (input, _) = token_lit("use")(input)?;
+        cut(move |input| {
+            let (input, template) = token(identifier)(input)?;
+            let (input, args) =
+                parenthesized(separated_list0(token_lit(","), token(number_or_constant)))(input)?;
+
+            Ok((
+                input,
+                FeatureNode {
+                    requirements: requirements.clone(),
+                    constants: Vec::new(),
+                    variables: Vec::new(),
+                    if_statements: Vec::new(),
+                    switch_statements: Vec::new(),
+                    assumptions: Vec::new(),
+                    bindings: Vec::new(),
+                    errors: Vec::new(),
+                    instance: Some(UseNode { template, args }),
+                },
+            ))
+        })(input)
+    })(input)
+}
+
+/// Fills in the features using a template with the body of the template,
+/// where each parameter becomes a constant with the value of its argument.
+/// A use of an unknown template or with the wrong number of arguments stays empty, and the checker reports it.
+pub fn expand_templates(root: &mut RootNode) {
+    for feature in &mut root.features {
+        let Some(instance) = &feature.instance else {
+            continue;
+        };
+        let Some(template) = root
+            .templates
+            .iter()
+            .find(|template| template.name == instance.template)
+        else {
+            continue;
+        };
+        if template.params.len() != instance.args.len() {
+            continue;
+        }
+
+        let body = template.feature.clone();
+        feature.constants = template
+            .params
+            .iter()
+            .zip(&instance.args)
+            .map(|(name, value)| ConstNode {
+                name,
+                value: *value,
+            })
+            .chain(body.constants)
+            .collect();
+        feature.requirements.extend(body.requirements);
+        feature.variables = body.variables;
+        feature.if_statements = body.if_statements;
+        feature.switch_statements = body.switch_statements;
+        feature.assumptions = body.assumptions;
+        feature.bindings = body.bindings;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        number::num,
+        parser::{ast::Number, root},
+    };
+
+    #[test]
+    fn test_template() {
+        let (rest, template_node) = template(
+            "template discount(threshold, rate) [ var price: num if(price >= threshold) ] use",
+        )
+        .unwrap();
+
+        assert_eq!(rest, "use");
+        assert_eq!(template_node.name, "discount");
+        assert_eq!(template_node.params, vec!["threshold", "rate"]);
+        assert_eq!(template_node.feature.variables.len(), 1);
+        assert_eq!(template_node.feature.if_statements.len(), 1);
+        assert!(template("template discount [ var price: num ]").is_err());
+        assert!(template("template discount(threshold) [ ]").is_err());
+    }
+
+    #[test]
+    fn test_use_template() {
+        assert_eq!(
+            use_template("@req(\"R1\") use discount(200, LIMIT)"),
+            Ok((
+                "",
+                FeatureNode {
+                    requirements: vec!["R1"],
+                    constants: Vec::new(),
+                    variables: Vec::new(),
+                    if_statements: Vec::new(),
+                    switch_statements: Vec::new(),
+                    assumptions: Vec::new(),
+                    errors: Vec::new(),
+                    bindings: Vec::new(),
+                    instance: Some(UseNode {
+                        template: "discount",
+                        args: vec![Number::Literal(num!(200)), Number::Constant("LIMIT")],
+                    }),
+                }
+            ))
+        );
+        assert!(use_template("use discount").is_err());
+        assert!(use_template("use (200)").is_err());
+    }
+
+    #[test]
+    fn test_expand_templates() {
+        let (_, mut root_node) = root(
+            "@req(\"R1\") template discount(threshold) [ var price: num if(price >= threshold) ]
+            use discount(200)
+            @req(\"R2\") use discount(150)
+            use shipping(10)",
+        )
+        .unwrap();
+        expand_templates(&mut root_node);
+
+        assert_eq!(
+            root_node
+                .features
+                .iter()
+                .map(|feature| (
+                    feature.instance.as_ref().unwrap().to_string(),
+                    feature.requirements.clone(),
+                    feature
+                        .constants
+                        .iter()
+                        .map(|const_node| (const_node.name, const_node.value))
+                        .collect::<Vec<_>>(),
+                    feature.if_statements.len(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "discount(200)".to_string(),
+                    vec!["R1"],
+                    vec![("threshold", Number::Literal(num!(200)))],
+                    1
+                ),
+                (
+                    "discount(150)".to_string(),
+                    vec!["R2", "R1"],
+                    vec![("threshold", Number::Literal(num!(150)))],
+                    1
+                ),
+                ("shipping(10)".to_string(), vec![], vec![], 0),
+            ]
+        );
+        assert_eq!(root_node.features[1].variables[0].var_name, "price");
+    }
+}
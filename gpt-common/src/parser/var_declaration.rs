@@ -1,15 +1,19 @@
 use nom::{
     branch::alt,
-    combinator::{complete, cut},
+    character::complete::alphanumeric1,
+    combinator::{complete, cut, map, map_opt, opt},
     error::context,
+    multi::separated_list1,
+    sequence::{preceded, terminated, tuple},
 };
 
 use super::{
-    ast::{Type, VarNode},
-    primitives::{float, var_name},
+    ast::{IntegerSize, Type, VarNode},
+    primitives::{float, identifier, interval_node, member_set},
     utils::{token, token_lit},
     IResult,
 };
+use crate::number::Num;
 
 fn parse_float_type(input: &str) -> IResult<Type> {
     let (input, _) = token_lit("num")(input)?;
@@ -27,17 +31,50 @@ fn parse_bool_type(input: &str) -> IResult<Type> {
 
     Ok((input, Type::Bool))
 }
+fn parse_string_type(input: &str) -> IResult<Type> {
+    let (input, _) = token_lit("string")(input)?;
+
+    Ok((input, Type::String))
+}
+
 fn parse_int_type(input: &str) -> IResult<Type> {
     let (input, _) = token_lit("int")(input)?;
 
-    Ok((input, Type::Integer))
+    Ok((input, Type::Integer { size: None }))
+}
+
+fn parse_sized_int_type(input: &str) -> IResult<Type> {
+    let (input, size) = token(map_opt(alphanumeric1, |name| {
+        IntegerSize::ALL
+            .into_iter()
+            .find(|size| size.name() == name)
+    }))(input)?;
+
+    Ok((input, Type::Integer { size: Some(size) }))
 }
 
 fn parse_simple_num_type(input: &str) -> IResult<Type> {
     let (input, _) = token_lit("num")(input)?;
 
     // TODO: This should be a default num precision somewhere
-    Ok((input, Type::Float { precision: 0.01 }))
+    Ok((
+        input,
+        Type::Float {
+            precision: Num::new(1, 2),
+        },
+    ))
+}
+
+fn parse_enum_type(input: &str) -> IResult<Type> {
+    let (input, _) = token_lit("enum")(input)?;
+    cut(|input| {
+        let (input, members) = token(member_set)(input)?;
+
+        Ok((
+            input,
+            Type::Enum(members.into_iter().map(str::to_owned).collect()),
+        ))
+    })(input)
 }
 
 fn parse_type(input: &str) -> IResult<Type> {
@@ -45,28 +82,71 @@ fn parse_type(input: &str) -> IResult<Type> {
         "type",
         alt((
             parse_bool_type,
+            parse_string_type,
             parse_int_type,
+            parse_sized_int_type,
+            parse_enum_type,
             complete(parse_float_type),
             parse_simple_num_type,
         )),
     )(input)
 }
 
+/// Parses the fields of a record type, like `{ price: num(0.1), prepaid: bool }`
+fn parse_record_fields(input: &str) -> IResult<Vec<VarNode>> {
+    let (input, _) = token_lit("{")(input)?;
+    cut(terminated(
+        separated_list1(token_lit(","), typed_name),
+        token_lit("}"),
+    ))(input)
+}
+
+/// Parses a name with its type and domain, like `age: int in [0, 130]` or `coupon: int?`, which is a variable or a field of a record
+fn typed_name(input: &str) -> IResult<VarNode> {
+    let (input, var_name) = token(identifier)(input)?;
+    let (input, _) = token_lit(":")(input)?;
+    let (input, (var_type, nullable, fields)) = alt((
+        map(parse_record_fields, |fields| (Type::Record, false, fields)),
+        map(
+            tuple((token(parse_type), opt(token_lit("?")))),
+            |(var_type, nullable)| (var_type, nullable.is_some(), Vec::new()),
+        ),
+    ))(input)?;
+    let (input, domain) = opt(preceded(
+        token_lit("in"),
+        cut(context("domain", token(interval_node))),
+    ))(input)?;
+
+    Ok((
+        input,
+        VarNode {
+            var_name,
+            var_type,
+            nullable,
+            domain,
+            fields,
+        },
+    ))
+}
+
 pub fn var_declaration(input: &str) -> IResult<VarNode> {
     context("var declaration", |input| {
         let (input, _) = token_lit("var")(input)?;
-        cut(|input| {
-            let (input, var_name) = token(var_name)(input)?;
-            let (input, _) = token_lit(":")(input)?;
-            let (input, var_type) = token(parse_type)(input)?;
-
-            Ok((input, VarNode { var_name, var_type }))
-        })(input)
+        cut(typed_name)(input)
     })(input)
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        interval::Boundary,
+        number::num,
+        parser::ast::{IntervalNode, Number},
+    };
+
     #[test]
     #[ignore = "todo"]
     fn test_parse_float_type() {
@@ -85,12 +165,65 @@ mod tests {
         todo!("Write tests for parse_int_type");
     }
 
+    #[test]
+    fn test_parse_sized_int_type() {
+        assert_eq!(
+            parse_sized_int_type("u8 in"),
+            Ok((
+                "in",
+                Type::Integer {
+                    size: Some(IntegerSize::U8)
+                }
+            ))
+        );
+        assert_eq!(
+            parse_sized_int_type("i64"),
+            Ok((
+                "",
+                Type::Integer {
+                    size: Some(IntegerSize::I64)
+                }
+            ))
+        );
+        assert!(parse_sized_int_type("u7").is_err());
+        assert!(parse_sized_int_type("u80").is_err());
+        assert!(parse_sized_int_type("int").is_err());
+    }
+
     #[test]
     #[ignore = "todo"]
     fn test_parse_simple_num_type() {
         todo!("Write tests for parse_simple_num_type");
     }
 
+    #[test]
+    fn test_parse_enum_type() {
+        assert_eq!(
+            parse_enum_type("enum { bronze, silver, gold }"),
+            Ok((
+                "",
+                Type::Enum(vec![
+                    "bronze".to_owned(),
+                    "silver".to_owned(),
+                    "gold".to_owned()
+                ])
+            ))
+        );
+        assert_eq!(
+            parse_enum_type("enum{card} asd"),
+            Ok(("asd", Type::Enum(vec!["card".to_owned()])))
+        );
+        assert!(parse_enum_type("enum").is_err());
+        assert!(parse_enum_type("enum {}").is_err());
+        assert!(parse_enum_type("num").is_err());
+    }
+
+    #[test]
+    fn test_parse_string_type() {
+        assert_eq!(parse_string_type("string?"), Ok(("?", Type::String)));
+        assert!(parse_string_type("str").is_err());
+    }
+
     #[test]
     #[ignore = "todo"]
     fn test_parse_type() {
@@ -98,8 +231,139 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "todo"]
     fn test_var_declaration() {
-        todo!("Write tests for var_declaration");
+        assert_eq!(
+            var_declaration("var x: bool"),
+            Ok((
+                "",
+                VarNode {
+                    var_name: "x",
+                    var_type: Type::Bool,
+                    nullable: false,
+                    domain: None,
+                    fields: Vec::new()
+                }
+            ))
+        );
+        assert_eq!(
+            var_declaration("var age: int in [0, 130] if"),
+            Ok((
+                "if",
+                VarNode {
+                    var_name: "age",
+                    var_type: Type::Integer { size: None },
+                    nullable: false,
+                    domain: Some(IntervalNode {
+                        lo_boundary: Boundary::Closed,
+                        lo: Number::Literal(num!(0.0)),
+                        hi: Number::Literal(num!(130.0)),
+                        hi_boundary: Boundary::Closed
+                    }),
+                    fields: Vec::new()
+                }
+            ))
+        );
+        assert_eq!(
+            var_declaration("var price: num(0.1) in (0, MAX_PRICE]"),
+            Ok((
+                "",
+                VarNode {
+                    var_name: "price",
+                    var_type: Type::Float {
+                        precision: num!(0.1)
+                    },
+                    nullable: false,
+                    domain: Some(IntervalNode {
+                        lo_boundary: Boundary::Open,
+                        lo: Number::Literal(num!(0.0)),
+                        hi: Number::Constant("MAX_PRICE"),
+                        hi_boundary: Boundary::Closed
+                    }),
+                    fields: Vec::new()
+                }
+            ))
+        );
+        assert_eq!(
+            var_declaration("var level: u8"),
+            Ok((
+                "",
+                VarNode {
+                    var_name: "level",
+                    var_type: Type::Integer {
+                        size: Some(IntegerSize::U8)
+                    },
+                    nullable: false,
+                    domain: None,
+                    fields: Vec::new()
+                }
+            ))
+        );
+        assert_eq!(
+            var_declaration("var order: { price: num(0.1) in [0, 1000], customer: { vip: bool } }"),
+            Ok((
+                "",
+                VarNode {
+                    var_name: "order",
+                    var_type: Type::Record,
+                    nullable: false,
+                    domain: None,
+                    fields: vec![
+                        VarNode {
+                            var_name: "price",
+                            var_type: Type::Float {
+                                precision: num!(0.1)
+                            },
+                            nullable: false,
+                            domain: Some(IntervalNode {
+                                lo_boundary: Boundary::Closed,
+                                lo: Number::Literal(num!(0)),
+                                hi: Number::Literal(num!(1000)),
+                                hi_boundary: Boundary::Closed
+                            }),
+                            fields: Vec::new()
+                        },
+                        VarNode {
+                            var_name: "customer",
+                            var_type: Type::Record,
+                            nullable: false,
+                            domain: None,
+                            fields: vec![VarNode {
+                                var_name: "vip",
+                                var_type: Type::Bool,
+                                nullable: false,
+                                domain: None,
+                                fields: Vec::new()
+                            }]
+                        }
+                    ]
+                }
+            ))
+        );
+        assert_eq!(
+            var_declaration("var coupon: num(0.1)? in [0, 100]"),
+            Ok((
+                "",
+                VarNode {
+                    var_name: "coupon",
+                    var_type: Type::Float {
+                        precision: num!(0.1)
+                    },
+                    nullable: true,
+                    domain: Some(IntervalNode {
+                        lo_boundary: Boundary::Closed,
+                        lo: Number::Literal(num!(0)),
+                        hi: Number::Literal(num!(100)),
+                        hi_boundary: Boundary::Closed
+                    }),
+                    fields: Vec::new()
+                }
+            ))
+        );
+        assert!(var_declaration("var order: {}").is_err());
+        assert!(var_declaration("var order: { price: num }?").is_ok_and(|(rest, _)| rest == "?"));
+        assert!(var_declaration("var order: { price: num, }").is_err());
+        assert!(var_declaration("var order.price: num").is_err());
+        assert!(var_declaration("var age: int in").is_err());
+        assert!(var_declaration("var age: int in 0..130").is_err());
     }
 }
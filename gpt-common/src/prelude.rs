@@ -1,13 +1,25 @@
+use itertools::Itertools;
 use thiserror::Error;
 
+use crate::diagnostic::Diagnostic;
+
 #[derive(Error, Debug)]
 pub enum GPTError {
-    #[error("Parse error: {0}")]
-    ParseError(String),
+    #[error("Parse error: {}", .0.iter().join("\n"))]
+    ParseError(Vec<Diagnostic>),
+
+    #[error("Semantic error: {}", .0.iter().join("\n"))]
+    SemanticError(Vec<Diagnostic>),
 
     #[error("Unknown Parse error: {0}")]
     UnknownParseError(String),
 
+    #[error("Import error: {}", .0.iter().join("\n"))]
+    ImportError(Vec<Diagnostic>),
+
+    #[error("Format error: {0}")]
+    FormatError(String),
+
     #[error("Interval error in logic: {0}")]
     IntervalError(String),
 
@@ -15,4 +27,16 @@ pub enum GPTError {
     Unknown,
 }
 
+impl GPTError {
+    /// The diagnostics of the error, which point to the problems in the source
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        match self {
+            Self::ParseError(diagnostics)
+            | Self::SemanticError(diagnostics)
+            | Self::ImportError(diagnostics) => diagnostics,
+            _ => &[],
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, GPTError>;
@@ -0,0 +1,747 @@
+//! Resolving relations between variables, like `x < y` or `price + shipping >= 200`, on single interval test cases.
+//!
+//! A linear relation is satisfied by pinning all of its variables to single values, except for the one with the smallest step,
+//! which is narrowed to the values satisfying the relation with the others.
+//! The graph reduction only ever narrows the outputs of the test cases it merges,
+//! so a merged test case still satisfies the relations of the test cases it was created from.
+
+use crate::{
+    bva::Bva,
+    dto::{BoolRelationDTO, LinearDTO, LinearTermDTO, NTupleSingleInterval, Output, RelationDTO},
+    interval::{Boundary, Intersectable, Interval, MultiInterval},
+    number::Num,
+    test_value_generator::test_value_for_interval,
+    util::UniquesVec,
+};
+
+/// The values of the term's variable in the test case, or its whole domain if it's not in the test case
+fn numeric_output(test_case: &NTupleSingleInterval, term: &LinearTermDTO) -> Interval {
+    match test_case.get(&term.var_name) {
+        None | Some(Output::MissingVariable) => *term
+            .domain
+            .intervals
+            .first()
+            .expect("The domain of a variable is a single interval"),
+        Some(Output::Interval(interval)) => *interval,
+        Some(output) => panic!(
+            "Mismatched types in relation! Variable {} should be a number, but it is {output:?}",
+            term.var_name
+        ),
+    }
+}
+
+fn bool_output(test_case: &NTupleSingleInterval, var_name: &str) -> Option<bool> {
+    match test_case.get(var_name) {
+        None | Some(Output::MissingVariable | Output::Null) => None,
+        Some(Output::Bool(bool_val)) => Some(*bool_val),
+        Some(output) => panic!(
+            "Mismatched types in relation! Variable {var_name} should be a bool, but it is {output:?}"
+        ),
+    }
+}
+
+/// Moves the boundaries onto the multiples of the precision, if they are between two of them.
+///
+/// Example: `(3.5, 10]` with the precision of `1` becomes `[4, 10]`
+pub fn snap_to_precision(interval: &Interval, precision: Num) -> Option<Interval> {
+    let is_on_grid = |x: Num| !x.is_finite() || x.is_multiple_of(precision);
+
+    let (lo_boundary, lo) = if is_on_grid(interval.lo) {
+        (interval.lo_boundary, interval.lo)
+    } else {
+        (Boundary::Closed, interval.lo.ceil_to(precision))
+    };
+    let (hi_boundary, hi) = if is_on_grid(interval.hi) {
+        (interval.hi_boundary, interval.hi)
+    } else {
+        (Boundary::Closed, interval.hi.floor_to(precision))
+    };
+
+    Interval::new(lo_boundary, lo, hi, hi_boundary).ok()
+}
+
+/// The values of `f(x)`, where `x` is in the `interval`, and `f` is linear, decreasing if `is_decreasing`
+fn map_linear(interval: &Interval, is_decreasing: bool, f: impl Fn(Num) -> Num) -> Interval {
+    if is_decreasing {
+        Interval {
+            lo_boundary: interval.hi_boundary,
+            lo: f(interval.hi),
+            hi: f(interval.lo),
+            hi_boundary: interval.lo_boundary,
+        }
+    } else {
+        Interval {
+            lo: f(interval.lo),
+            hi: f(interval.hi),
+            ..*interval
+        }
+    }
+}
+
+/// The values of `coefficient * x`, where `x` is in the `interval`
+fn scale(interval: &Interval, coefficient: Num) -> Interval {
+    map_linear(interval, coefficient.is_sign_negative(), |x| {
+        x * coefficient
+    })
+}
+
+/// The values of `x`, where `coefficient * x` is in the `interval`
+fn unscale(interval: &Interval, coefficient: Num) -> Interval {
+    map_linear(interval, coefficient.is_sign_negative(), |x| {
+        x / coefficient
+    })
+}
+
+/// The lowest and highest acceptable value of the term in the test case
+fn term_range(test_case: &NTupleSingleInterval, term: &LinearTermDTO) -> Option<(Num, Num)> {
+    let values = numeric_output(test_case, term).calc_in(term.precision);
+    let values = scale(values.intervals.first()?, term.coefficient);
+
+    Some((values.lo, values.hi))
+}
+
+/// The acceptable values of the term's variable in the test case, for which `coefficient * x` is in the `interval`
+fn term_values(
+    test_case: &NTupleSingleInterval,
+    term: &LinearTermDTO,
+    interval: &Interval,
+) -> Option<MultiInterval> {
+    let values = snap_to_precision(&unscale(interval, term.coefficient), term.precision)?;
+    let values = numeric_output(test_case, term).intersect(&values)?;
+
+    Some(values.calc_in(term.precision)).filter(|values| !values.is_empty())
+}
+
+/// The values of the term around the `value`, one for every remainder its multiples can leave when divided by `step`.
+///
+/// Example: `3 * y` of an integer leaves the remainders `0, 1` when divided by `2` for `y = 0, 1`
+fn values_for_remainders(
+    term: &LinearTermDTO,
+    values: &MultiInterval,
+    value: Num,
+    step: Num,
+) -> Vec<Num> {
+    const MAX_VALUES: usize = 64;
+
+    let remainders = (step / term.step().gcd(step)).to_f64() as usize;
+
+    (0..remainders.clamp(1, MAX_VALUES) as i64)
+        .flat_map(|steps| [steps, -steps])
+        .map(|steps| value.add_steps(steps, term.precision))
+        .filter(|value| {
+            values
+                .intervals
+                .iter()
+                .any(|interval| interval.contains_point(*value))
+        })
+        .collect::<Vec<_>>()
+        .uniques()
+}
+
+/// Pins the terms one by one to a value, for which the rest of the terms can still reach the `target`,
+/// and narrows the pivot to the values reaching it with them.
+///
+/// The pivot can only take the multiples of its step, so every term tries the values leaving different remainders of it.
+fn pin_terms(
+    test_case: &NTupleSingleInterval,
+    pivot: &LinearTermDTO,
+    terms: &[&LinearTermDTO],
+    target: &Interval,
+) -> Option<NTupleSingleInterval> {
+    let mut test_case = test_case.clone();
+
+    let Some((term, rest)) = terms.split_first() else {
+        let pivot_values = term_values(&test_case, pivot, target)?;
+        test_case.insert(
+            pivot.var_name.clone(),
+            Output::Interval(*pivot_values.intervals.first()?),
+        );
+
+        return Some(test_case);
+    };
+
+    let (rest_lo, rest_hi) = std::iter::once(pivot)
+        .chain(rest.iter().copied())
+        .try_fold((Num::ZERO, Num::ZERO), |(lo, hi), term| {
+            let (term_lo, term_hi) = term_range(&test_case, term)?;
+            Some((lo + term_lo, hi + term_hi))
+        })?;
+    let reachable = Interval::new(
+        target.lo_boundary,
+        target.lo - rest_hi,
+        target.hi - rest_lo,
+        target.hi_boundary,
+    )
+    .ok()?;
+    let values = term_values(&test_case, term, &reachable)?;
+    let value = values.intervals.first().and_then(test_value_for_interval)?;
+
+    values_for_remainders(term, &values, value, pivot.step())
+        .into_iter()
+        .find_map(|value| {
+            test_case.insert(
+                term.var_name.clone(),
+                Output::Interval(Interval::new_closed_point(value)),
+            );
+            let target = Interval {
+                lo: target.lo - term.coefficient * value,
+                hi: target.hi - term.coefficient * value,
+                ..*target
+            };
+
+            pin_terms(&test_case, pivot, rest, &target)
+        })
+}
+
+/// Narrows the test case, so the sum of the terms is in the `target` interval.
+///
+/// The term with the smallest step is the pivot, the first one of them if there are more,
+/// and it's narrowed to the values reaching the `target` with the others pinned to a single value.
+fn satisfy_linear_target(
+    test_case: &NTupleSingleInterval,
+    linear: &LinearDTO,
+    target: &Interval,
+) -> Option<NTupleSingleInterval> {
+    let pivot = linear.terms.iter().min_by_key(|term| term.step())?;
+    let others = linear
+        .terms
+        .iter()
+        .filter(|term| !std::ptr::eq(*term, pivot))
+        .collect::<Vec<_>>();
+
+    pin_terms(test_case, pivot, &others, target)
+}
+
+fn satisfy_linear(
+    test_case: &NTupleSingleInterval,
+    linear: &LinearDTO,
+) -> Option<NTupleSingleInterval> {
+    linear
+        .interval
+        .intervals
+        .iter()
+        .find_map(|target| satisfy_linear_target(test_case, linear, target))
+}
+
+fn satisfy_bool_relation(
+    test_case: &NTupleSingleInterval,
+    relation: &BoolRelationDTO,
+) -> Option<NTupleSingleInterval> {
+    let to_left = |right: bool| right == relation.should_equal;
+
+    let left = bool_output(test_case, &relation.left);
+    let right = bool_output(test_case, &relation.right);
+
+    let (left, right) = match (left, right) {
+        (Some(left), Some(right)) if left != to_left(right) => return None,
+        (_, Some(right)) => (to_left(right), right),
+        (Some(left), None) => (left, to_left(left)),
+        (None, None) => (to_left(true), true),
+    };
+
+    let mut test_case = test_case.clone();
+    test_case.insert(relation.left.clone(), Output::Bool(left));
+    test_case.insert(relation.right.clone(), Output::Bool(right));
+
+    Some(test_case)
+}
+
+fn satisfy_relation(
+    test_case: &NTupleSingleInterval,
+    relation: &RelationDTO,
+) -> Option<NTupleSingleInterval> {
+    match relation {
+        RelationDTO::Bool(relation) => satisfy_bool_relation(test_case, relation),
+        RelationDTO::Linear(linear) => satisfy_linear(test_case, linear),
+    }
+}
+
+/// Narrows the test case, so that it satisfies all the relations.
+/// Returns `None` if that's not possible.
+pub fn satisfy_relations(
+    test_case: &NTupleSingleInterval,
+    relations: &[RelationDTO],
+) -> Option<NTupleSingleInterval> {
+    relations
+        .iter()
+        .try_fold(test_case.clone(), |test_case, relation| {
+            satisfy_relation(&test_case, relation)
+        })
+}
+
+/// Whether the sum of the terms is in the interval of the relation for every value of the test case
+fn linear_holds(test_case: &NTupleSingleInterval, linear: &LinearDTO) -> bool {
+    // A null variable has no value to add up
+    if linear
+        .terms
+        .iter()
+        .any(|term| test_case.get(&term.var_name) == Some(&Output::Null))
+    {
+        return false;
+    }
+
+    let Some((lo, hi)) = linear
+        .terms
+        .iter()
+        .try_fold((Num::ZERO, Num::ZERO), |(lo, hi), term| {
+            let (term_lo, term_hi) = term_range(test_case, term)?;
+            Some((lo + term_lo, hi + term_hi))
+        })
+    else {
+        return false;
+    };
+
+    Interval::new(Boundary::Closed, lo, hi, Boundary::Closed).is_ok_and(|sum| {
+        linear
+            .interval
+            .intervals
+            .iter()
+            .any(|interval| interval.contains(&sum))
+    })
+}
+
+/// Whether every value of the test case satisfies all the relations
+pub fn relations_hold(test_case: &NTupleSingleInterval, relations: &[RelationDTO]) -> bool {
+    relations.iter().all(|relation| match relation {
+        RelationDTO::Bool(relation) => {
+            match (
+                bool_output(test_case, &relation.left),
+                bool_output(test_case, &relation.right),
+            ) {
+                (Some(left), Some(right)) => (left == right) == relation.should_equal,
+                _ => false,
+            }
+        }
+        RelationDTO::Linear(linear) => linear_holds(test_case, linear),
+    })
+}
+
+/// The On, Off, In-In and Out values of the sum, each of them as a test case
+fn linear_boundaries(
+    test_case: &NTupleSingleInterval,
+    linear: &LinearDTO,
+) -> Vec<NTupleSingleInterval> {
+    let precision = linear.precision();
+    // The sum only takes the multiples of its precision, so `x + 1.5 > y` of integers is `x - y >= -1`
+    let interval = MultiInterval::from_intervals(
+        linear
+            .interval
+            .intervals
+            .iter()
+            .filter_map(|interval| snap_to_precision(interval, precision))
+            .collect(),
+    );
+
+    [
+        interval.on(precision),
+        interval.off(precision),
+        interval.inin(precision),
+        interval.out(precision),
+    ]
+    .iter()
+    .flat_map(|targets| targets.intervals.iter())
+    .filter_map(|target| satisfy_linear_target(test_case, linear, target))
+    .collect()
+}
+
+/// Creates the boundary test cases of every relation from the `test_case`.
+///
+/// For a linear relation these are the On, Off, In-In and Out values of the sum,
+/// for a bool relation `x == y` and `x != y`. All the other relations are satisfied in these test cases.
+pub fn relation_boundaries(
+    test_case: &NTupleSingleInterval,
+    relations: &[RelationDTO],
+) -> Vec<NTupleSingleInterval> {
+    relations
+        .iter()
+        .enumerate()
+        .flat_map(|(i, relation)| {
+            let boundaries = match relation {
+                RelationDTO::Linear(linear) => linear_boundaries(test_case, linear),
+                RelationDTO::Bool(relation) => [true, false]
+                    .into_iter()
+                    .filter_map(|should_equal| {
+                        satisfy_bool_relation(
+                            test_case,
+                            &BoolRelationDTO {
+                                should_equal,
+                                ..relation.clone()
+                            },
+                        )
+                    })
+                    .collect(),
+            };
+
+            let other_relations = relations
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, relation)| relation.clone())
+                .collect::<Vec<_>>();
+
+            boundaries
+                .into_iter()
+                .filter_map(move |boundary| satisfy_relations(&boundary, &other_relations))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        and_reduce_gpt_input,
+        dto::tests::create_ntuple_single_interval,
+        generate_tests_for_gpt_input,
+        graph_reduction::{
+            create_graph,
+            least_losing_components::run_least_losing_components,
+            least_losing_edges::{run_least_losing_edges, run_most_losing_edges},
+            least_losing_nodes_reachable::run_least_losing_nodes_reachable,
+            monke::run_monke,
+        },
+        interval::test::int,
+        ir::{ast_to_ir::binary_op_to_interval, ir_to_ntuple::ir_to_ntuple},
+        number::num,
+        parser::ast::BinaryOp,
+        util::UniquesVec,
+    };
+
+    fn linear(terms: Vec<(&str, Num)>, binary_op: BinaryOp, constant: Num) -> RelationDTO {
+        RelationDTO::Linear(LinearDTO {
+            terms: terms
+                .into_iter()
+                .map(|(var_name, coefficient)| LinearTermDTO {
+                    var_name: var_name.to_owned(),
+                    coefficient,
+                    precision: num!(1.0),
+                    domain: MultiInterval::new_unbounded(),
+                })
+                .collect(),
+            interval: binary_op_to_interval(&binary_op, constant),
+        })
+    }
+
+    fn point(x: Num) -> Output<Interval> {
+        Output::Interval(Interval::new_closed_point(x))
+    }
+
+    #[rstest]
+    #[case::less_than(BinaryOp::LessThan, "[0, 100]", "[50, 100]", Some(("[0, 49]", num!(50.0))))]
+    #[case::less_than_equal(BinaryOp::LessThanEqualTo, "[0, 100]", "[50, 100]", Some(("[0, 50]", num!(50.0))))]
+    #[case::greater_than(BinaryOp::GreaterThan, "[0, 100]", "[50, 100]", Some(("[51, 100]", num!(50.0))))]
+    #[case::equal(BinaryOp::Equal, "[0, 100]", "[50, 100]", Some(("[50, 50]", num!(50.0))))]
+    #[case::right_has_to_be_moved(BinaryOp::LessThan, "[60, 100]", "[50, 100]", Some(("[60, 60]", num!(61.0))))]
+    #[case::not_equal_single_point(BinaryOp::NotEqual, "[50, 50]", "[50, 51]", Some(("[50, 50]", num!(51.0))))]
+    #[case::infeasible(BinaryOp::GreaterThan, "[0, 10]", "[50, 100]", None)]
+    fn test_satisfy_relation_between_two_variables(
+        #[case] binary_op: BinaryOp,
+        #[case] x: &str,
+        #[case] y: &str,
+        #[case] expected: Option<(&str, Num)>,
+    ) {
+        let test_case = create_ntuple_single_interval(vec![
+            ("x", Output::Interval(int(x))),
+            ("y", Output::Interval(int(y))),
+        ]);
+
+        let expected = expected.map(|(x, y)| {
+            create_ntuple_single_interval(vec![("x", Output::Interval(int(x))), ("y", point(y))])
+        });
+
+        assert_eq!(
+            satisfy_relations(
+                &test_case,
+                &[linear(
+                    vec![("x", num!(1.0)), ("y", num!(-1.0))],
+                    binary_op,
+                    num!(0.0)
+                )]
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    // price + shipping >= 200
+    #[case::sum(vec![("price", num!(1.0)), ("shipping", num!(1.0))], BinaryOp::GreaterThanEqualTo, num!(200.0), Some(vec![
+        ("price", Output::Interval(int("[200, 200]"))),
+        ("shipping", point(num!(0.0))),
+        ("age", Output::Interval(int("[0, 100]"))),
+    ]))]
+    // 2 * age - shipping > 10, where shipping has the smaller step
+    #[case::coefficient(vec![("age", num!(2.0)), ("shipping", num!(-1.0))], BinaryOp::GreaterThan, num!(10.0), Some(vec![
+        ("price", Output::Interval(int("[0, 200]"))),
+        ("shipping", Output::Interval(int("[0, 1]"))),
+        ("age", point(num!(6.0))),
+    ]))]
+    // 2 * age + 3 * shipping == 5 is only reached with an odd shipping
+    #[case::pinned_to_reach_pivot(vec![("age", num!(2.0)), ("shipping", num!(3.0))], BinaryOp::Equal, num!(5.0), Some(vec![
+        ("price", Output::Interval(int("[0, 200]"))),
+        ("shipping", point(num!(1.0))),
+        ("age", Output::Interval(int("[1, 1]"))),
+    ]))]
+    // 2 * age == 7 has no integer solution
+    #[case::not_on_the_grid(vec![("age", num!(2.0))], BinaryOp::Equal, num!(7.0), None)]
+    // price + shipping + age > 300
+    #[case::three_variables(vec![("price", num!(1.0)), ("shipping", num!(1.0)), ("age", num!(1.0))], BinaryOp::GreaterThan, num!(300.0), Some(vec![
+        ("price", Output::Interval(int("[200, 200]"))),
+        ("shipping", point(num!(1.0))),
+        ("age", point(num!(100.0))),
+    ]))]
+    // price + shipping + age > 320 can't be reached
+    #[case::infeasible(vec![("price", num!(1.0)), ("shipping", num!(1.0)), ("age", num!(1.0))], BinaryOp::GreaterThan, num!(320.0), None)]
+    fn test_satisfy_linear_relation(
+        #[case] terms: Vec<(&str, Num)>,
+        #[case] binary_op: BinaryOp,
+        #[case] constant: Num,
+        #[case] expected: Option<Vec<(&str, Output<Interval>)>>,
+    ) {
+        let test_case = create_ntuple_single_interval(vec![
+            ("price", Output::Interval(int("[0, 200]"))),
+            ("shipping", Output::Interval(int("[0, 20]"))),
+            ("age", Output::Interval(int("[0, 100]"))),
+        ]);
+
+        assert_eq!(
+            satisfy_relations(&test_case, &[linear(terms, binary_op, constant)]),
+            expected.map(create_ntuple_single_interval)
+        );
+    }
+
+    #[test]
+    fn test_satisfy_bool_relation() {
+        let x_not_equal_to_y = RelationDTO::Bool(BoolRelationDTO {
+            left: "x".to_owned(),
+            right: "y".to_owned(),
+            should_equal: false,
+        });
+
+        assert_eq!(
+            satisfy_relations(
+                &create_ntuple_single_interval(vec![("x", Output::Bool(false))]),
+                &[x_not_equal_to_y.clone()]
+            ),
+            Some(create_ntuple_single_interval(vec![
+                ("x", Output::Bool(false)),
+                ("y", Output::Bool(true)),
+            ]))
+        );
+        assert_eq!(
+            satisfy_relations(
+                &create_ntuple_single_interval(vec![
+                    ("x", Output::Bool(false)),
+                    ("y", Output::Bool(false)),
+                ]),
+                &[x_not_equal_to_y]
+            ),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::on("[19, 19]", "[20, 20]", true)]
+    #[case::off("[20, 20]", "[20, 20]", false)]
+    #[case::in_in("(-Inf, 18]", "[20, 20]", true)]
+    #[case::out("[21, Inf)", "[20, 20]", false)]
+    #[case::partially("[15, 25]", "[20, 20]", false)]
+    fn test_relations_hold(#[case] x: &str, #[case] y: &str, #[case] expected: bool) {
+        let test_case = create_ntuple_single_interval(vec![
+            ("x", Output::Interval(int(x))),
+            ("y", Output::Interval(int(y))),
+        ]);
+
+        // x < y
+        assert_eq!(
+            relations_hold(
+                &test_case,
+                &[linear(
+                    vec![("x", num!(1.0)), ("y", num!(-1.0))],
+                    BinaryOp::LessThan,
+                    num!(0.0)
+                )]
+            ),
+            expected
+        );
+    }
+
+    /// The lowest and highest sum of the terms in the test case
+    fn sum_range(test_case: &NTupleSingleInterval, relation: &RelationDTO) -> String {
+        let RelationDTO::Linear(linear) = relation else {
+            panic!("Expected a linear relation, got {relation:?}");
+        };
+        let (lo, hi) = linear
+            .terms
+            .iter()
+            .try_fold((Num::ZERO, Num::ZERO), |(lo, hi), term| {
+                let (term_lo, term_hi) = term_range(test_case, term)?;
+                Some((lo + term_lo, hi + term_hi))
+            })
+            .expect("The boundaries have values for every term");
+
+        format!("[{lo}, {hi}]")
+    }
+
+    #[rstest]
+    // 2 * x + 3 * y == 5, reached by x = 1 and y = 1
+    #[case::equal(vec![("x", num!(2.0)), ("y", num!(3.0))], BinaryOp::Equal, num!(5.0), vec!["[5, 5]", "[4, 4]", "[6, 6]", "[-Inf, 2]", "[8, Inf]"])]
+    // 2 * x - y > 10
+    #[case::greater_than(vec![("x", num!(2.0)), ("y", num!(-1.0))], BinaryOp::GreaterThan, num!(10.0), vec!["[11, 11]", "[10, 10]", "[12, Inf]", "[-Inf, 9]"])]
+    // x - 2 * y + z < -3
+    #[case::three_variables(vec![("x", num!(1.0)), ("y", num!(-2.0)), ("z", num!(1.0))], BinaryOp::LessThan, num!(-3.0), vec!["[-4, -4]", "[-3, -3]", "[-Inf, -5]", "[-2, Inf]"])]
+    // x + 1.5 > y, where the sum of integers is never -1.5
+    #[case::constant_off_the_grid(vec![("x", num!(1.0)), ("y", num!(-1.0))], BinaryOp::GreaterThan, num!(-1.5), vec!["[-1, -1]", "[-2, -2]", "[0, Inf]", "[-Inf, -3]"])]
+    fn test_linear_boundaries(
+        #[case] terms: Vec<(&str, Num)>,
+        #[case] binary_op: BinaryOp,
+        #[case] constant: Num,
+        #[case] expected: Vec<&str>,
+    ) {
+        let relation = linear(terms, binary_op, constant);
+        let RelationDTO::Linear(linear) = &relation else {
+            unreachable!("The relation is created as a linear one");
+        };
+
+        assert_eq!(
+            linear_boundaries(&create_ntuple_single_interval(vec![]), linear)
+                .iter()
+                .map(|test_case| sum_range(test_case, &relation))
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_relation_boundaries() {
+        // y <= 20
+        let test_case =
+            create_ntuple_single_interval(vec![("y", Output::Interval(int("(-Inf, 20]")))]);
+        let relations = [
+            // x < y
+            linear(
+                vec![("x", num!(1.0)), ("y", num!(-1.0))],
+                BinaryOp::LessThan,
+                num!(0.0),
+            ),
+            // y + z <= 30
+            linear(
+                vec![("y", num!(1.0)), ("z", num!(1.0))],
+                BinaryOp::LessThanEqualTo,
+                num!(30.0),
+            ),
+        ];
+
+        let expected = vec![
+            // x < y: On, Off, In-In and Out
+            create_ntuple_single_interval(vec![
+                ("x", point(num!(19.0))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(10.0))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("x", point(num!(20.0))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(10.0))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("x", Output::Interval(int("(-Inf, 18]"))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(10.0))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("x", Output::Interval(int("[21, Inf)"))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(10.0))),
+            ]),
+            // y + z <= 30: On, Off, In-In and Out
+            create_ntuple_single_interval(vec![
+                ("x", Output::Interval(int("(-Inf, 19]"))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(10.0))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("x", Output::Interval(int("(-Inf, 19]"))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(11.0))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("x", Output::Interval(int("(-Inf, 19]"))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(0.0))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("x", Output::Interval(int("(-Inf, 19]"))),
+                ("y", point(num!(20.0))),
+                ("z", point(num!(12.0))),
+            ]),
+        ];
+
+        assert_eq!(relation_boundaries(&test_case, &relations), expected);
+    }
+
+    /// Whether every value of the `narrowed` test case is one of the values of the `test_case`
+    fn is_narrowed(narrowed: &NTupleSingleInterval, test_case: &NTupleSingleInterval) -> bool {
+        test_case.iter().all(
+            |(var_name, output)| match (output, narrowed.get(var_name)) {
+                (Output::Interval(values), Some(Output::Interval(narrowed))) => {
+                    values.contains(narrowed)
+                }
+                (output, narrowed) => Some(output) == narrowed,
+            },
+        )
+    }
+
+    #[test]
+    fn test_graph_reduction_keeps_relations() {
+        let input = "var price: num(0.1) in [0, 1000]\nvar shipping: num(0.1) in [0, 50]\nvar express: bool\nvar gift: bool\n\
+            if(price + shipping >= 200 && shipping <= 10)\n\
+            if(price - 2 * shipping < 100 && express == gift)\n\
+            if(price > 500 && express != gift)";
+        let relations = and_reduce_gpt_input(input)
+            .unwrap()
+            .iter()
+            .flat_map(ir_to_ntuple)
+            .flat_map(|ntuple| ntuple.relations)
+            .collect::<Vec<_>>()
+            .uniques();
+        assert_eq!(relations.len(), 4);
+        let test_cases = generate_tests_for_gpt_input(input).unwrap();
+        let graph = create_graph(&test_cases);
+
+        for reduced_graph in [
+            run_monke(&graph),
+            run_least_losing_nodes_reachable(&graph),
+            run_least_losing_edges(&graph),
+            run_most_losing_edges(&graph),
+            run_least_losing_components(&graph),
+        ] {
+            let reduced = reduced_graph
+                .node_weights()
+                .map(|test_case| test_case.as_ref().clone())
+                .collect::<Vec<_>>();
+            assert!(reduced.len() < test_cases.len());
+
+            for test_case in &test_cases {
+                let merged = reduced
+                    .iter()
+                    .filter(|merged| is_narrowed(merged, test_case))
+                    .collect::<Vec<_>>();
+                // Every test case is merged into one, which only narrows its values
+                assert!(!merged.is_empty(), "{test_case:?}");
+
+                for relation in &relations {
+                    let relation = std::slice::from_ref(relation);
+                    if relations_hold(test_case, relation) {
+                        assert!(
+                            merged.iter().all(|merged| relations_hold(merged, relation)),
+                            "{relation:?} {test_case:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,45 +1,271 @@
 use std::collections::HashMap;
 
+use itertools::Itertools;
+
 use crate::{
     bva::Bva,
     dto::{
-        BoolDTO, BoolExpression, Input, IntervalDTO, NTupleInput, NTupleOutput,
-        NTupleSingleInterval, Output,
+        AssumptionDTO, BoolDTO, BoolExpression, EnumDTO, Input, IntervalDTO, NTupleInput,
+        NTupleOutput, NTupleSingleInterval, NamedValue, Output, PatternDTO, PeriodicDTO,
+        RelationDTO, SetDTO, StringDTO,
     },
-    interval::{IntervalError, MultiInterval},
+    interval::{Intersectable, Interval, IntervalError, MultiInterval},
+    ir::LengthCondition,
+    number::Num,
+    relation::{relation_boundaries, relations_hold, satisfy_relations, snap_to_precision},
+    test_value_generator::test_value_for_string,
     util::UniquesVec,
 };
 
+/// Creates the test cases of every feature.
+/// The number of test cases dropped, because they can't satisfy the assumptions of their feature, is returned as well.
 pub fn generate_test_cases_for_multiple_features(
     features: &Vec<Vec<NTupleInput>>,
-) -> Result<Vec<NTupleSingleInterval>, IntervalError> {
+) -> Result<(Vec<NTupleSingleInterval>, usize), IntervalError> {
     let mut res = Vec::new();
+    let mut infeasible = 0;
     for feature in features {
-        let mut test_cases = generate_test_cases_for_feature(feature);
+        let (mut test_cases, infeasible_in_feature) = generate_test_cases_for_feature(feature);
         res.append(&mut test_cases);
+        infeasible += infeasible_in_feature;
     }
-    Ok(res)
+    Ok((res, infeasible))
+}
+
+/// Creates test cases, where a variable is just outside of its declared domain, and the others take valid values.
+///
+/// These are only generated on request, because such inputs are usually rejected before reaching the tested logic.
+pub fn generate_robustness_test_cases_for_multiple_features(
+    features: &[Vec<NTupleInput>],
+) -> Vec<NTupleSingleInterval> {
+    features
+        .iter()
+        .flatten()
+        .flat_map(generate_robustness_test_cases_for_inputs)
+        .collect::<Vec<_>>()
+        .uniques()
 }
 
-fn generate_test_cases_for_feature(n_tuples: &[NTupleInput]) -> Vec<NTupleSingleInterval> {
+fn generate_test_cases_for_feature(n_tuples: &[NTupleInput]) -> (Vec<NTupleSingleInterval>, usize) {
     let mut result_test_cases = Vec::new();
+    let mut infeasible = 0;
     for ntuple in n_tuples {
-        let mut test_cases = generate_test_cases_for_inputs(&ntuple.clone());
+        let (mut test_cases, infeasible_in_ntuple) =
+            generate_test_cases_for_inputs(&ntuple.clone());
         result_test_cases.append(&mut test_cases);
+        infeasible += infeasible_in_ntuple;
     }
 
-    result_test_cases
+    (result_test_cases, infeasible)
+}
+
+/// Whether every value of the test case satisfies the conditions of the n-tuple
+fn satisfies(test_case: &NTupleSingleInterval, ntuple: &NTupleInput) -> bool {
+    ntuple
+        .inputs
+        .iter()
+        .all(|(var_name, input)| holds(input, test_case.get(var_name)))
+        && relations_hold(test_case, &ntuple.relations)
 }
 
-fn generate_test_cases_for_inputs(inputs: &NTupleInput) -> Vec<NTupleSingleInterval> {
+/// Whether every value of the output satisfies the condition of the input
+fn holds(input: &Input, output: Option<&Output<Interval>>) -> bool {
+    match (input, output) {
+        (Input::Bool(BoolDTO { bool_val, .. }), Some(Output::Bool(value))) => bool_val == value,
+        (Input::Interval(IntervalDTO { interval, .. }), Some(Output::Interval(value))) => {
+            interval.intervals.iter().any(|x| x.contains(value))
+        }
+        (Input::Enum(EnumDTO { values, .. }), Some(Output::Enum(members))) => {
+            members.iter().all(|member| values.contains(member))
+        }
+        (
+            Input::Set(SetDTO {
+                values, is_member, ..
+            }),
+            Some(Output::Interval(value)),
+        ) => values.iter().any(|x| value.contains_point(*x)) == *is_member,
+        (Input::Null(_), Some(Output::Null)) => true,
+        (Input::Periodic(periodic), Some(Output::Interval(value))) => {
+            value.is_single_point()
+                && periodic
+                    .interval
+                    .intervals
+                    .iter()
+                    .any(|x| x.contains(value))
+                && periodic.holds_for(value.lo)
+        }
+        (
+            Input::String(StringDTO {
+                lengths, patterns, ..
+            }),
+            Some(Output::Text {
+                lengths: value,
+                patterns: made_up_patterns,
+            }),
+        ) => {
+            lengths.intervals.iter().any(|x| x.contains(value))
+                && patterns
+                    .iter()
+                    .all(|pattern| made_up_patterns.contains(pattern))
+        }
+        _ => false,
+    }
+}
+
+/// Narrows the test case to satisfy one of the assumptions, or returns `None` if it can't satisfy any of them.
+/// The variables missing from the test case are left out, since their values don't matter.
+fn assume(
+    test_case: NTupleSingleInterval,
+    assumptions: &[AssumptionDTO],
+) -> Option<NTupleSingleInterval> {
+    if assumptions.is_empty() {
+        return Some(test_case);
+    }
+
+    assumptions.iter().find_map(|assumption| {
+        let mut narrowed = test_case.clone();
+
+        for (var_name, input) in &assumption.inputs {
+            let output = match (input, narrowed.get(var_name)) {
+                (_, None | Some(Output::MissingVariable)) => continue,
+                (
+                    Input::Interval(IntervalDTO {
+                        interval,
+                        precision,
+                        ..
+                    }),
+                    Some(Output::Interval(value)),
+                ) => Output::Interval(
+                    interval
+                        .intervals
+                        .iter()
+                        .filter_map(|allowed| allowed.intersect(value))
+                        .filter_map(|allowed| snap_to_precision(&allowed, *precision))
+                        // The open boundaries of the assumption, like `age > 10`, are closed at the next value
+                        .find_map(|allowed| {
+                            allowed.calc_in(*precision).intervals.into_iter().next()
+                        })?,
+                ),
+                (Input::Enum(EnumDTO { values, .. }), Some(Output::Enum(members))) => {
+                    let members = members
+                        .iter()
+                        .filter(|member| values.contains(member))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if members.is_empty() {
+                        return None;
+                    }
+                    Output::Enum(members)
+                }
+                (input, output) if holds(input, output) => continue,
+                _ => return None,
+            };
+            narrowed.insert(var_name.clone(), output);
+        }
+
+        satisfy_relations(&narrowed, &assumption.relations)
+    })
+}
+
+/// The values of the test case at the named constants of the conditions of the n-tuple,
+/// which are the ON points if they satisfy their conditions, and the OFF points if they don't
+fn named_values(test_case: &NTupleSingleInterval, ntuple: &NTupleInput) -> Vec<NamedValue> {
+    ntuple
+        .constants
+        .iter()
+        .filter(|constant| {
+            matches!(
+                test_case.get(&constant.var_name),
+                Some(Output::Interval(value)) if value.is_single_point() && value.lo == constant.value
+            )
+        })
+        .filter_map(|constant| {
+            let input = ntuple.inputs.get(&constant.var_name)?;
+            Some(NamedValue {
+                var_name: constant.var_name.clone(),
+                name: constant.name.clone(),
+                is_on: holds(input, test_case.get(&constant.var_name)),
+            })
+        })
+        .collect()
+}
+
+/// The expected outcomes of the n-tuple are attached to the test cases satisfying it,
+/// and its requirements, template instantiations and bindings to every test case, because they test its boundaries either way.
+///
+/// The test cases are narrowed to satisfy the assumptions of the n-tuple, and the ones which can't are dropped and counted.
+fn generate_test_cases_for_inputs(inputs: &NTupleInput) -> (Vec<NTupleSingleInterval>, usize) {
+    let test_cases = generate_boundary_test_cases_for_inputs(inputs);
+    let count = test_cases.len();
+
+    let test_cases = test_cases
+        .into_iter()
+        .filter_map(|test_case| assume(test_case, &inputs.assumptions))
+        .map(|mut test_case| {
+            if satisfies(&test_case, inputs) {
+                test_case.expected = inputs.expected.clone();
+            }
+            test_case.requirements = inputs.requirements.clone();
+            test_case.instances = inputs.instances.clone();
+            test_case.bindings = inputs.bindings.clone();
+            test_case.constants = named_values(&test_case, inputs);
+            test_case
+        })
+        .collect::<Vec<_>>();
+    let infeasible = count - test_cases.len();
+
+    // Narrowed test cases can be the same as other ones
+    (test_cases.uniques(), infeasible)
+}
+
+fn generate_boundary_test_cases_for_inputs(inputs: &NTupleInput) -> Vec<NTupleSingleInterval> {
     let mut modified_inputs = calc_in_on_inin(inputs);
+    modified_inputs.extend(enum_members(inputs));
     modified_inputs.extend(off_out(inputs));
+    modified_inputs.extend(type_limits(inputs));
 
     modified_inputs = modified_inputs.uniques();
 
-    modified_inputs
+    let test_cases = modified_inputs
+        .iter()
+        .flat_map(ntuple_multi_cartesian_product);
+
+    // The relations can't hold for a null variable, so the null test cases aren't narrowed by them
+    let null_test_cases = nulls(inputs)
+        .iter()
+        .flat_map(ntuple_multi_cartesian_product)
+        .collect::<Vec<_>>();
+
+    if inputs.relations.is_empty() {
+        let mut test_cases = test_cases.collect::<Vec<_>>();
+        test_cases.extend(null_test_cases);
+        return test_cases;
+    }
+
+    // Relations between variables are resolved on the single interval test cases,
+    // and their boundaries are tested on top of the baseline
+    let mut test_cases = test_cases
+        .filter_map(|test_case| satisfy_relations(&test_case, &inputs.relations))
+        .collect::<Vec<_>>();
+    test_cases.extend(
+        ntuple_multi_cartesian_product(&baseline(inputs))
+            .iter()
+            .flat_map(|test_case| relation_boundaries(test_case, &inputs.relations)),
+    );
+    test_cases.extend(null_test_cases);
+
+    test_cases.uniques()
+}
+
+fn generate_robustness_test_cases_for_inputs(inputs: &NTupleInput) -> Vec<NTupleSingleInterval> {
+    domain_violations(inputs)
         .iter()
         .flat_map(ntuple_multi_cartesian_product)
+        .filter_map(|test_case| satisfy_relations(&test_case, &inputs.relations))
+        .map(|mut test_case| {
+            test_case.instances = inputs.instances.clone();
+            test_case
+        })
         .collect()
 }
 
@@ -47,9 +273,13 @@ fn generate_test_cases_for_inputs(inputs: &NTupleInput) -> Vec<NTupleSingleInter
 /// If a multiinterval would have multiple intervals, it creates an `NTuple` with all the possible single interval combinations.
 fn ntuple_multi_cartesian_product(ntuple: &NTupleOutput) -> Vec<NTupleSingleInterval> {
     if ntuple.outputs.iter().any(|(_, output)| match output {
-        Output::MissingVariable => false,
+        Output::MissingVariable | Output::Null => false,
         Output::Bool(_) => false,
-        Output::Interval(interval) => interval.is_empty(),
+        Output::Interval(interval)
+        | Output::Text {
+            lengths: interval, ..
+        } => interval.is_empty(),
+        Output::Enum(members) => members.is_empty(),
     }) {
         return Vec::new();
     }
@@ -64,8 +294,14 @@ fn ntuple_multi_cartesian_product(ntuple: &NTupleOutput) -> Vec<NTupleSingleInte
                 var_name.clone(),
                 match output {
                     Output::MissingVariable => Output::MissingVariable,
+                    Output::Null => Output::Null,
                     Output::Bool(x) => Output::Bool(*x),
                     Output::Interval(x) => Output::Interval(x.intervals[0]),
+                    Output::Enum(x) => Output::Enum(x.clone()),
+                    Output::Text { lengths, patterns } => Output::Text {
+                        lengths: lengths.intervals[0],
+                        patterns: patterns.clone(),
+                    },
                 },
             )
         })
@@ -79,7 +315,7 @@ fn ntuple_multi_cartesian_product(ntuple: &NTupleOutput) -> Vec<NTupleSingleInte
         let current = res.clone();
 
         match output {
-            Output::MissingVariable | Output::Bool(_) => (),
+            Output::MissingVariable | Output::Null | Output::Bool(_) | Output::Enum(_) => (),
             Output::Interval(interval) => {
                 let mut new = Vec::new();
                 for interval in interval.intervals.iter().skip(1) {
@@ -91,16 +327,45 @@ fn ntuple_multi_cartesian_product(ntuple: &NTupleOutput) -> Vec<NTupleSingleInte
                 }
                 res.append(&mut new);
             }
+            Output::Text { lengths, patterns } => {
+                let mut new = Vec::new();
+                for lengths in lengths.intervals.iter().skip(1) {
+                    for x in &current {
+                        let mut x = x.clone();
+                        x.insert(
+                            var_name.clone(),
+                            Output::Text {
+                                lengths: *lengths,
+                                patterns: patterns.clone(),
+                            },
+                        );
+                        new.push(x);
+                    }
+                }
+                res.append(&mut new);
+            }
         }
     }
 
-    res
+    // Too short strings can't violate their patterns.
+    // The others are narrowed to the length of the string they stand for, so the same strings can be deduplicated
+    res.into_iter()
+        .filter_map(|mut test_case| {
+            for output in test_case.values_mut() {
+                if let Output::Text { lengths, patterns } = output {
+                    let length = test_value_for_string(lengths, patterns)?.chars().count();
+                    *lengths = Interval::new_closed_point(Num::from(length));
+                }
+            }
+            Some(test_case)
+        })
+        .collect()
 }
 
 fn calc_in_on_inin(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
     fn input_to_output(
         ntuple: &NTupleInput,
-        f: impl Fn(&MultiInterval, f32) -> MultiInterval,
+        f: impl Fn(&MultiInterval, Num) -> MultiInterval,
     ) -> HashMap<String, Output<MultiInterval>> {
         ntuple
             .inputs
@@ -108,6 +373,7 @@ fn calc_in_on_inin(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
             .map(|(var_name, input)| -> (String, Output<MultiInterval>) {
                 let output = match input {
                     Input::Bool(BoolDTO { bool_val, .. }) => Output::Bool(*bool_val),
+                    Input::Enum(EnumDTO { values, .. }) => Output::Enum(values.clone()),
                     Input::Interval(IntervalDTO {
                         is_constant,
                         interval,
@@ -118,6 +384,24 @@ fn calc_in_on_inin(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
                         precision,
                         ..
                     }) => Output::Interval(f(interval, *precision)),
+                    // Every member is tested, instead of the boundaries around them
+                    Input::Set(set) => Output::Interval(set.satisfying_and_violating().0),
+                    Input::Periodic(periodic) => {
+                        Output::Interval(periodic.satisfying_and_violating().0)
+                    }
+                    Input::String(StringDTO {
+                        is_constant,
+                        lengths,
+                        patterns,
+                    }) => Output::Text {
+                        lengths: if *is_constant {
+                            lengths.clone()
+                        } else {
+                            f(lengths, Num::ONE)
+                        },
+                        patterns: patterns.clone(),
+                    },
+                    Input::Null(_) => Output::Null,
                 };
 
                 (var_name.clone(), output)
@@ -147,28 +431,162 @@ fn calc_in_on_inin(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
     ]
 }
 
-fn baseline(ntuple: &NTupleInput) -> NTupleOutput {
-    let outputs = ntuple
+/// Creates one test case for every acceptable member of every enum input,
+/// with the rest of the inputs taken from the baseline.
+fn enum_members(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
+    let base = baseline(ntuple);
+
+    ntuple
         .inputs
-        .clone()
-        .into_iter()
-        .map(|(var_name, input)| {
-            let outputs = match input {
-                Input::Bool(BoolDTO { bool_val, .. }) => Output::Bool(bool_val),
-                Input::Interval(IntervalDTO {
-                    interval,
-                    precision,
+        .iter()
+        .flat_map(|(var_name, input)| {
+            match input {
+                Input::Enum(EnumDTO {
+                    values,
+                    is_constant,
                     ..
-                }) => Output::Interval(interval.calc_in(precision)),
-            };
+                }) if !*is_constant => values.clone(),
+                _ => Vec::new(),
+            }
+            .into_iter()
+            .map(|value| {
+                let mut base_member = base.clone();
+                base_member
+                    .outputs
+                    .insert(var_name.clone(), Output::Enum(vec![value]));
+                base_member
+            })
+        })
+        .collect()
+}
 
-            (var_name, outputs)
+fn first_interval(interval: &MultiInterval) -> MultiInterval {
+    interval
+        .intervals
+        .first()
+        .map_or_else(MultiInterval::new_empty, |first| {
+            MultiInterval::from_interval(*first)
         })
+}
+
+fn baseline(ntuple: &NTupleInput) -> NTupleOutput {
+    let outputs = ntuple
+        .inputs
+        .iter()
+        .map(|(var_name, input)| (var_name.clone(), baseline_output(input)))
         .collect::<HashMap<String, Output<MultiInterval>>>();
 
     NTupleOutput { outputs }
 }
 
+fn baseline_output(input: &Input) -> Output<MultiInterval> {
+    match input {
+        Input::Bool(BoolDTO { bool_val, .. }) => Output::Bool(*bool_val),
+        Input::Interval(IntervalDTO {
+            interval,
+            precision,
+            ..
+        }) => Output::Interval(interval.calc_in(*precision)),
+        Input::Enum(EnumDTO { values, .. }) => Output::Enum(values.clone()),
+        Input::Set(set) => Output::Interval(first_interval(&set.satisfying_and_violating().0)),
+        Input::Periodic(periodic) => {
+            Output::Interval(first_interval(&periodic.satisfying_and_violating().0))
+        }
+        Input::String(StringDTO {
+            lengths, patterns, ..
+        }) => Output::Text {
+            lengths: lengths.calc_in(Num::ONE),
+            patterns: patterns.clone(),
+        },
+        Input::Null(_) => Output::Null,
+    }
+}
+
+/// Null for each nullable variable on top of the baseline, unless the variable already has to be null
+fn nulls(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
+    let base = baseline(ntuple);
+
+    ntuple
+        .nullable_variables
+        .iter()
+        .filter(|var_name| !matches!(ntuple.inputs.get(*var_name), Some(Input::Null(_))))
+        .map(|var_name| {
+            let mut base_null = base.clone();
+            base_null.outputs.insert(var_name.clone(), Output::Null);
+            base_null
+        })
+        .collect()
+}
+
+/// The first values outside of the domain of each variable on top of the baseline
+fn domain_violations(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
+    domain_points(ntuple, |_| true, Bva::off)
+}
+
+/// The smallest and the largest value of each sized integer variable on top of the baseline,
+/// because overflow bugs sit at the limits of the type
+fn type_limits(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
+    domain_points(
+        ntuple,
+        |var_name| ntuple.sized_variables.iter().any(|sized| sized == var_name),
+        Bva::on,
+    )
+}
+
+/// One test case on top of the baseline for each of the `points` of the domains of the number variables.
+/// The variables only in relations are included, the relations narrow the others to fit these points.
+fn domain_points(
+    ntuple: &NTupleInput,
+    is_selected: impl Fn(&str) -> bool,
+    points: impl Fn(&MultiInterval, Num) -> MultiInterval,
+) -> Vec<NTupleOutput> {
+    let base = baseline(ntuple);
+
+    let inputs = ntuple
+        .inputs
+        .iter()
+        .filter_map(|(var_name, input)| match input {
+            Input::Interval(IntervalDTO {
+                domain, precision, ..
+            })
+            | Input::Set(SetDTO {
+                domain, precision, ..
+            })
+            | Input::Periodic(PeriodicDTO {
+                domain, precision, ..
+            }) => Some((var_name, domain, *precision)),
+            _ => None,
+        });
+    let related = ntuple
+        .relations
+        .iter()
+        .flat_map(|relation| match relation {
+            RelationDTO::Linear(linear) => linear.terms.iter().collect(),
+            RelationDTO::Bool(_) => Vec::new(),
+        })
+        .filter(|term| !ntuple.inputs.contains_key(&term.var_name))
+        .map(|term| (&term.var_name, &term.domain, term.precision));
+
+    inputs
+        .chain(related)
+        .unique_by(|(var_name, _, _)| *var_name)
+        .filter(|(var_name, _, _)| is_selected(var_name))
+        .flat_map(|(var_name, domain, precision)| {
+            points(domain, precision)
+                .intervals
+                .into_iter()
+                .map(|point| {
+                    let mut base_point = base.clone();
+                    base_point.outputs.insert(
+                        var_name.clone(),
+                        Output::Interval(MultiInterval::from_interval(point)),
+                    );
+                    base_point
+                })
+        })
+        .collect()
+}
+
 fn off_out(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
     let mut output: Vec<NTupleOutput> = Vec::new();
     let base = baseline(ntuple);
@@ -177,6 +595,10 @@ fn off_out(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
         match input {
             Input::Bool(BoolDTO { is_constant, .. }) if *is_constant => continue,
             Input::Interval(IntervalDTO { is_constant, .. }) if *is_constant => continue,
+            Input::Enum(EnumDTO { is_constant, .. }) if *is_constant => continue,
+            Input::Set(SetDTO { is_constant, .. }) if *is_constant => continue,
+            Input::Periodic(PeriodicDTO { is_constant, .. }) if *is_constant => continue,
+            Input::String(StringDTO { is_constant, .. }) if *is_constant => continue,
             _ => (),
         }
 
@@ -198,36 +620,141 @@ fn off_out(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
                 }
             },
             // Generate OFF+OUT combo if the interval is a single point
+            // The values outside of the domain are left to the robustness tests
             Input::Interval(IntervalDTO {
                 interval,
                 precision,
+                domain,
                 ..
             }) if interval.is_single_point() => {
                 let mut base_off_out = base.clone();
-                base_off_out
-                    .outputs
-                    .insert(i.clone(), Output::Interval(interval.off_out(*precision)));
+                base_off_out.outputs.insert(
+                    i.clone(),
+                    Output::Interval(interval.off_out(*precision).restricted_to(domain)),
+                );
 
                 output.push(base_off_out);
             }
             Input::Interval(IntervalDTO {
                 interval,
                 precision,
+                domain,
                 ..
             }) => {
                 let mut base_off = base.clone();
-                base_off
-                    .outputs
-                    .insert(i.clone(), Output::Interval(interval.off(*precision)));
+                base_off.outputs.insert(
+                    i.clone(),
+                    Output::Interval(interval.off(*precision).restricted_to(domain)),
+                );
 
                 let mut base_out = base.clone();
-                base_out
-                    .outputs
-                    .insert(i.clone(), Output::Interval(interval.out(*precision)));
+                base_out.outputs.insert(
+                    i.clone(),
+                    Output::Interval(interval.out(*precision).restricted_to(domain)),
+                );
 
                 output.push(base_off);
                 output.push(base_out);
             }
+            // Every other member is handled as one equivalence partition
+            Input::Enum(EnumDTO {
+                values, members, ..
+            }) => {
+                let other_members = members
+                    .iter()
+                    .filter(|member| !values.contains(member))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if !other_members.is_empty() {
+                    let mut base_other = base.clone();
+                    base_other
+                        .outputs
+                        .insert(i.clone(), Output::Enum(other_members));
+
+                    output.push(base_other);
+                }
+            }
+            // The representative values on the other side of the set condition
+            Input::Set(set) => {
+                let mut base_other = base.clone();
+                base_other.outputs.insert(
+                    i.clone(),
+                    Output::Interval(set.satisfying_and_violating().1),
+                );
+
+                output.push(base_other);
+            }
+            // The values violating the modulo conditions, and the ones outside of the interval
+            Input::Periodic(periodic) => {
+                let mut base_violating = base.clone();
+                base_violating.outputs.insert(
+                    i.clone(),
+                    Output::Interval(periodic.satisfying_and_violating().1),
+                );
+                output.push(base_violating);
+
+                for outside in [
+                    periodic.interval.off(periodic.precision),
+                    periodic.interval.out(periodic.precision),
+                ] {
+                    let mut base_outside = base.clone();
+                    base_outside.outputs.insert(
+                        i.clone(),
+                        Output::Interval(outside.restricted_to(&periodic.domain)),
+                    );
+                    output.push(base_outside);
+                }
+            }
+            // The lengths outside of the length condition,
+            // and a character violating each class on top of the baseline length
+            Input::String(StringDTO {
+                lengths, patterns, ..
+            }) => {
+                let outside = if lengths.is_single_point() {
+                    vec![lengths.off_out(Num::ONE)]
+                } else {
+                    vec![lengths.off(Num::ONE), lengths.out(Num::ONE)]
+                };
+                for outside in outside {
+                    let mut base_outside = base.clone();
+                    base_outside.outputs.insert(
+                        i.clone(),
+                        Output::Text {
+                            lengths: outside.restricted_to(&LengthCondition::domain()),
+                            patterns: patterns.clone(),
+                        },
+                    );
+                    output.push(base_outside);
+                }
+
+                for (j, pattern) in patterns.iter().enumerate() {
+                    let mut violated = patterns.clone();
+                    violated[j] = PatternDTO {
+                        class: pattern.class.clone(),
+                        matches: !pattern.matches,
+                    };
+
+                    let mut base_violated = base.clone();
+                    base_violated.outputs.insert(
+                        i.clone(),
+                        Output::Text {
+                            lengths: lengths.calc_in(Num::ONE),
+                            patterns: violated,
+                        },
+                    );
+                    output.push(base_violated);
+                }
+            }
+            // Any value other than null, like `b == false` of `b == true`
+            Input::Null(not_null) => {
+                let mut base_not_null = base.clone();
+                base_not_null
+                    .outputs
+                    .insert(i.clone(), baseline_output(not_null));
+
+                output.push(base_not_null);
+            }
         }
     }
 
@@ -236,21 +763,38 @@ fn off_out(ntuple: &NTupleInput) -> Vec<NTupleOutput> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use pretty_assertions::assert_eq;
     use Boundary::Open;
 
-    use super::{generate_test_cases_for_inputs, ntuple_multi_cartesian_product};
+    use std::collections::HashMap;
+
+    use itertools::Itertools;
+
+    use super::{
+        generate_robustness_test_cases_for_multiple_features, generate_test_cases_for_inputs,
+        ntuple_multi_cartesian_product, satisfies,
+    };
     use crate::{
+        and_reduce_gpt_input,
         dto::{
             tests::{create_ntuple_input, create_ntuple_output, create_ntuple_single_interval},
-            BoolDTO, BoolExpression, Input, IntervalDTO, NTupleSingleInterval, Output,
+            AssumptionDTO, BoolDTO, BoolExpression, EnumDTO, Input, IntervalDTO, LinearDTO,
+            LinearTermDTO, ModuloDTO, NTupleSingleInterval, Output, PatternDTO, PeriodicDTO,
+            RelationDTO, SetDTO, StringDTO,
         },
+        generate_robustness_tests_for_gpt_input, generate_tests_and_count_infeasible_for_gpt_input,
+        generate_tests_for_gpt_input,
         interval::{
             test::{int, multiint},
             Boundary, Interval, MultiInterval,
         },
+        ir::ir_to_ntuple::ir_to_ntuple,
+        number::{num, Num},
+        parser::ast::CharClass,
+        relation::relations_hold,
+        test_value_generator::generate_test_value,
+        util::UniquesVec,
+        Infeasible,
     };
 
     #[test]
@@ -264,126 +808,126 @@ mod tests {
         ]);
 
         let expected: Vec<NTupleSingleInterval> = vec![
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[2,2]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[7,7]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[3,3]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[7,7]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[4,4]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[7,7]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[2,2]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[7,7]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[3,3]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[7,7]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[4,4]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[7,7]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[2,2]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[8,8]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[3,3]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[8,8]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[4,4]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[8,8]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[2,2]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[8,8]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[3,3]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[8,8]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[4,4]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[8,8]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[2,2]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[9,9]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[3,3]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[9,9]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[4,4]"))),
                 ("d".to_owned(), Output::Interval(int("[5,5]"))),
                 ("e".to_owned(), Output::Interval(int("[9,9]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[2,2]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[9,9]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[3,3]"))),
                 ("d".to_owned(), Output::Interval(int("[6,6]"))),
                 ("e".to_owned(), Output::Interval(int("[9,9]"))),
             ]),
-            HashMap::from([
+            NTupleSingleInterval::from_iter([
                 ("a".to_owned(), Output::Interval(int("[1,1]"))),
                 ("b".to_owned(), Output::Bool(true)),
                 ("c".to_owned(), Output::Interval(int("[4,4]"))),
@@ -446,9 +990,11 @@ mod tests {
             (
                 "y",
                 Input::Interval(IntervalDTO {
-                    interval: MultiInterval::new(Open, f32::NEG_INFINITY, 50.0, Open).unwrap(),
-                    precision: 0.01,
+                    interval: MultiInterval::new(Open, Num::NEG_INFINITY, num!(50.0), Open)
+                        .unwrap(),
+                    precision: num!(0.01),
                     is_constant: false,
+                    domain: MultiInterval::new_unbounded(),
                 }),
             ),
         ]);
@@ -459,20 +1005,23 @@ mod tests {
                 ("x", Output::Bool(true)),
                 (
                     "y",
-                    Output::Interval(Interval::new_closed(f32::NEG_INFINITY, 49.99).unwrap()),
+                    Output::Interval(Interval::new_closed(Num::NEG_INFINITY, num!(49.99)).unwrap()),
                 ),
             ]),
             // on
             create_ntuple_single_interval(vec![
                 ("x", Output::Bool(true)),
-                ("y", Output::Interval(Interval::new_closed_point(49.99))),
+                (
+                    "y",
+                    Output::Interval(Interval::new_closed_point(num!(49.99))),
+                ),
             ]),
             // inin
             create_ntuple_single_interval(vec![
                 ("x", Output::Bool(true)),
                 (
                     "y",
-                    Output::Interval(Interval::new_closed(f32::NEG_INFINITY, 49.98).unwrap()),
+                    Output::Interval(Interval::new_closed(Num::NEG_INFINITY, num!(49.98)).unwrap()),
                 ),
             ]),
             // Bool False
@@ -480,7 +1029,7 @@ mod tests {
                 ("x", Output::Bool(false)),
                 (
                     "y",
-                    Output::Interval(Interval::new_closed(f32::NEG_INFINITY, 49.99).unwrap()),
+                    Output::Interval(Interval::new_closed(Num::NEG_INFINITY, num!(49.99)).unwrap()),
                 ),
             ]),
             // Out
@@ -488,17 +1037,20 @@ mod tests {
                 ("x", Output::Bool(true)),
                 (
                     "y",
-                    Output::Interval(Interval::new_closed(50.01, f32::INFINITY).unwrap()),
+                    Output::Interval(Interval::new_closed(num!(50.01), Num::INFINITY).unwrap()),
                 ),
             ]),
             // Off
             create_ntuple_single_interval(vec![
                 ("x", Output::Bool(true)),
-                ("y", Output::Interval(Interval::new_closed_point(50.0))),
+                (
+                    "y",
+                    Output::Interval(Interval::new_closed_point(num!(50.0))),
+                ),
             ]),
         ];
 
-        let result = generate_test_cases_for_inputs(&inputs);
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
 
         // println!("Expected: {:#?}", expected);
         // println!("Result: {:#?}", result);
@@ -507,4 +1059,875 @@ mod tests {
         assert!(result.iter().all(|x| expected.contains(x)));
         assert!(expected.iter().all(|x| result.contains(x)));
     }
+
+    #[test]
+    fn test_generate_test_cases_for_enum_inputs() {
+        // {silver, gold}; [0, 10]
+        let members = vec!["bronze".to_owned(), "silver".to_owned(), "gold".to_owned()];
+        let inputs = create_ntuple_input(vec![
+            (
+                "tier",
+                Input::Enum(EnumDTO {
+                    values: vec!["silver".to_owned(), "gold".to_owned()],
+                    members,
+                    is_constant: false,
+                }),
+            ),
+            (
+                "y",
+                Input::Interval(IntervalDTO {
+                    interval: multiint("[0, 10]"),
+                    precision: num!(1.0),
+                    is_constant: false,
+                    domain: MultiInterval::new_unbounded(),
+                }),
+            ),
+        ]);
+
+        let any_value = Output::Enum(vec!["silver".to_owned(), "gold".to_owned()]);
+        let expected: Vec<NTupleSingleInterval> = vec![
+            // in
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("[0, 10]"))),
+            ]),
+            // on
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("[0, 0]"))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("[10, 10]"))),
+            ]),
+            // inin
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("[1, 9]"))),
+            ]),
+            // one for each member
+            create_ntuple_single_interval(vec![
+                ("tier", Output::Enum(vec!["silver".to_owned()])),
+                ("y", Output::Interval(int("[0, 10]"))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("tier", Output::Enum(vec!["gold".to_owned()])),
+                ("y", Output::Interval(int("[0, 10]"))),
+            ]),
+            // other member
+            create_ntuple_single_interval(vec![
+                ("tier", Output::Enum(vec!["bronze".to_owned()])),
+                ("y", Output::Interval(int("[0, 10]"))),
+            ]),
+            // off
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("[-1, -1]"))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("[11, 11]"))),
+            ]),
+            // out
+            create_ntuple_single_interval(vec![
+                ("tier", any_value.clone()),
+                ("y", Output::Interval(int("(-Inf, -2]"))),
+            ]),
+            create_ntuple_single_interval(vec![
+                ("tier", any_value),
+                ("y", Output::Interval(int("[12, Inf)"))),
+            ]),
+        ];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(expected.iter().all(|x| result.contains(x)));
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_inputs_with_domain() {
+        // [18, Inf) in the domain of [0, 130]
+        let inputs = create_ntuple_input(vec![(
+            "age",
+            Input::Interval(IntervalDTO {
+                interval: multiint("[18, 130]"),
+                precision: num!(1.0),
+                is_constant: false,
+                domain: multiint("[0, 130]"),
+            }),
+        )]);
+
+        let expected: Vec<NTupleSingleInterval> = vec![
+            // in
+            create_ntuple_single_interval(vec![("age", Output::Interval(int("[18, 130]")))]),
+            // on
+            create_ntuple_single_interval(vec![("age", Output::Interval(int("[18, 18]")))]),
+            create_ntuple_single_interval(vec![("age", Output::Interval(int("[130, 130]")))]),
+            // inin
+            create_ntuple_single_interval(vec![("age", Output::Interval(int("[19, 129]")))]),
+            // off, 131 is outside of the domain
+            create_ntuple_single_interval(vec![("age", Output::Interval(int("[17, 17]")))]),
+            // out, clamped to the domain
+            create_ntuple_single_interval(vec![("age", Output::Interval(int("[0, 16]")))]),
+        ];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(expected.iter().all(|x| result.contains(x)));
+
+        assert_eq!(
+            generate_robustness_test_cases_for_multiple_features(&[vec![inputs]]),
+            vec![
+                create_ntuple_single_interval(vec![("age", Output::Interval(int("[-1, -1]")))]),
+                create_ntuple_single_interval(vec![("age", Output::Interval(int("[131, 131]")))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_sized_inputs() {
+        // level > 100 for a u8
+        let mut inputs = create_ntuple_input(vec![(
+            "level",
+            Input::Interval(IntervalDTO {
+                interval: multiint("(100, 255]"),
+                precision: num!(1),
+                is_constant: false,
+                domain: multiint("[0, 255]"),
+            }),
+        )]);
+        inputs.sized_variables = vec!["level".to_owned()];
+
+        let expected: Vec<NTupleSingleInterval> = vec![
+            // in
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[101, 255]")))]),
+            // on, 255 is the max of the type
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[101, 101]")))]),
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[255, 255]")))]),
+            // inin
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[102, 254]")))]),
+            // off
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[100, 100]")))]),
+            // out
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[0, 99]")))]),
+            // the min of the type
+            create_ntuple_single_interval(vec![("level", Output::Interval(int("[0, 0]")))]),
+        ];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(expected.iter().all(|x| result.contains(x)));
+
+        assert_eq!(
+            generate_robustness_test_cases_for_multiple_features(&[vec![inputs]]),
+            vec![
+                create_ntuple_single_interval(vec![("level", Output::Interval(int("[-1, -1]")))]),
+                create_ntuple_single_interval(vec![("level", Output::Interval(int("[256, 256]")))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_nullable_inputs() {
+        // coupon > 20 for an int? and vip == true
+        let mut inputs = create_ntuple_input(vec![
+            (
+                "coupon",
+                Input::Interval(IntervalDTO {
+                    interval: multiint("(20, 50]"),
+                    precision: num!(1),
+                    is_constant: false,
+                    domain: multiint("[0, 50]"),
+                }),
+            ),
+            (
+                "vip",
+                Input::Bool(BoolDTO {
+                    expression: BoolExpression::IsTrue,
+                    bool_val: true,
+                    is_constant: false,
+                }),
+            ),
+        ]);
+        inputs.nullable_variables = vec!["coupon".to_owned()];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(
+            result
+                .iter()
+                .filter(|test_case| test_case.get("coupon") == Some(&Output::Null))
+                .collect::<Vec<_>>(),
+            vec![&create_ntuple_single_interval(vec![
+                ("coupon", Output::Null),
+                ("vip", Output::Bool(true)),
+            ])]
+        );
+        assert_eq!(result.len(), 8);
+
+        // coupon is null
+        let mut inputs = create_ntuple_input(vec![(
+            "coupon",
+            Input::Null(Box::new(Input::Interval(IntervalDTO {
+                interval: multiint("[0, 50]"),
+                precision: num!(1),
+                is_constant: false,
+                domain: multiint("[0, 50]"),
+            }))),
+        )]);
+        inputs.nullable_variables = vec!["coupon".to_owned()];
+
+        assert_eq!(
+            generate_test_cases_for_inputs(&inputs).0,
+            vec![
+                create_ntuple_single_interval(vec![("coupon", Output::Null)]),
+                create_ntuple_single_interval(vec![("coupon", Output::Interval(int("[0, 50]")))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_string_inputs() {
+        // len(postcode) == 4 && postcode matches "[0-9]"
+        let inputs = create_ntuple_input(vec![(
+            "postcode",
+            Input::String(StringDTO {
+                lengths: multiint("[4, 4]"),
+                patterns: vec![PatternDTO {
+                    class: CharClass {
+                        ranges: vec![('0', '9')],
+                    },
+                    matches: true,
+                }],
+                is_constant: false,
+            }),
+        )]);
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|test_case| generate_test_value(&test_case["postcode"], false))
+                .sorted()
+                .collect::<Vec<_>>(),
+            vec!["\"\"", "\"0000\"", "\"00000\"", "\"aaaa\""]
+        );
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_inputs_with_assumptions() {
+        // age < 18 in the domain of [0, 130], assuming age >= 16 && service <= age - 16
+        let mut inputs = create_ntuple_input(vec![(
+            "age",
+            Input::Interval(IntervalDTO {
+                interval: multiint("[0, 18)"),
+                precision: num!(1),
+                is_constant: false,
+                domain: multiint("[0, 130]"),
+            }),
+        )]);
+        let relation = RelationDTO::Linear(LinearDTO {
+            terms: vec![("service", num!(1)), ("age", num!(-1))]
+                .into_iter()
+                .map(|(var_name, coefficient)| LinearTermDTO {
+                    var_name: var_name.to_owned(),
+                    coefficient,
+                    precision: num!(1),
+                    domain: multiint("[0, 60]"),
+                })
+                .collect(),
+            interval: multiint("(-Inf, -16]"),
+        });
+        inputs.assumptions = vec![AssumptionDTO {
+            inputs: HashMap::from([(
+                "age".to_owned(),
+                Input::Interval(IntervalDTO {
+                    interval: multiint("[16, Inf)"),
+                    precision: num!(1),
+                    is_constant: false,
+                    domain: multiint("[0, 130]"),
+                }),
+            )]),
+            relations: vec![relation.clone()],
+        }];
+
+        let test_case = |age: &str, service: &str| {
+            create_ntuple_single_interval(vec![
+                ("age", Output::Interval(int(age))),
+                ("service", Output::Interval(int(service))),
+            ])
+        };
+        // the ages are narrowed to at least 16, and the relation pins each age to a point with a valid service
+        let expected: Vec<NTupleSingleInterval> = vec![
+            test_case("[16, 16]", "[0, 0]"),
+            test_case("[17, 17]", "[0, 1]"),
+            test_case("[18, 18]", "[0, 2]"),
+            test_case("[19, 19]", "[0, 3]"),
+        ];
+
+        let (result, infeasible) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(result
+            .iter()
+            .all(|test_case| relations_hold(test_case, &[relation.clone()])));
+        assert_eq!(infeasible, 1);
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_set_inputs() {
+        // {3, 7, 12, 15} in the domain of [0, 20]
+        let set = SetDTO {
+            values: vec![num!(3.0), num!(7.0), num!(12.0), num!(15.0)],
+            is_member: true,
+            precision: num!(1.0),
+            is_constant: false,
+            domain: multiint("[0, 20]"),
+        };
+        let inputs = create_ntuple_input(vec![("code", Input::Set(set.clone()))]);
+
+        let expected: Vec<NTupleSingleInterval> = vec![
+            // one for each member
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[3, 3]")))]),
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[7, 7]")))]),
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[12, 12]")))]),
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[15, 15]")))]),
+            // non-members below, in the middle of and above the set
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[0, 2]")))]),
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[8, 11]")))]),
+            create_ntuple_single_interval(vec![("code", Output::Interval(int("[16, 20]")))]),
+        ];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(expected.iter().all(|x| result.contains(x)));
+
+        // not in {3, 7, 12, 15}, only the non-members satisfy it
+        let mut inputs = create_ntuple_input(vec![(
+            "code",
+            Input::Set(SetDTO {
+                is_member: false,
+                ..set
+            }),
+        )]);
+        inputs.expected = vec!["report".to_owned()];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result
+            .iter()
+            .all(|x| expected.iter().any(|y| x.values == y.values)));
+        assert_eq!(
+            result
+                .iter()
+                .filter(|test_case| !test_case.expected.is_empty())
+                .map(|test_case| test_case["code"].clone())
+                .collect::<Vec<_>>(),
+            vec![
+                Output::Interval(int("[0, 2]")),
+                Output::Interval(int("[8, 11]")),
+                Output::Interval(int("[16, 20]")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_periodic_inputs() {
+        // [1650, 1750] && x % 4 == 0 && x % 100 != 0 in the domain of [1600, 2400]
+        let mut inputs = create_ntuple_input(vec![(
+            "year",
+            Input::Periodic(PeriodicDTO {
+                interval: multiint("[1650, 1750]"),
+                modulos: vec![
+                    ModuloDTO {
+                        divisor: num!(4.0),
+                        remainder: num!(0.0),
+                        should_equal: true,
+                    },
+                    ModuloDTO {
+                        divisor: num!(100.0),
+                        remainder: num!(0.0),
+                        should_equal: false,
+                    },
+                ],
+                precision: num!(1.0),
+                is_constant: false,
+                domain: multiint("[1600, 2400]"),
+            }),
+        )]);
+        inputs.expected = vec!["leap year".to_owned()];
+
+        let year = |interval: &str| {
+            create_ntuple_single_interval(vec![("year", Output::Interval(int(interval)))])
+        };
+        let expected: Vec<NTupleSingleInterval> = vec![
+            // satisfying, nearest to the boundaries
+            NTupleSingleInterval {
+                expected: vec!["leap year".to_owned()],
+                ..year("[1652, 1652]")
+            },
+            NTupleSingleInterval {
+                expected: vec!["leap year".to_owned()],
+                ..year("[1748, 1748]")
+            },
+            // not divisible by 4
+            year("[1650, 1650]"),
+            year("[1750, 1750]"),
+            // divisible by 100
+            year("[1700, 1700]"),
+            // off
+            year("[1649, 1649]"),
+            year("[1751, 1751]"),
+            // out, clamped to the domain
+            year("[1600, 1648]"),
+            year("[1752, 2400]"),
+        ];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(expected.iter().all(|x| result.contains(x)));
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_inputs_with_expected_outcome() {
+        // price >= 200 && vip == true => discount = 10
+        let mut inputs = create_ntuple_input(vec![
+            (
+                "price",
+                Input::Interval(IntervalDTO {
+                    interval: multiint("[200, Inf)"),
+                    precision: num!(1.0),
+                    is_constant: false,
+                    domain: MultiInterval::new_unbounded(),
+                }),
+            ),
+            (
+                "vip",
+                Input::Bool(BoolDTO {
+                    expression: BoolExpression::IsTrue,
+                    bool_val: true,
+                    is_constant: false,
+                }),
+            ),
+        ]);
+        inputs.expected = vec!["discount = 10".to_owned()];
+        inputs.requirements = vec!["R1".to_owned()];
+        inputs.instances = vec!["discount(200)".to_owned()];
+        inputs.bindings = vec!["big_spender".to_owned()];
+
+        let test_case = |price: &str, vip: bool, expected: Vec<&str>| NTupleSingleInterval {
+            values: create_ntuple_single_interval(vec![
+                ("price", Output::Interval(int(price))),
+                ("vip", Output::Bool(vip)),
+            ])
+            .values,
+            expected: expected.into_iter().map(str::to_owned).collect(),
+            // Every test case exercises the requirement of the branch
+            requirements: vec!["R1".to_owned()],
+            instances: vec!["discount(200)".to_owned()],
+            bindings: vec!["big_spender".to_owned()],
+            constants: Vec::new(),
+        };
+
+        let expected: Vec<NTupleSingleInterval> = vec![
+            // in, on and inin satisfy the conditions
+            test_case("[200, Inf)", true, vec!["discount = 10"]),
+            test_case("[200, 200]", true, vec!["discount = 10"]),
+            test_case("[201, Inf)", true, vec!["discount = 10"]),
+            // off and out don't
+            test_case("[199, 199]", true, vec![]),
+            test_case("(-Inf, 198]", true, vec![]),
+            test_case("[200, Inf)", false, vec![]),
+        ];
+
+        let (result, _) = generate_test_cases_for_inputs(&inputs);
+
+        assert_eq!(result.len(), expected.len());
+        assert!(result.iter().all(|x| expected.contains(x)));
+        assert!(expected.iter().all(|x| result.contains(x)));
+    }
+
+    /// The expected outcomes of the branches, which the values satisfy
+    fn outcomes_of(input: &str, values: Vec<(&str, Output<Interval>)>) -> Vec<String> {
+        let test_case = create_ntuple_single_interval(values);
+
+        and_reduce_gpt_input(input)
+            .unwrap()
+            .iter()
+            .flat_map(ir_to_ntuple)
+            .filter(|ntuple| satisfies(&test_case, ntuple))
+            .flat_map(|ntuple| ntuple.expected)
+            .unique()
+            .collect()
+    }
+
+    #[test]
+    fn test_generate_test_cases_with_outcomes_of_else_ifs() {
+        let input = include_str!("../../examples/gpt-lang/discount_outcomes.gpt");
+
+        let reached = generate_tests_for_gpt_input(input)
+            .unwrap()
+            .into_iter()
+            .flat_map(|test_case| test_case.expected)
+            .unique()
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            reached,
+            vec![
+                "discount = 10",
+                "discount = 15",
+                "discount = 5",
+                "no discount"
+            ]
+        );
+
+        for (price, vip, expected) in [
+            ("[250, 250]", true, "discount = 15"),
+            ("[200, 200]", false, "discount = 10"),
+            ("[199.9, 199.9]", true, "discount = 5"),
+            ("[100, 100]", false, "discount = 5"),
+            ("[99.9, 99.9]", true, "no discount"),
+            ("[0, 0]", false, "no discount"),
+        ] {
+            let values = vec![
+                ("price", Output::Interval(int(price))),
+                ("vip", Output::Bool(vip)),
+            ];
+
+            assert_eq!(outcomes_of(input, values), vec![expected], "{price} {vip}");
+        }
+    }
+
+    #[test]
+    fn test_generate_test_cases_with_outcomes_of_periodic_else_ifs() {
+        let input = include_str!("../../examples/gpt-lang/leap_year.gpt");
+
+        for (year, expected) in [
+            ("[1600, 1600]", "leap year"),
+            ("[1900, 1900]", "common year"),
+            ("[2000, 2000]", "leap year"),
+            ("[2024, 2024]", "leap year"),
+            ("[2023, 2023]", "common year"),
+        ] {
+            let values = vec![("year", Output::Interval(int(year)))];
+
+            assert_eq!(outcomes_of(input, values), vec![expected], "{year}");
+        }
+    }
+
+    #[test]
+    fn test_generate_test_cases_with_outcomes_of_periodic_condition() {
+        // The leap year rules in a single condition, where a conjunction is or-ed with a single condition
+        let input = "var year: int in [1600, 2400]
+if(year % 4 == 0 && year % 100 != 0 || year % 400 == 0) => \"leap year\"
+else => \"common year\"";
+
+        let reached = generate_tests_for_gpt_input(input)
+            .unwrap()
+            .into_iter()
+            .flat_map(|test_case| {
+                let year = test_case.get("year").cloned();
+                test_case
+                    .expected
+                    .into_iter()
+                    .map(move |expected| (year.clone(), expected))
+            })
+            .collect_vec()
+            .uniques();
+        assert_eq!(
+            reached,
+            [
+                ("[1600, 1600]", "leap year"),
+                ("[2400, 2400]", "leap year"),
+                ("[1604, 1604]", "leap year"),
+                ("[2396, 2396]", "leap year"),
+                ("[1601, 1601]", "common year"),
+                ("[2399, 2399]", "common year"),
+                ("[1700, 1700]", "common year"),
+                ("[2300, 2300]", "common year"),
+            ]
+            .map(|(year, expected)| (Some(Output::Interval(int(year))), expected.to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_generate_test_cases_with_named_values() {
+        let input = "const LIMIT = 100
+var price: int in [0, 1000]
+if(price > LIMIT) => \"high\"
+else => \"low\"";
+
+        let named = generate_tests_for_gpt_input(input)
+            .unwrap()
+            .into_iter()
+            .filter(|test_case| !test_case.constants.is_empty())
+            .map(|test_case| {
+                (
+                    test_case.get("price").cloned(),
+                    test_case
+                        .constants
+                        .iter()
+                        .map(ToString::to_string)
+                        .join(", "),
+                    test_case.expected,
+                )
+            })
+            .collect_vec()
+            .uniques();
+        assert_eq!(
+            named,
+            vec![
+                (
+                    Some(Output::Interval(int("[100, 100]"))),
+                    "price = LIMIT (OFF)".to_owned(),
+                    Vec::new(),
+                ),
+                (
+                    Some(Output::Interval(int("[100, 100]"))),
+                    "price = LIMIT (ON)".to_owned(),
+                    vec!["low".to_owned()],
+                ),
+            ]
+        );
+    }
+
+    /// The conditions of every conjunction the branches are split into, like `x == true && y == false`
+    fn conjunctions(input: &str) -> Vec<String> {
+        and_reduce_gpt_input(input)
+            .unwrap()
+            .iter()
+            .flat_map(|feature| &feature.predicates)
+            .flat_map(|predicate| predicate.conjunction_of_conditions())
+            .map(|conditions| conditions.iter().join(" && "))
+            .collect()
+    }
+
+    #[test]
+    fn test_conjunctions_of_ands_and_ors() {
+        for (input, expected) in [
+            (
+                include_str!("../../examples/gpt-lang/three_or.gpt"),
+                vec![
+                    "x == true",
+                    "x == false && y == true",
+                    "x == false && y == false && z == true",
+                    "x == false && z == true",
+                    "x == false && z == false && y == true",
+                    "y == true",
+                    "y == false && x == true",
+                    "y == false && z == true",
+                    "y == false && z == false && x == true",
+                    "z == true",
+                    "z == false && x == true",
+                    "z == false && y == true",
+                ],
+            ),
+            (
+                include_str!("../../examples/gpt-lang/or_and_or.gpt"),
+                vec![
+                    "a == true && c == true",
+                    "a == true && c == false && d == true",
+                    "a == true && d == true",
+                    "a == true && d == false && c == true",
+                    "a == false && b == true && c == true",
+                    "a == false && b == true && c == false && d == true",
+                    "a == false && b == true && d == true",
+                    "a == false && b == true && d == false && c == true",
+                    "b == true && c == true",
+                    "b == true && c == false && d == true",
+                    "b == true && d == true",
+                    "b == true && d == false && c == true",
+                    "b == false && a == true && c == true",
+                    "b == false && a == true && c == false && d == true",
+                    "b == false && a == true && d == true",
+                    "b == false && a == true && d == false && c == true",
+                ],
+            ),
+            (
+                "var x: int\nvar y: int\nif((x == 0 && y == 0) || (x == 1 && y == 1))",
+                vec!["x in [0, 0] && y in [0, 0]", "x in [1, 1] && y in [1, 1]"],
+            ),
+            // `a || b => c` is lowered to `(!a && !b) || c`, where a disjunct is or-ed with a conjunction
+            (
+                "var a: bool\nvar b: bool\nvar c: bool\nif(a == true || b == true => c == true)",
+                vec!["c == true", "a == false && b == false"],
+            ),
+        ] {
+            assert_eq!(conjunctions(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_infeasible_branch() {
+        // The branches can't be taken under the assumptions, but not taking them can be tested.
+        // Their other test cases are removed, as they can't satisfy the assumptions
+        for (input, var_name, expected, removed) in [
+            (
+                "var x: int\nassume(x > 10)\nif(x > 5 && x < 8)",
+                "x",
+                "[11, Inf)",
+                6,
+            ),
+            (
+                "var age: int in [0, 130]\nassume(age > 10 && age < 20)\nif(age >= 60)",
+                "age",
+                "[11, 19]",
+                5,
+            ),
+        ] {
+            let (test_cases, infeasible) =
+                generate_tests_and_count_infeasible_for_gpt_input(input).unwrap();
+
+            assert_eq!(
+                test_cases,
+                vec![create_ntuple_single_interval(vec![(
+                    var_name,
+                    Output::Interval(int(expected))
+                )])],
+                "{input}"
+            );
+            assert_eq!(
+                infeasible,
+                Infeasible {
+                    branches: 1,
+                    test_cases: removed,
+                },
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_null_conditions() {
+        for (input, expected) in [
+            // A value can't be both null and greater than 5
+            ("var x: int?\nif(x is null && x > 5)", vec![]),
+            (
+                "var x: int?\nif(x is null || x > 5)",
+                vec![
+                    Output::Null,
+                    Output::Interval(int("(-Inf, Inf)")),
+                    Output::Interval(int("[6, Inf)")),
+                    Output::Interval(int("[6, 6]")),
+                    Output::Interval(int("[7, Inf)")),
+                    Output::Interval(int("[5, 5]")),
+                    Output::Interval(int("(-Inf, 4]")),
+                    Output::Null,
+                ],
+            ),
+            (
+                "var x: int?\nif(x is null)",
+                vec![Output::Null, Output::Interval(int("(-Inf, Inf)"))],
+            ),
+        ] {
+            assert_eq!(
+                generate_tests_for_gpt_input(input)
+                    .unwrap()
+                    .iter()
+                    .map(|test_case| test_case.get("x").cloned().unwrap())
+                    .collect::<Vec<_>>(),
+                expected,
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_conflicting_conditions() {
+        // No value satisfies both conditions, so the conjunction is dropped instead of keeping either
+        for input in [
+            "var t: enum { a, b }\nif(t == a && t == b)",
+            "var x: int\nif(x in {1, 2} && x in {5, 6})",
+            "var s: string\nif(len(s) in [3, 16] && len(s) > 20)",
+        ] {
+            assert_eq!(
+                generate_tests_for_gpt_input(input).unwrap(),
+                vec![],
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_test_cases_with_the_same_strings() {
+        let input = include_str!("../../examples/gpt-lang/signup_form.gpt");
+
+        let strings = generate_tests_for_gpt_input(input)
+            .unwrap()
+            .iter()
+            .flat_map(|test_case| {
+                test_case
+                    .iter()
+                    .map(|(var_name, output)| {
+                        (var_name.clone(), generate_test_value(output, false))
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        // The in and on strings of `len(username) in [3, 16]` are both "aaa"
+        assert_eq!(strings.iter().unique().count(), strings.len());
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_limits_of_related_variables() {
+        let input = "var health: i16\nvar healing: i16 in [0, 500]\nif(health + healing > 30000)";
+        let values_of = |test_cases: Vec<NTupleSingleInterval>, var_name: &str| {
+            test_cases
+                .iter()
+                .filter_map(|test_case| test_case.get(var_name).cloned())
+                .collect_vec()
+        };
+
+        let test_cases = generate_tests_for_gpt_input(input).unwrap();
+        assert!(values_of(test_cases.clone(), "health")
+            .contains(&Output::Interval(int("[32767, 32767]"))));
+        assert!(values_of(test_cases, "healing").contains(&Output::Interval(int("[500, 500]"))));
+
+        let robustness_test_cases = generate_robustness_tests_for_gpt_input(input).unwrap();
+        let health = values_of(robustness_test_cases.clone(), "health");
+        let healing = values_of(robustness_test_cases, "healing");
+        assert!(health.contains(&Output::Interval(int("[32768, 32768]"))));
+        // The sum can't reach the relation from -32769
+        assert!(!health.contains(&Output::Interval(int("[-32769, -32769]"))));
+        assert!(healing.contains(&Output::Interval(int("[-1, -1]"))));
+        assert!(healing.contains(&Output::Interval(int("[501, 501]"))));
+    }
+
+    #[test]
+    fn test_generate_test_cases_for_constant_off_precision() {
+        let input = "var p: num(0.1)\nif(p >= 199.95)";
+
+        let values = generate_tests_for_gpt_input(input)
+            .unwrap()
+            .iter()
+            .filter_map(|test_case| test_case.get("p").cloned())
+            .collect_vec();
+
+        // The boundary is rounded up to the first value `p` can take
+        assert_eq!(
+            values,
+            vec![
+                Output::Interval(int("[200.0, Inf)")),
+                Output::Interval(int("[200.0, 200.0]")),
+                Output::Interval(int("[200.1, Inf)")),
+                Output::Interval(int("[199.9, 199.9]")),
+                Output::Interval(int("(-Inf, 199.8]")),
+            ]
+        );
+    }
 }
@@ -1,26 +1,98 @@
-use crate::{dto::Output, interval::Interval};
+use crate::{
+    dto::{Output, PatternDTO},
+    interval::{Boundary, Intersectable, Interval},
+    number::Num,
+    relation::snap_to_precision,
+};
 
-fn test_value_for_interval(interval: &Interval) -> Option<f32> {
+/// The characters strings are made up from, the most readable ones first
+const STRING_CHARACTERS: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-.@ !#$%&*+/:;=?";
+
+pub(crate) fn test_value_for_interval(interval: &Interval) -> Option<Num> {
     if interval.is_empty() {
         None
     } else {
         Some(match (interval.lo, interval.hi) {
-            (f32::NEG_INFINITY, f32::INFINITY) => 0.0,
-            (f32::NEG_INFINITY, x) => x,
-            (x, f32::INFINITY) => x,
+            (Num::NegInfinity, Num::Infinity) => Num::ZERO,
+            (Num::NegInfinity, x) => x,
+            (x, Num::Infinity) => x,
             (x, _) => x,
         })
     }
 }
 
+/// The shortest string with a length in the interval, whose characters are in every class it has to match.
+/// Each class it has to violate gets a character outside of it at the start of the string.
+///
+/// Returns `None` if there is no such string, like when the interval is too short for the violations.
+pub(crate) fn test_value_for_string(lengths: &Interval, patterns: &[PatternDTO]) -> Option<String> {
+    let allowed = |c: &char| {
+        patterns
+            .iter()
+            .filter(|pattern| pattern.matches)
+            .all(|pattern| pattern.class.contains(*c))
+    };
+    let violations = patterns
+        .iter()
+        .filter(|pattern| !pattern.matches)
+        .map(|pattern| {
+            STRING_CHARACTERS
+                .chars()
+                .filter(allowed)
+                .find(|c| !pattern.class.contains(*c))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let long_enough = Interval::new(
+        Boundary::Closed,
+        Num::from(violations.len()),
+        Num::INFINITY,
+        Boundary::Open,
+    )
+    .ok()?;
+    let lengths = snap_to_precision(&lengths.intersect(&long_enough)?, Num::ONE)?;
+    let length = match lengths.lo_boundary {
+        Boundary::Closed => lengths.lo,
+        Boundary::Open => lengths.lo + Num::ONE,
+    };
+    if !lengths.contains_point(length) {
+        return None;
+    }
+
+    let filler = STRING_CHARACTERS.chars().find(allowed);
+    let fillers = length.to_f64() as usize - violations.len();
+    if fillers > 0 && filler.is_none() {
+        return None;
+    }
+
+    Some(
+        violations
+            .into_iter()
+            .chain(filler.into_iter().cycle().take(fillers))
+            .collect(),
+    )
+}
+
 // TODO: There should be a value which returns the whole test case table
 pub fn generate_test_value(output: &Output<Interval>, show_interval_values: bool) -> String {
     match output {
         Output::MissingVariable => "*".to_owned(),
+        Output::Null => "null".to_owned(),
         Output::Bool(bool_val) => match *bool_val {
             true => "true".to_owned(),
             false => "false".to_owned(),
         },
+        Output::Enum(members) => {
+            if show_interval_values {
+                format!("{{{}}}", members.join(", "))
+            } else {
+                members
+                    .first()
+                    .expect("NTupleSingleInterval should not be empty, it was checked before")
+                    .clone()
+            }
+        }
         Output::Interval(interval) => {
             if show_interval_values {
                 format!("{interval:?}")
@@ -32,5 +104,79 @@ pub fn generate_test_value(output: &Output<Interval>, show_interval_values: bool
                 )
             }
         }
+        Output::Text { lengths, patterns } => {
+            if show_interval_values {
+                format!(
+                    "len {lengths:?}{}",
+                    patterns
+                        .iter()
+                        .map(|pattern| format!(
+                            " {} \"{}\"",
+                            if pattern.matches {
+                                "matches"
+                            } else {
+                                "doesn't match"
+                            },
+                            pattern.class
+                        ))
+                        .collect::<String>()
+                )
+            } else {
+                format!(
+                    "{:?}",
+                    test_value_for_string(lengths, patterns)
+                        .expect("NTupleSingleInterval should not be empty, it was checked before")
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{interval::test::int, parser::ast::CharClass};
+
+    fn pattern(ranges: &[(char, char)], matches: bool) -> PatternDTO {
+        PatternDTO {
+            class: CharClass {
+                ranges: ranges.to_vec(),
+            },
+            matches,
+        }
+    }
+
+    #[rstest]
+    #[case::shortest(int("[3, 16]"), vec![], Some("aaa"))]
+    #[case::empty(int("[0, 0]"), vec![], Some(""))]
+    #[case::digits(int("[4, 4]"), vec![pattern(&[('0', '9')], true)], Some("0000"))]
+    #[case::violation(
+        int("[3, 3]"),
+        vec![pattern(&[('a', 'z')], true), pattern(&[('a', 'c')], false)],
+        Some("daa")
+    )]
+    #[case::longer_for_violations(
+        int("[0, 5]"),
+        vec![pattern(&[('a', 'z')], false), pattern(&[('0', '9')], false)],
+        Some("Aa")
+    )]
+    #[case::too_short_for_violation(int("[0, 0]"), vec![pattern(&[('a', 'z')], false)], None)]
+    #[case::no_violating_character(
+        int("[2, 2]"),
+        vec![pattern(&[('0', '9')], true), pattern(&[('0', '9')], false)],
+        None
+    )]
+    fn test_test_value_for_string(
+        #[case] lengths: Interval,
+        #[case] patterns: Vec<PatternDTO>,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            test_value_for_string(&lengths, &patterns),
+            expected.map(str::to_owned)
+        );
     }
 }
@@ -1,8 +1,12 @@
+use gpt_common::diagnostic::Diagnostic;
 use yew::prelude::*;
 
 #[derive(PartialEq, Eq, Properties)]
 pub struct Props {
     pub error_text: String,
+    /// The problems in the input, shown instead of the error text if there are any
+    #[prop_or_default]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 #[function_component(ErrorDisplay)]
@@ -10,7 +14,19 @@ pub fn error_display(props: &Props) -> Html {
     html! {
         <div>
             <h2>{ "Error" }</h2>
-            <pre>{ &props.error_text }</pre>
+            if props.diagnostics.is_empty() {
+                <pre>{ &props.error_text }</pre>
+            } else {
+                <ul>
+                    { for props.diagnostics.iter().map(|diagnostic| html! {
+                        <li class={diagnostic.severity.to_string()}>
+                            <b>{ format!("{}[{}]", diagnostic.severity, diagnostic.code) }</b>
+                            { format!(" line {}, column {}: ", diagnostic.line, diagnostic.column) }
+                            { &diagnostic.message }
+                        </li>
+                    }) }
+                </ul>
+            }
         </div>
     }
 }
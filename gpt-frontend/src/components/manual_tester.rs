@@ -1,6 +1,7 @@
 use gpt_common::{
+    diagnostic::Diagnostic,
     dto::NTupleSingleInterval,
-    generate_tests_for_gpt_input,
+    generate_tests_for_gpt_input_recovering,
     graph_reduction::{create_graph, create_graph_url, monke::run_monke},
 };
 use yew::prelude::*;
@@ -36,17 +37,22 @@ pub fn manual_tester() -> Html {
         .to_owned()
     });
     let generated_state = use_state(|| Some(Ok(Vec::<NTupleSingleInterval>::new())));
+    // The syntax errors, the test cases of the other features are still shown
+    let diagnostics_state = use_state(Vec::<Diagnostic>::new);
 
     let toggle_button_onclick = {
         let is_loading = is_loading.clone();
         let input = input.clone();
         let generated_state = generated_state.clone();
+        let diagnostics_state = diagnostics_state.clone();
         Callback::from(move |_| {
             is_loading.set(true);
             generated_state.set(None);
 
-            match generate_tests_for_gpt_input(&input) {
-                Ok(test_cases) => {
+            match generate_tests_for_gpt_input_recovering(&input) {
+                Ok((test_cases, diagnostics)) => {
+                    diagnostics_state.set(diagnostics);
+
                     let graph = create_graph(&test_cases);
 
                     log::info!("Original count: {}", graph.node_count());
@@ -77,6 +83,7 @@ pub fn manual_tester() -> Html {
                 }
                 Err(err) => {
                     log::error!("Error: {}", err);
+                    diagnostics_state.set(Vec::new());
                     generated_state.set(Some(Err(err)));
                 }
             }
@@ -131,6 +138,12 @@ pub fn manual_tester() -> Html {
 
       <div class="rightOutput">
         if !*is_loading {
+        if !diagnostics_state.is_empty() {
+          <ErrorDisplay
+            error_text={"Syntax error"}
+            diagnostics={(*diagnostics_state).clone()}
+          />
+        }
         if let Some(Ok(state)) = &*generated_state {
             <TestCaseTable
               // variables={state.variables}
@@ -138,7 +151,7 @@ pub fn manual_tester() -> Html {
               test_cases={state.clone()}
             />
         } else if let Some(Err(err)) = &*generated_state {
-          <ErrorDisplay error_text={err.to_string()} />
+          <ErrorDisplay error_text={err.to_string()} diagnostics={err.diagnostics().to_vec()} />
         }}
       </div>
     </div>
@@ -20,9 +20,11 @@ fn sort_outputs_into_varible_columns(
 
 type TestCaseCell = Option<Output<Interval>>;
 
-fn create_test_case_table(
-    ntuples: &[NTupleSingleInterval],
-) -> (Vec<String>, Vec<Vec<TestCaseCell>>) {
+/// The cells of the variables, then the expected outcomes, the requirements, the template instances, the bindings
+/// and the values at named constants of a test case
+type TestCaseRow = (Vec<TestCaseCell>, [String; 5]);
+
+fn create_test_case_table(ntuples: &[NTupleSingleInterval]) -> (Vec<String>, Vec<TestCaseRow>) {
     let mut variables: Vec<String> =
         HashSet::<String>::from_iter(ntuples.iter().flat_map(|ntuple| ntuple.keys()).cloned())
             .into_iter()
@@ -33,7 +35,24 @@ fn create_test_case_table(
     let output = ntuples
         .iter()
         .cloned()
-        .map(|ntuple| sort_outputs_into_varible_columns(&variables, ntuple))
+        .map(|ntuple| {
+            let traceability = [
+                ntuple.expected.join(", "),
+                ntuple.requirements.join(", "),
+                ntuple.instances.join(", "),
+                ntuple.bindings.join(", "),
+                ntuple
+                    .constants
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ];
+            (
+                sort_outputs_into_varible_columns(&variables, ntuple),
+                traceability,
+            )
+        })
         .collect();
 
     (variables, output)
@@ -85,10 +104,15 @@ pub fn test_case_table(props: &Props) -> Html {
             {(*test_case_table_data.0).iter().map(|var_name|
               html! { <th>{var_name}</th> }
             ).collect::<Html>()}
+            <th>{"Expected"}</th>
+            <th>{"Requirements"}</th>
+            <th>{"Instances"}</th>
+            <th>{"Bindings"}</th>
+            <th>{"Constants"}</th>
           </tr>
         </thead>
         <tbody>
-        { (*test_case_table_data.1).iter().enumerate().map(|(index, outputs)|
+        { (*test_case_table_data.1).iter().enumerate().map(|(index, (outputs, traceability))|
             html!{
               <tr>
                 <td>{index + 1}</td>
@@ -100,6 +124,7 @@ pub fn test_case_table(props: &Props) -> Html {
                     }}
                   </td>
                 }).collect::<Html>()}
+                {traceability.iter().map(|cell| html! { <td>{cell}</td> }).collect::<Html>()}
               </tr>
             }).collect::<Html>()}
         </tbody>